@@ -199,6 +199,204 @@ mod msm {
     }
 }
 
+#[divan::bench_group(sample_count = 3, sample_size = 1)]
+mod setup {
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use divan::Bencher;
+    use poly_multiproof::method2::M2NoPrecomp;
+    use rand::thread_rng;
+
+    /// Generates the powers of tau in G1. Run with `--features parallel` to use the chunked
+    /// multithreaded generation.
+    #[divan::bench(args = [2usize.pow(18)])]
+    fn bench_gen_powers_of_g1(bencher: Bencher, size: usize) {
+        bencher
+            .with_inputs(|| {
+                (
+                    Fr::rand(&mut thread_rng()),
+                    G1Projective::rand(&mut thread_rng()),
+                    G2Projective::rand(&mut thread_rng()),
+                )
+            })
+            .bench_values(|(x, g1, g2)| M2NoPrecomp::<Bls12_381>::new_from_scalar(x, g1, g2, size));
+    }
+}
+
+#[divan::bench_group(sample_count = 5, sample_size = 1)]
+mod verify {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use divan::Bencher;
+    use merlin::Transcript;
+    use poly_multiproof::{
+        method2::M2NoPrecomp,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+    };
+    use rand::thread_rng;
+
+    /// Verifies an opening of a few polynomials at many points without precomputation, which is
+    /// dominated by interpolating the folded evals. Run with `--features parallel` to interpolate
+    /// in parallel.
+    #[divan::bench(args = [512])]
+    fn bench_m2_verify_many_points(bencher: Bencher, n_points: usize) {
+        let s = M2NoPrecomp::<Bls12_381>::new(1024, &mut thread_rng());
+        let points = (0..n_points)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(1023, &mut thread_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let proof = s
+            .open(&mut Transcript::new(b"bench"), &evals, &coeffs, &points)
+            .unwrap();
+        bencher.bench(|| {
+            s.verify(
+                &mut Transcript::new(b"bench"),
+                &commits,
+                &points,
+                &evals,
+                &proof,
+            )
+        });
+    }
+}
+
 fn main() {
     divan::main()
 }
+
+#[divan::bench_group(sample_count = 5, sample_size = 1)]
+mod commit {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use divan::Bencher;
+    use poly_multiproof::{method2::M2NoPrecomp, traits::Committer};
+    use rand::thread_rng;
+
+    fn inputs(n_polys: usize) -> (M2NoPrecomp<Bls12_381>, Vec<Vec<Fr>>) {
+        let s = M2NoPrecomp::<Bls12_381>::new(16, &mut thread_rng());
+        let polys = (0..n_polys)
+            .map(|_| DensePolynomial::<Fr>::rand(15, &mut thread_rng()).coeffs)
+            .collect::<Vec<_>>();
+        (s, polys)
+    }
+
+    /// Commits to many small polynomials at once, normalizing the commitments together
+    #[divan::bench(args = [1000])]
+    fn bench_commit_batch(bencher: Bencher, n_polys: usize) {
+        let (s, polys) = inputs(n_polys);
+        bencher.bench(|| s.commit_batch(&polys));
+    }
+
+    /// Commits to the same polynomials one at a time, normalizing each commitment on its own
+    #[divan::bench(args = [1000])]
+    fn bench_commit_each(bencher: Bencher, n_polys: usize) {
+        let (s, polys) = inputs(n_polys);
+        bencher.bench(|| polys.iter().map(|p| s.commit(p)).collect::<Vec<_>>());
+    }
+}
+
+#[divan::bench_group(sample_count = 5, sample_size = 1)]
+mod division {
+    use ark_bls12_381::Fr;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use divan::Bencher;
+    use poly_multiproof::poly_ops::{divide_poly, fft_divide_poly};
+    use rand::thread_rng;
+
+    /// Divides a polynomial of degree `degree` by one of half its degree
+    fn inputs(degree: usize) -> (DensePolynomial<Fr>, DensePolynomial<Fr>) {
+        (
+            DensePolynomial::rand(degree, &mut thread_rng()),
+            DensePolynomial::rand(degree / 2, &mut thread_rng()),
+        )
+    }
+
+    #[divan::bench(args = [256, 1024, 4096, 2usize.pow(14)])]
+    fn bench_schoolbook_division(bencher: Bencher, degree: usize) {
+        let (num, denom) = inputs(degree);
+        bencher.bench(|| divide_poly(&num, &denom));
+    }
+
+    #[divan::bench(args = [256, 1024, 4096, 2usize.pow(14)])]
+    fn bench_fft_division(bencher: Bencher, degree: usize) {
+        let (num, denom) = inputs(degree);
+        bencher.bench(|| fft_divide_poly(&num, &denom));
+    }
+}
+
+#[divan::bench_group(sample_count = 3, sample_size = 1)]
+mod extend {
+    use ark_bls12_381::{Bls12_381, G1Affine};
+    use ark_ff::UniformRand;
+    use divan::Bencher;
+    use poly_multiproof::Commitment;
+    use rand::thread_rng;
+
+    /// Half as many commitments as `output_size`, extended to `output_size`
+    fn inputs(output_size: usize) -> Vec<Commitment<Bls12_381>> {
+        (0..output_size / 2)
+            .map(|_| Commitment(G1Affine::rand(&mut thread_rng())))
+            .collect()
+    }
+
+    #[divan::bench(args = [8192])]
+    fn bench_extend_projective_fft(bencher: Bencher, output_size: usize) {
+        let commits = inputs(output_size);
+        bencher.bench(|| Commitment::extend_commitments(&commits, output_size));
+    }
+
+    #[divan::bench(args = [8192])]
+    fn bench_extend_affine_fft(bencher: Bencher, output_size: usize) {
+        let commits = inputs(output_size);
+        bencher.bench(|| Commitment::extend_commitments_affine_fft(&commits, output_size));
+    }
+}
+
+#[divan::bench_group(sample_count = 5, sample_size = 1)]
+mod evaluate {
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use divan::Bencher;
+    use poly_multiproof::poly_ops::evaluate_matrix_shared_points;
+    use rand::thread_rng;
+
+    /// Many polynomials of degree 255 and a few points
+    fn inputs(n_polys: usize) -> (Vec<DensePolynomial<Fr>>, Vec<Fr>) {
+        let polys = (0..n_polys)
+            .map(|_| DensePolynomial::rand(255, &mut thread_rng()))
+            .collect();
+        let points = (0..4).map(|_| Fr::rand(&mut thread_rng())).collect();
+        (polys, points)
+    }
+
+    #[divan::bench(args = [1000])]
+    fn bench_horner(bencher: Bencher, n_polys: usize) {
+        let (polys, points) = inputs(n_polys);
+        bencher.bench(|| {
+            polys
+                .iter()
+                .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        });
+    }
+
+    #[divan::bench(args = [1000])]
+    fn bench_shared_points(bencher: Bencher, n_polys: usize) {
+        let (polys, points) = inputs(n_polys);
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        bencher.bench(|| evaluate_matrix_shared_points(&coeffs, &points));
+    }
+}
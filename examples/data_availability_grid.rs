@@ -54,7 +54,7 @@ impl Grid {
         let pt_size = Fr::zero().serialized_size(Compress::Yes) - 1;
         let points: Vec<_> = data
             .chunks(pt_size)
-            .map(|chunk| Fr::from_be_bytes_mod_order(chunk))
+            .map(Fr::from_be_bytes_mod_order)
             .collect();
 
         let mut rows: Vec<_> = points
@@ -87,8 +87,8 @@ impl Grid {
 
         for j in 0..GRID_WIDTH {
             let mut col = Vec::with_capacity(rows.len());
-            for i in 0..rows.len() {
-                col.push(rows[i][j]);
+            for row in rows.iter() {
+                col.push(row[j]);
             }
             domain_h.ifft_in_place(&mut col);
             domain_2h.fft_in_place(&mut col);
@@ -103,7 +103,7 @@ impl Grid {
 
         let poly_t = start_timer!(|| "computing polynomials from evals");
         let polys: Vec<_> = cfg_iter!(interp_rows)
-            .map(|(_, row)| domain_w.ifft(&row))
+            .map(|(_, row)| domain_w.ifft(row))
             .collect();
         end_timer!(poly_t);
 
@@ -202,7 +202,7 @@ fn main() {
                 evals,
                 proof,
             )
-            .expect(format!("Verify errored at {:>3}, {:>3}", i, j).as_str());
+            .unwrap_or_else(|_| panic!("Verify errored at {:>3}, {:>3}", i, j));
         if !res {
             println!("Verify failed at {:>3}, {:>3}", i, j);
         }
@@ -0,0 +1,278 @@
+//! # Batch verification
+//! Verifying a proof with precomputation comes down to checking a single [`PairingEquation`]. A
+//! [`BatchVerifier`] accumulates the equations of many proofs, each weighted by a fresh random
+//! scalar, and checks them all at once with one multi-pairing. Equations sharing a G2 point share
+//! a pairing, so a batch over a few point sets costs only a few pairings.
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::Zero;
+use ark_std::{
+    rand::{CryptoRng, RngCore},
+    vec::Vec,
+    UniformRand,
+};
+use merlin::Transcript;
+
+use crate::{
+    traits::{MSMEngine, PairingEquationProof},
+    Commitment, Error,
+};
+
+/// The pairing equation $e(a_1, a_2) = e(b_1, b_2)$
+#[derive(Debug, Clone)]
+pub struct PairingEquation<E: Pairing> {
+    /// The inputs $(a_1, a_2)$ of the left hand side pairing
+    pub lhs: (E::G1Affine, E::G2Affine),
+    /// The inputs $(b_1, b_2)$ of the right hand side pairing
+    pub rhs: (E::G1Affine, E::G2Affine),
+}
+
+impl<E: Pairing> PairingEquation<E> {
+    /// An equation that holds exactly when `holds` is true, for statements that don't need a
+    /// pairing, such as an opening at no points
+    pub(crate) fn vacuous(holds: bool, g1: E::G1Affine, g2: E::G2Affine) -> Self {
+        let lhs = if holds { E::G1Affine::zero() } else { g1 };
+        Self {
+            lhs: (lhs, g2),
+            rhs: (E::G1Affine::zero(), g2),
+        }
+    }
+
+    /// Checks the equation with the given msm engine's pairing check
+    pub(crate) fn check<M: MSMEngine<E = E>>(&self) -> bool {
+        M::pairing_eq_check(self.lhs.0, self.lhs.1, self.rhs.0, self.rhs.1)
+    }
+
+    /// Checks the equation as a single multi-pairing
+    pub(crate) fn check_multi_pairing(&self) -> bool {
+        E::final_exponentiation(MillerLoopOutput(self.miller_output()))
+            .is_some_and(|out| out.is_zero())
+    }
+
+    /// The product of the Miller loops of $e(a_1, a_2)$ and $e(-b_1, b_2)$, before the final
+    /// exponentiation. After the final exponentiation this is one exactly when the equation
+    /// holds, so it can be multiplied with other Miller loop outputs and checked with a single
+    /// final exponentiation.
+    pub fn miller_output(&self) -> E::TargetField {
+        crate::op_count::count_pairings(2);
+        E::multi_miller_loop(
+            [self.lhs.0.into_group(), -self.rhs.0.into_group()],
+            [self.lhs.1, self.rhs.1],
+        )
+        .0
+    }
+}
+
+/// Accumulates proofs made with a scheme with precomputation and checks them all at once
+pub struct BatchVerifier<'a, E: Pairing, P, R> {
+    scheme: &'a P,
+    rng: R,
+    /// The weighted G1 inputs summed by the G2 point they are paired with
+    terms: Vec<(E::G2Affine, E::G1)>,
+}
+
+impl<'a, E, P, R> BatchVerifier<'a, E, P, R>
+where
+    E: Pairing,
+    P: PairingEquationProof<E>,
+    R: RngCore + CryptoRng,
+{
+    /// Make a new empty batch for proofs made with `scheme`. The weights are drawn from `rng`.
+    pub fn new(scheme: &'a P, rng: R) -> Self {
+        Self {
+            scheme,
+            rng,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Does everything `verify` does except the final pairing check, which is deferred to
+    /// [`Self::finalize`]. Errors are the same as `verify`'s.
+    pub fn add(
+        &mut self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &P::Proof,
+    ) -> Result<(), Error> {
+        let eq = self
+            .scheme
+            .verify_equation(transcript, commits, point_set_index, evals, proof)?;
+        let weight = E::ScalarField::rand(&mut self.rng);
+        self.push(eq.lhs.1, eq.lhs.0 * weight);
+        self.push(eq.rhs.1, -(eq.rhs.0 * weight));
+        Ok(())
+    }
+
+    fn push(&mut self, g2: E::G2Affine, g1: E::G1) {
+        match self.terms.iter_mut().find(|(q, _)| *q == g2) {
+            Some((_, acc)) => *acc += g1,
+            None => self.terms.push((g2, g1)),
+        }
+    }
+
+    /// Checks every proof added to the batch. Returns `true` only if all of them verify, up to a
+    /// negligible chance over the random weights. An empty batch verifies.
+    pub fn finalize(self) -> Result<bool, Error> {
+        let (g2s, g1s): (Vec<_>, Vec<_>) = self.terms.into_iter().unzip();
+        crate::op_count::count_pairings(g1s.len());
+        Ok(E::multi_pairing(E::G1::normalize_batch(&g1s), g2s).is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchVerifier;
+    use crate::{
+        m1_cycl::M1CyclPrecomp,
+        method1::{precompute::M1Precomp, M1NoPrecomp},
+        method2::{precompute::M2Precomp, M2NoPrecomp},
+        msm::ArkMSMEngine,
+        poly_ops::ev_points,
+        test_rng,
+        testing::miller_output_is_one,
+        traits::{Committer, PairingEquationProof, PolyMultiProof},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::pairing::Pairing;
+    use ark_ff::One;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    /// Opens 10 blocks of 3 polynomials, alternating between two point sets, then checks a batch
+    /// of all of them passes, and fails if one block's evals are wrong
+    fn test_batch<P>(s: &P, point_sets: &[Vec<Fr>])
+    where
+        P: PairingEquationProof<Bls12_381> + Committer<Bls12_381>,
+    {
+        let blocks = (0..10)
+            .map(|block| {
+                let idx = block % point_sets.len();
+                let polys = (0..3)
+                    .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+                    .collect::<Vec<_>>();
+                let evals = polys
+                    .iter()
+                    .map(|p| point_sets[idx].iter().map(|x| p.evaluate(x)).collect())
+                    .collect::<Vec<Vec<_>>>();
+                let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+                let commits = coeffs
+                    .iter()
+                    .map(|p| s.commit(p).unwrap())
+                    .collect::<Vec<_>>();
+                let proof = s
+                    .open(&mut Transcript::new(b"block"), &evals, &coeffs, idx)
+                    .unwrap();
+                (idx, commits, evals, proof)
+            })
+            .collect::<Vec<_>>();
+
+        let run = |bad_block: Option<usize>| {
+            let mut batch = BatchVerifier::new(s, test_rng());
+            for (block, (idx, commits, evals, proof)) in blocks.iter().enumerate() {
+                let mut evals = evals.clone();
+                if bad_block == Some(block) {
+                    evals[1][0] += Fr::one();
+                }
+                batch
+                    .add(&mut Transcript::new(b"block"), commits, *idx, &evals, proof)
+                    .unwrap();
+            }
+            batch.finalize()
+        };
+        assert_eq!(Ok(true), run(None));
+        assert_eq!(Ok(false), run(Some(6)));
+    }
+
+    fn random_point_sets(n_sets: usize, n_points: usize) -> Vec<Vec<Fr>> {
+        (0..n_sets)
+            .map(|_| (0..n_points).map(|_| Fr::rand(&mut test_rng())).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_verifier_method1() {
+        let point_sets = random_point_sets(2, 8);
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).unwrap();
+        test_batch(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_batch_verifier_method2() {
+        let point_sets = random_point_sets(2, 8);
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let s = M2Precomp::from_inner(s, point_sets.clone()).unwrap();
+        test_batch(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_batch_verifier_cyclic() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 16, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 32, 4).unwrap();
+        let point_sets = (0..4)
+            .map(|i| ev_points(&s.point_sets().subgroup(i).unwrap()))
+            .collect::<Vec<_>>();
+        test_batch(&s, &point_sets);
+    }
+
+    #[test]
+    fn test_miller_outputs_compose() {
+        let point_sets = random_point_sets(2, 8);
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let s = M2Precomp::from_inner(s, point_sets.clone()).unwrap();
+        let openings = (0..2)
+            .map(|idx| {
+                let polys = (0..3)
+                    .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+                    .collect::<Vec<_>>();
+                let evals = polys
+                    .iter()
+                    .map(|p| point_sets[idx].iter().map(|x| p.evaluate(x)).collect())
+                    .collect::<Vec<Vec<_>>>();
+                let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+                let commits = coeffs
+                    .iter()
+                    .map(|p| s.commit(p).unwrap())
+                    .collect::<Vec<_>>();
+                let proof = s
+                    .open(&mut Transcript::new(b"block"), &evals, &coeffs, idx)
+                    .unwrap();
+                (idx, commits, evals, proof)
+            })
+            .collect::<Vec<_>>();
+        let miller = |bad: bool| {
+            openings
+                .iter()
+                .map(|(idx, commits, evals, proof)| {
+                    let mut evals = evals.clone();
+                    if bad && *idx == 1 {
+                        evals[0][0] += Fr::one();
+                    }
+                    s.verify_miller_output(
+                        &mut Transcript::new(b"block"),
+                        commits,
+                        *idx,
+                        &evals,
+                        proof,
+                    )
+                    .unwrap()
+                })
+                .product::<<Bls12_381 as Pairing>::TargetField>()
+        };
+        // One final exponentiation checks both proofs
+        assert!(miller_output_is_one::<Bls12_381>(miller(false)));
+        assert!(!miller_output_is_one::<Bls12_381>(miller(true)));
+    }
+
+    #[test]
+    fn test_empty_batch_verifies() {
+        let s = M2NoPrecomp::<Bls12_381>::new(8, &mut test_rng());
+        let s = M2Precomp::from_inner(s, vec![vec![Fr::one()]]).unwrap();
+        assert_eq!(Ok(true), BatchVerifier::new(&s, test_rng()).finalize());
+    }
+}
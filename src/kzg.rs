@@ -1,18 +1,22 @@
 //! KZG10 proof system for each instance of the multiproof scheme
 use core::ops::Mul;
 
-use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
 
 use crate::{
+    batch::PairingEquation,
     m1_cycl::M1CyclPrecomp,
     method1::{precompute::M1Precomp, M1NoPrecomp},
+    method2::M2NoPrecomp,
     poly_div_q_r,
-    traits::{Committer, KZGProof, MSMEngine},
-    Error,
+    traits::{Ceremony, Committer, KZGProof, MSMEngine},
+    transcribe_generic, Commitment, Error,
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::vec;
 use ark_std::vec::Vec;
+use merlin::Transcript;
 
 impl<E: Pairing, A: Committer<E> + WithSrs<E>> KZGProof<E> for A {
     type Proof = crate::method1::Proof<E>;
@@ -58,6 +62,222 @@ impl<E: Pairing, A: Committer<E> + WithSrs<E>> KZGProof<E> for A {
     }
 }
 
+/// The setup elements needed to check a single point KZG opening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgVerifierKey<E: Pairing> {
+    /// The G1 generator
+    pub g1: E::G1Affine,
+    /// The G2 generator
+    pub g2: E::G2Affine,
+    /// The G2 generator multiplied by tau
+    pub g2_tau: E::G2Affine,
+}
+
+impl<E: Pairing> KzgVerifierKey<E> {
+    /// The G2 points the pairing checks use, $[1]_2$ then $[\tau]_2$. These are all a method 2
+    /// verifier needs in G2, e.g. to hardcode in an external verifier.
+    pub fn g2_elements(&self) -> Vec<E::G2Affine> {
+        vec![self.g2, self.g2_tau]
+    }
+}
+
+/// The G1 generator of a setup, or an error if it has no powers of tau in G1
+fn g1_generator<E: Pairing>(powers_of_g1: &[E::G1Affine]) -> Result<E::G1Affine, Error> {
+    powers_of_g1
+        .first()
+        .copied()
+        .ok_or(Error::SetupTooSmall { needed: 1, got: 0 })
+}
+
+/// Errors with [`Error::SetupTooSmall`] if the setup is too small to have $[\tau]_2$, see
+/// [`Ceremony::g2_tau`]
+impl<E: Pairing, M: MSMEngine<E = E>> TryFrom<&M1NoPrecomp<E, M>> for KzgVerifierKey<E> {
+    type Error = Error;
+
+    fn try_from(s: &M1NoPrecomp<E, M>) -> Result<Self, Error> {
+        // Having [tau]_2 means there's also [1]_2
+        let g2_tau = s.g2_tau()?;
+        Ok(Self {
+            g1: g1_generator::<E>(&s.powers_of_g1)?,
+            g2: s.powers_of_g2[0],
+            g2_tau,
+        })
+    }
+}
+
+/// Errors with [`Error::SetupTooSmall`] if the setup has no powers of tau in G1
+impl<E: Pairing> TryFrom<&M2NoPrecomp<E>> for KzgVerifierKey<E> {
+    type Error = Error;
+
+    fn try_from(s: &M2NoPrecomp<E>) -> Result<Self, Error> {
+        Ok(Self {
+            g1: g1_generator::<E>(&s.powers_of_g1)?,
+            g2: s.g2,
+            g2_tau: s.g2x,
+        })
+    }
+}
+
+/// Checks the KZG equation $e(C - [y]_1, [1]_2) = e(W, [\tau - z]_2)$, i.e. that `witness`
+/// proves the polynomial committed to by `commit` is `y` at `z`
+pub fn kzg_check<E: Pairing>(
+    vk: &KzgVerifierKey<E>,
+    commit: &Commitment<E>,
+    z: E::ScalarField,
+    y: E::ScalarField,
+    witness: &E::G1Affine,
+) -> Result<bool, Error> {
+    let lhs = commit.0.into_group() - vk.g1.mul(y);
+    let tau_minus_z = vk.g2_tau.into_group() - vk.g2.mul(z);
+    Ok(PairingEquation::<E> {
+        lhs: (lhs.into_affine(), vk.g2),
+        rhs: (*witness, tau_minus_z.into_affine()),
+    }
+    .check_multi_pairing())
+}
+
+/// A proof that a committed polynomial takes some value at a single point, which is just the
+/// commitment to the witness polynomial. See [`Committer::open_single`] and [`verify_single`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SinglePointOpen<E: Pairing>(pub E::G1Affine);
+
+/// Verifies a proof made with [`Committer::open_single`] that the polynomial committed to by
+/// `commit` is `y` at `z`
+pub fn verify_single<E: Pairing>(
+    vk: &KzgVerifierKey<E>,
+    commit: &Commitment<E>,
+    z: E::ScalarField,
+    y: E::ScalarField,
+    open: &SinglePointOpen<E>,
+) -> Result<bool, Error> {
+    kzg_check(vk, commit, z, y, &open.0)
+}
+
+/// Finds which of `candidates` is the value at `z` of the polynomial committed to by `commit`,
+/// given a proof made with [`Committer::open_single`]. The value itself can't be recovered from
+/// the commitment and witness, since the pairing equation only gives it as $e(g_1, g_2)^y$ and
+/// taking its discrete log is infeasible, so a cache that dropped `y` has to guess it. This
+/// computes $e(g_1, g_2)^y$ once, then costs one target group exponentiation per candidate
+/// instead of the two pairings of [`verify_single`]. Returns `None` if no candidate matches.
+pub fn recover_value<E: Pairing>(
+    vk: &KzgVerifierKey<E>,
+    commit: &Commitment<E>,
+    z: E::ScalarField,
+    open: &SinglePointOpen<E>,
+    candidates: impl IntoIterator<Item = E::ScalarField>,
+) -> Option<E::ScalarField> {
+    let tau_minus_z = vk.g2_tau.into_group() - vk.g2.mul(z);
+    // e(C, g2) - e(W, [tau - z]_2) = y * e(g1, g2)
+    crate::op_count::count_pairings(2);
+    let target = E::multi_pairing(
+        [commit.0, (-open.0.into_group()).into_affine()],
+        [vk.g2, tau_minus_z.into_affine()],
+    );
+    crate::op_count::count_pairings(1);
+    let base = E::pairing(vk.g1, vk.g2);
+    candidates.into_iter().find(|y| base * y == target)
+}
+
+/// A proof that a committed polynomial is identically zero, see [`Committer::prove_zero`] and
+/// [`verify_zero`]. The commitment alone shows this, so the proof carries no data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZeroProof;
+
+/// Verifies a proof made with [`Committer::prove_zero`] that `commit` is a commitment to the
+/// zero polynomial, i.e. that it's the identity. `commit` is absorbed into the transcript, which
+/// is then in the same state as the prover's if the proof verifies.
+pub fn verify_zero<E: Pairing>(
+    transcript: &mut Transcript,
+    commit: &Commitment<E>,
+    _proof: &ZeroProof,
+) -> Result<bool, Error> {
+    transcribe_generic(transcript, b"zero commit", &commit.0)?;
+    Ok(commit.0.is_zero())
+}
+
+/// A Schnorr-style proof of knowledge of the coefficients behind a commitment, see
+/// [`Committer::prove_commit_knowledge`] and [`Committer::verify_commit_knowledge`]
+#[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PoK<E: Pairing> {
+    /// The commitment to the random mask polynomial
+    pub mask_commit: E::G1Affine,
+    /// The mask plus the challenge times the committed coefficients
+    pub response: Vec<E::ScalarField>,
+}
+
+/// The size of an [`Eip4844Opening`] in bytes
+pub const EIP4844_OPENING_SIZE: usize = 32 + 32 + 48 + 48;
+
+/// A single point opening over BLS12-381 laid out for the EIP-4844 point evaluation precompile.
+/// The precompile input is `versioned_hash || z || y || commitment || proof`, and
+/// [`Eip4844Opening::to_eip4844`] gives everything after the versioned hash. The caller prepends
+/// the hash, `0x01 || sha256(commitment)[1..]`, since this crate doesn't depend on sha256.
+#[cfg(feature = "ark-bls12-381")]
+#[derive(Debug, Clone)]
+pub struct Eip4844Opening {
+    /// The commitment to the polynomial
+    pub commitment: crate::Commitment<ark_bls12_381::Bls12_381>,
+    /// The point the polynomial is opened at
+    pub z: ark_bls12_381::Fr,
+    /// The value of the polynomial at `z`
+    pub y: ark_bls12_381::Fr,
+    /// The proof, as made by [`KZGProof::open`]
+    pub proof: crate::method1::Proof<ark_bls12_381::Bls12_381>,
+}
+
+#[cfg(feature = "ark-bls12-381")]
+impl Eip4844Opening {
+    /// Encodes the opening as `z || y || commitment || proof`. Scalars are 32 bytes big-endian
+    /// and points are 48 byte compressed in the zcash format, as the precompile expects.
+    pub fn to_eip4844(&self) -> [u8; EIP4844_OPENING_SIZE] {
+        let mut out = [0u8; EIP4844_OPENING_SIZE];
+        let (z, rest) = out.split_at_mut(32);
+        let (y, rest) = rest.split_at_mut(32);
+        let (commitment, proof) = rest.split_at_mut(48);
+        write_scalar_be(&self.z, z);
+        write_scalar_be(&self.y, y);
+        self.commitment
+            .0
+            .serialize_compressed(commitment)
+            .expect("48 bytes fit a compressed G1 point");
+        self.proof
+            .0
+            .serialize_compressed(proof)
+            .expect("48 bytes fit a compressed G1 point");
+        out
+    }
+
+    /// Decodes an opening encoded with [`Self::to_eip4844`]. Errors if a scalar isn't canonical
+    /// or a point isn't a valid compressed point in the prime order subgroup.
+    pub fn from_eip4844(bytes: &[u8; EIP4844_OPENING_SIZE]) -> Result<Self, Error> {
+        let (z, rest) = bytes.split_at(32);
+        let (y, rest) = rest.split_at(32);
+        let (commitment, proof) = rest.split_at(48);
+        Ok(Self {
+            commitment: crate::Commitment(ark_bls12_381::G1Affine::deserialize_compressed(
+                commitment,
+            )?),
+            z: read_scalar_be(z)?,
+            y: read_scalar_be(y)?,
+            proof: crate::method1::Proof(ark_bls12_381::G1Affine::deserialize_compressed(proof)?),
+        })
+    }
+}
+
+#[cfg(feature = "ark-bls12-381")]
+fn write_scalar_be(x: &ark_bls12_381::Fr, out: &mut [u8]) {
+    x.serialize_compressed(&mut *out)
+        .expect("32 bytes fit a scalar");
+    out.reverse();
+}
+
+#[cfg(feature = "ark-bls12-381")]
+fn read_scalar_be(bytes: &[u8]) -> Result<ark_bls12_381::Fr, Error> {
+    let mut le = bytes.to_vec();
+    le.reverse();
+    Ok(ark_bls12_381::Fr::deserialize_compressed(le.as_slice())?)
+}
+
 // Impls
 trait WithSrs<E: Pairing> {
     fn g1s(&self) -> &[E::G1Affine];
@@ -93,3 +313,360 @@ impl<E: Pairing, M: MSMEngine<E = E>> WithSrs<E> for M1CyclPrecomp<E, M> {
         &self.inner.powers_of_g2
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::ArkMSMEngine,
+        test_rng,
+        testing::test_kzg,
+        traits::{Committer, KZGProof},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Zero;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::vec;
+
+    #[test]
+    fn test_kzg_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        test_kzg(&s);
+    }
+
+    #[test]
+    #[cfg(feature = "ark-bls12-381")]
+    fn test_eip4844_layout_round_trips() {
+        use super::{Eip4844Opening, EIP4844_OPENING_SIZE};
+        use crate::{method1::Proof, Commitment, Error};
+        use ark_bls12_381::G1Affine;
+        use ark_ec::AffineRepr;
+        use ark_ff::{BigInteger, One, PrimeField};
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        let coeffs = DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs;
+        let z = Fr::from(1234u64);
+        let opening = Eip4844Opening {
+            commitment: s.commit(&coeffs).unwrap(),
+            z,
+            y: DensePolynomial::from_coefficients_slice(&coeffs).evaluate(&z),
+            proof: s
+                .open(s.compute_witness_polynomial(coeffs, z).unwrap())
+                .unwrap(),
+        };
+        let same = |a: &Eip4844Opening, b: &Eip4844Opening| {
+            a.commitment == b.commitment && a.z == b.z && a.y == b.y && a.proof.0 == b.proof.0
+        };
+        let bytes = opening.to_eip4844();
+        assert!(same(
+            &opening,
+            &Eip4844Opening::from_eip4844(&bytes).unwrap()
+        ));
+        // Scalars are big-endian
+        assert_eq!(&[0u8; 30], &bytes[..30]);
+        assert_eq!(&1234u16.to_be_bytes(), &bytes[30..32]);
+        assert_eq!(
+            opening.y.into_bigint().to_bytes_be().as_slice(),
+            &bytes[32..64]
+        );
+
+        // Points are in the zcash compressed format
+        let known = Eip4844Opening {
+            commitment: Commitment(G1Affine::generator()),
+            z: Fr::one(),
+            y: Fr::zero(),
+            proof: Proof(G1Affine::zero()),
+        };
+        let bytes = known.to_eip4844();
+        let generator = [
+            0x97, 0xf1, 0xd3, 0xa7, 0x31, 0x97, 0xd7, 0x94, 0x26, 0x95, 0x63, 0x8c, 0x4f, 0xa9,
+            0xac, 0x0f, 0xc3, 0x68, 0x8c, 0x4f, 0x97, 0x74, 0xb9, 0x05, 0xa1, 0x4e, 0x3a, 0x3f,
+            0x17, 0x1b, 0xac, 0x58, 0x6c, 0x55, 0xe8, 0x3f, 0xf9, 0x7a, 0x1a, 0xef, 0xfb, 0x3a,
+            0xf0, 0x0a, 0xdb, 0x22, 0xc6, 0xbb,
+        ];
+        assert_eq!(&generator, &bytes[64..112]);
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert_eq!(&infinity, &bytes[112..]);
+        assert!(same(&known, &Eip4844Opening::from_eip4844(&bytes).unwrap()));
+
+        // The scalar field modulus isn't a canonical scalar
+        let mut bad = [0u8; EIP4844_OPENING_SIZE];
+        bad.copy_from_slice(&bytes);
+        bad[..32].copy_from_slice(&Fr::MODULUS.to_bytes_be());
+        assert_eq!(
+            Err(Error::SerializationError),
+            Eip4844Opening::from_eip4844(&bad).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_kzg_check() {
+        use super::{kzg_check, KzgVerifierKey};
+        use crate::{method2::M2NoPrecomp, poly_ops::divide_poly, Error};
+        use ark_ff::One;
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let z = Fr::from(77u64);
+        let y = poly.evaluate(&z);
+        let commit = s.commit(&poly.coeffs).unwrap();
+        let proof = s
+            .open(
+                s.compute_witness_polynomial(poly.coeffs.clone(), z)
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(Ok(true), kzg_check(&vk, &commit, z, y, &proof.0));
+        assert_eq!(
+            Ok(false),
+            kzg_check(&vk, &commit, z, y + Fr::one(), &proof.0)
+        );
+        assert_eq!(
+            Ok(false),
+            kzg_check(&vk, &commit, z + Fr::one(), y, &proof.0)
+        );
+        let other = s.open(vec![Fr::one(); 10]).unwrap();
+        assert_eq!(Ok(false), kzg_check(&vk, &commit, z, y, &other.0));
+
+        // Method 2 setups have no KZGProof impl, but their witnesses check the same way
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let divisor = DensePolynomial::from_coefficients_vec(vec![-z, Fr::one()]);
+        let (q, _) = divide_poly(&poly, &divisor).unwrap();
+        let witness = s.commit(&q.coeffs).unwrap().0;
+        let commit = s.commit(&poly.coeffs).unwrap();
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        assert_eq!(Ok(true), kzg_check(&vk, &commit, z, y, &witness));
+
+        // Setups too small to check an opening don't give a key
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(16, 0, &mut test_rng());
+        assert_eq!(
+            Err(Error::SetupTooSmall { needed: 2, got: 1 }),
+            KzgVerifierKey::try_from(&s)
+        );
+        let s = M2NoPrecomp::<Bls12_381>::new(0, &mut test_rng());
+        assert_eq!(
+            Err(Error::SetupTooSmall { needed: 1, got: 0 }),
+            KzgVerifierKey::try_from(&s)
+        );
+    }
+
+    #[test]
+    fn test_prove_zero() {
+        use super::{verify_zero, ZeroProof};
+        use crate::{method2::M2NoPrecomp, Error};
+        use merlin::Transcript;
+
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let zeros = vec![Fr::zero(); 20];
+        let mut prover = Transcript::new(b"testing");
+        let proof = s.prove_zero(&mut prover, &zeros).unwrap();
+        let mut verifier = Transcript::new(b"testing");
+        assert_eq!(
+            Ok(true),
+            verify_zero(&mut verifier, &s.commit(&zeros).unwrap(), &proof)
+        );
+        // Both transcripts absorbed the commitment
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        prover.challenge_bytes(b"next", &mut a);
+        verifier.challenge_bytes(b"next", &mut b);
+        assert_eq!(a, b);
+        let mut fresh = Transcript::new(b"testing");
+        fresh.challenge_bytes(b"next", &mut b);
+        assert_ne!(a, b);
+        assert_eq!(
+            Ok(ZeroProof),
+            s.prove_zero(&mut Transcript::new(b"testing"), &[])
+        );
+
+        let mut poly = DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs;
+        assert_eq!(
+            Ok(false),
+            verify_zero(
+                &mut Transcript::new(b"testing"),
+                &s.commit(&poly).unwrap(),
+                &proof
+            )
+        );
+        poly[..7].iter_mut().for_each(|c| *c = Fr::zero());
+        assert_eq!(
+            Err(Error::PolynomialNotZero { degree: 7 }),
+            s.prove_zero(&mut Transcript::new(b"testing"), &poly)
+        );
+    }
+
+    #[test]
+    fn test_commit_knowledge() {
+        use crate::method2::M2NoPrecomp;
+        use merlin::Transcript;
+
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let coeffs = DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs;
+        let commit = s.commit(&coeffs).unwrap();
+        let pok = s
+            .prove_commit_knowledge(&mut Transcript::new(b"testing"), &coeffs, &mut test_rng())
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_commit_knowledge(&mut Transcript::new(b"testing"), &commit, &pok)
+        );
+
+        let other = s
+            .commit(DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs)
+            .unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_commit_knowledge(&mut Transcript::new(b"testing"), &other, &pok)
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_commit_knowledge(&mut Transcript::new(b"other"), &commit, &pok)
+        );
+    }
+
+    #[test]
+    fn test_open_derivative() {
+        use super::{verify_single, KzgVerifierKey};
+        use crate::poly_ops::derivative;
+        use ark_ff::One;
+
+        // 3 + 2x + 5x^3 has derivative 2 + 15x^2
+        let p = [3u64, 2, 0, 5].map(Fr::from);
+        assert_eq!(
+            vec![Fr::from(2u64), Fr::zero(), Fr::from(15u64)],
+            derivative(&p)
+        );
+        assert!(derivative::<Fr>(&[]).is_empty());
+        assert!(derivative(&[Fr::one()]).is_empty());
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let z = Fr::from(9u64);
+        let d_commit = s.commit_derivative(&poly.coeffs).unwrap();
+        let (y, open) = s.open_derivative(&poly.coeffs, z).unwrap();
+        // The product rule gives (x p)'(z) = p(z) + z p'(z)
+        let mut xp = vec![Fr::zero()];
+        xp.extend_from_slice(&poly.coeffs);
+        assert_eq!(
+            poly.evaluate(&z) + z * y,
+            s.open_derivative(&xp, z).unwrap().0
+        );
+        assert_eq!(Ok(true), verify_single(&vk, &d_commit, z, y, &open));
+        assert_eq!(
+            Ok(false),
+            verify_single(&vk, &d_commit, z, y + Fr::one(), &open)
+        );
+        // It's a proof about p', not p
+        let commit = s.commit(&poly.coeffs).unwrap();
+        assert_eq!(Ok(false), verify_single(&vk, &commit, z, y, &open));
+    }
+
+    #[test]
+    fn test_recover_value() {
+        use super::{recover_value, KzgVerifierKey};
+        use ark_ff::One;
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let z = Fr::from(9u64);
+        let commit = s.commit(&poly.coeffs).unwrap();
+        let (y, open) = s.open_single(&poly.coeffs, z).unwrap();
+        let candidates = (0..5u64).map(|i| y + Fr::from(i)).rev().collect::<Vec<_>>();
+        assert_eq!(
+            Some(y),
+            recover_value(&vk, &commit, z, &open, candidates.iter().copied())
+        );
+        assert_eq!(
+            None,
+            recover_value(&vk, &commit, z, &open, candidates[..4].iter().copied())
+        );
+        assert_eq!(
+            None,
+            recover_value(&vk, &commit, z + Fr::one(), &open, candidates)
+        );
+    }
+
+    #[test]
+    fn test_open_single() {
+        use super::{verify_single, KzgVerifierKey};
+        use crate::{method2::M2NoPrecomp, traits::PolyMultiProofNoPrecomp};
+        use ark_ff::One;
+        use ark_serialize::CanonicalSerialize;
+        use merlin::Transcript;
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let z = Fr::from(9u64);
+        let commit = s.commit(&poly.coeffs).unwrap();
+        let (y, open) = s.open_single(&poly.coeffs, z).unwrap();
+        assert_eq!(poly.evaluate(&z), y);
+        assert_eq!(Ok(true), verify_single(&vk, &commit, z, y, &open));
+        assert_eq!(
+            Ok(false),
+            verify_single(&vk, &commit, z, y + Fr::one(), &open)
+        );
+        assert_eq!(
+            Ok(false),
+            verify_single(&vk, &commit, z + Fr::one(), y, &open)
+        );
+
+        // With one polynomial at one point the multipoint witness is the same element, since the
+        // first gamma power is one
+        let general = PolyMultiProofNoPrecomp::open(
+            &s,
+            &mut Transcript::new(b"testing"),
+            &[[y]],
+            &[&poly.coeffs],
+            &[z],
+        )
+        .unwrap();
+        assert_eq!(general.0, open.0);
+        assert_eq!(48, open.compressed_size());
+
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let (y, open) = s.open_single(&poly.coeffs, z).unwrap();
+        let commit = s.commit(&poly.coeffs).unwrap();
+        assert_eq!(
+            Ok(true),
+            verify_single(&KzgVerifierKey::try_from(&s).unwrap(), &commit, z, y, &open)
+        );
+        let general = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &[[y]],
+                &[&poly.coeffs],
+                &[z],
+            )
+            .unwrap();
+        assert!(open.compressed_size() < general.compressed_size());
+    }
+
+    #[test]
+    fn test_open_at_zero() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 2, &mut test_rng());
+        for coeffs in [
+            DensePolynomial::<Fr>::rand(40, &mut test_rng()).coeffs,
+            vec![Fr::from(5u64)],
+            vec![],
+        ] {
+            let commit = s.commit(&coeffs).unwrap();
+            let (value, proof) = s.open_at_zero(&coeffs).unwrap();
+            let witness = s
+                .compute_witness_polynomial(coeffs.clone(), Fr::zero())
+                .unwrap();
+            assert_eq!(s.open(witness).unwrap().0, proof.0);
+            assert_eq!(
+                DensePolynomial::from_coefficients_vec(coeffs).evaluate(&Fr::zero()),
+                value
+            );
+            assert_eq!(
+                Ok(true),
+                s.verify::<ArkMSMEngine<Bls12_381>>(&commit, Fr::zero(), value, &proof)
+            );
+        }
+    }
+}
@@ -13,7 +13,20 @@
 //! * `blst` enables a specific `bls12-381` implementation which uses `blst` for curve msm.
 //! * `parallel` enables parallel computation for
 //!   * PMP setup generation
+//!   * batch commitments with `Committer::commit_batch`
 //!   * operations in the `data_availability_grid` example
+//!
+//!   This work runs on rayon's global pool, or on a given pool with the `*_in_pool` variants
+//!   such as `Committer::commit_batch_in_pool`.
+//! * `transcript-debug` records the messages absorbed into transcripts, see `transcript::record`
+//! * `debug-checks` makes `open` check the given evals against the polynomials, returning
+//!   `Error::InconsistentEvals` instead of a proof that won't verify. It also makes
+//!   `verify_with_powers` check the supplied challenge powers.
+//! * `testing` exposes helpers in `testing` for generating valid openings in downstream tests
+//! * `insecure-deterministic-challenges` adds `transcript::with_counter_challenges`, which
+//!   replaces every challenge with a counter so failing tests are reproducible. This breaks
+//!   soundness entirely, so it must never be enabled in a build that proves or verifies for real.
+//! * `op-counting` counts the MSMs, pairings and field inversions done, see `op_count`
 //! * `print-trace` enables some tracing that shows the time certain things take to execute
 //!
 //! See [the `poly-multiproof` documentation](https://docs.rs/poly-multiproof) for more details.
@@ -58,17 +71,23 @@
 //! The logs in `bench_out.txt` can then be parsed and plotted in `Plot Benches.ipynb`.
 //! Using `--quick` is nice since there are many many inputs benchmarked and it will still take an hour or so to run with `--quick`.
 //!
-use ark_ec::{scalar_mul::fixed_base::FixedBase, CurveGroup, ScalarMul};
-use ark_ff::{Field, PrimeField};
-use ark_poly::{
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
-};
+use ark_ec::{scalar_mul::fixed_base::FixedBase, AffineRepr, CurveGroup, ScalarMul};
+use ark_ff::{BigInteger, FftField, Field, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Radix2EvaluationDomain};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError};
 use ark_std::{vec, vec::Vec};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
 use merlin::Transcript;
+use poly_ops::KzgDomain;
 #[cfg(test)]
 use rand::thread_rng as test_rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 // Public uses
 pub use ark_ec::pairing::Pairing;
@@ -81,7 +100,11 @@ pub mod m1_cycl;
 pub mod method1;
 pub mod method2;
 
+pub mod batch;
 pub mod kzg;
+pub mod merkle;
+pub mod multilinear;
+pub mod remote;
 
 pub(crate) mod lagrange;
 
@@ -90,9 +113,16 @@ pub mod traits;
 pub mod poly_ops;
 pub mod utils;
 
+pub(crate) use poly_ops::{
+    gen_powers, linear_combination, poly_div_q_r, powers_iter, vanishing_polynomial,
+};
+
 pub mod msm;
+pub mod op_count;
 
-#[cfg(test)]
+pub mod transcript;
+
+#[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
 #[cfg(feature = "ark-bls12-381")]
@@ -165,8 +195,18 @@ pub enum Error {
         n_commits: usize,
     },
     /// Failed to construct a domain of the given size
-    #[cfg_attr(feature = "std", error("Unable to construct a domain of size {0}"))]
-    DomainConstructionFailed(usize),
+    #[cfg_attr(
+        feature = "std",
+        error("Unable to construct a domain of size {size}, nearest supported size is {nearest_supported} (field two-adicity is {two_adicity})")
+    )]
+    DomainConstructionFailed {
+        /// The requested size
+        size: usize,
+        /// The nearest power of two a domain can be constructed for
+        nearest_supported: usize,
+        /// The two-adicity of the scalar field, the largest domain has size `2^two_adicity`
+        two_adicity: u32,
+    },
     /// Subgroup index was invalid
     #[cfg_attr(
         feature = "std",
@@ -178,6 +218,34 @@ pub enum Error {
         /// Number of splits
         n_splits: usize,
     },
+    /// Point set index was invalid
+    #[cfg_attr(
+        feature = "std",
+        error("Invalid point set index {idx} for {n_point_sets} point sets")
+    )]
+    InvalidPointSetIndex {
+        /// Index
+        idx: usize,
+        /// Number of point sets
+        n_point_sets: usize,
+    },
+    /// A byte blob of commitments wasn't a whole number of compressed points
+    #[cfg_attr(
+        feature = "std",
+        error("Commitment bytes of length {n_bytes} are not a multiple of {point_size}")
+    )]
+    InvalidCommitBytesLength {
+        /// The number of bytes given
+        n_bytes: usize,
+        /// The size of a compressed commitment
+        point_size: usize,
+    },
+    /// The setup used isn't from the expected ceremony
+    #[cfg_attr(
+        feature = "std",
+        error("Setup is from a different ceremony than expected")
+    )]
+    CeremonyMismatch,
     /// Invalid input length
     #[cfg_attr(
         feature = "std",
@@ -189,6 +257,231 @@ pub enum Error {
         /// Actual length
         got: usize,
     },
+    /// Fewer samples were given than are needed to recover the rest
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_samples} samples, but {needed} are needed")
+    )]
+    NotEnoughSamples {
+        /// The number of samples given
+        n_samples: usize,
+        /// The number of samples needed
+        needed: usize,
+    },
+    /// A sample index was outside the domain or given more than once
+    #[cfg_attr(
+        feature = "std",
+        error("Invalid or repeated sample index {idx} for a domain of size {domain_size}")
+    )]
+    InvalidSampleIndex {
+        /// The sample index
+        idx: usize,
+        /// The size of the domain
+        domain_size: usize,
+    },
+    /// The evals given to `open` don't match the polynomial at the points. Only checked with the
+    /// `debug-checks` feature, or with [`check_evals_match_coeffs`].
+    #[cfg_attr(
+        feature = "std",
+        error("Eval given for polynomial {poly_index} at point {point_index} doesn't match")
+    )]
+    InconsistentEvals {
+        /// The index of the polynomial with wrong evals
+        poly_index: usize,
+        /// The index of the first point its eval is wrong at
+        point_index: usize,
+    },
+    /// An index into an evaluation domain was out of range
+    #[cfg_attr(
+        feature = "std",
+        error("Index {idx} is out of range for a domain of size {domain_size}")
+    )]
+    IndexOutOfDomain {
+        /// The index
+        idx: usize,
+        /// The size of the domain
+        domain_size: usize,
+    },
+    /// The Merkle root over the evals didn't match the expected root
+    #[cfg_attr(feature = "std", error("Merkle root over the evals doesn't match"))]
+    EvalRootMismatch,
+    /// A polynomial given to `open` has more coefficients than the setup has powers of tau
+    #[cfg_attr(
+        feature = "std",
+        error("Polynomial {poly} has {n_coeffs} coefficients, but the setup supports at most {expected_max}")
+    )]
+    PolynomialTooLarge {
+        /// The index of the polynomial that is too large
+        poly: usize,
+        /// The number of coefficients, ignoring trailing zeros
+        n_coeffs: usize,
+        /// The number of powers of tau in the setup
+        expected_max: usize,
+    },
+    /// More polynomials were given than the scheme's [`SizeLimits`] allow
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_polys} polynomials, but at most {max} are allowed")
+    )]
+    TooManyPolynomials {
+        /// The number of polynomials given
+        n_polys: usize,
+        /// The configured maximum
+        max: usize,
+    },
+    /// More points were given than the scheme's [`SizeLimits`] allow
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_points} points, but at most {max} are allowed")
+    )]
+    TooManyPoints {
+        /// The number of points given
+        n_points: usize,
+        /// The configured maximum
+        max: usize,
+    },
+    /// Polynomial index was invalid
+    #[cfg_attr(
+        feature = "std",
+        error("Invalid polynomial index {idx} for {n_polys} polynomials")
+    )]
+    InvalidPolyIndex {
+        /// Index
+        idx: usize,
+        /// Number of polynomials
+        n_polys: usize,
+    },
+    /// A commitment was the point at infinity, which the scheme was configured to reject
+    #[cfg_attr(feature = "std", error("Commitment {index} is the point at infinity"))]
+    IdentityCommitment {
+        /// The index of the commitment
+        index: usize,
+    },
+    /// An opening can't be split into this many shards
+    #[cfg_attr(
+        feature = "std",
+        error("Can't split {n_polys} polynomials into {n_shards} shards")
+    )]
+    InvalidShardCount {
+        /// The number of shards asked for
+        n_shards: usize,
+        /// The number of polynomials
+        n_polys: usize,
+    },
+    /// A shard index is out of range for the number of shards
+    #[cfg_attr(
+        feature = "std",
+        error("Invalid shard index {shard} for {n_shards} shards")
+    )]
+    InvalidShardIndex {
+        /// The shard index
+        shard: usize,
+        /// The number of shards
+        n_shards: usize,
+    },
+    /// A polynomial claimed to be zero has a nonzero coefficient
+    #[cfg_attr(
+        feature = "std",
+        error("Polynomial has nonzero coefficient of degree {degree}")
+    )]
+    PolynomialNotZero {
+        /// The degree of the first nonzero coefficient
+        degree: usize,
+    },
+    /// A serialized proof has a different format version than this crate reads
+    #[cfg_attr(
+        feature = "std",
+        error("Proof has format version {found}, but only version {supported} is supported")
+    )]
+    UnsupportedProofVersion {
+        /// The version the proof was written with
+        found: u8,
+        /// The version this crate reads
+        supported: u8,
+    },
+    /// Supplied powers of the gamma challenge aren't the powers of the challenge the transcript
+    /// gives. Only checked with the `debug-checks` feature.
+    #[cfg_attr(
+        feature = "std",
+        error("Supplied challenge powers don't match the transcript's challenge")
+    )]
+    WrongChallengePowers,
+    /// A polynomial claimed to vanish on a set of points is nonzero at one of them
+    #[cfg_attr(
+        feature = "std",
+        error("Polynomial doesn't vanish at point {point_index}")
+    )]
+    PolynomialDoesNotVanish {
+        /// The index of the first point the polynomial is nonzero at
+        point_index: usize,
+    },
+    /// Point-major evaluations gave the wrong number of evaluations at a point
+    #[cfg_attr(
+        feature = "std",
+        error("Given {n_evals} evaluations at point {point}, but {expected} polynomials")
+    )]
+    PointEvalsIncorrectSize {
+        /// The index of the point that had incorrect evals
+        point: usize,
+        /// The number of evals at the point
+        n_evals: usize,
+        /// The expected number of evals, one per polynomial
+        expected: usize,
+    },
+    /// The transport of a [`remote::RemoteProver`] failed to send a request or get a response
+    #[cfg_attr(feature = "std", error("Remote prover transport failed"))]
+    RemoteTransportFailed,
+    /// The setup doesn't have enough powers of tau for what was asked of it
+    #[cfg_attr(
+        feature = "std",
+        error("Setup has {got} powers of tau, but {needed} are needed")
+    )]
+    SetupTooSmall {
+        /// The number of powers of tau needed
+        needed: usize,
+        /// The number of powers of tau the setup has
+        got: usize,
+    },
+    /// An entry of a precomputed table doesn't match its point set
+    #[cfg_attr(
+        feature = "std",
+        error("Precomputed table entry for point set {point_set} is wrong")
+    )]
+    InvalidPrecompTable {
+        /// The index of the point set whose entry is wrong
+        point_set: usize,
+    },
+    /// The gamma challenge was capped at too few bits to keep folding sound, see
+    /// [`traits::ConfigurableScheme::with_challenge_bits`]
+    #[cfg_attr(
+        feature = "std",
+        error("Challenges capped at {bits} bits, below the minimum of {min}")
+    )]
+    ChallengeBitsTooSmall {
+        /// The requested cap
+        bits: usize,
+        /// The smallest cap allowed
+        min: usize,
+    },
+    /// A [`PreparedVerify`] was finished by a scheme other than the one that prepared it, or by
+    /// the same scheme with different settings
+    #[cfg_attr(
+        feature = "std",
+        error("Verification was prepared by a different scheme")
+    )]
+    PreparedByOtherScheme,
+}
+
+impl Error {
+    /// Makes a [`Error::DomainConstructionFailed`] for a domain of `size` over `F`
+    pub(crate) fn domain_construction_failed<F: FftField>(size: usize) -> Self {
+        Self::DomainConstructionFailed {
+            size,
+            nearest_supported: utils::round_up_domain_size::<F>(size)
+                .unwrap_or_else(utils::max_domain_size::<F>),
+            two_adicity: F::TWO_ADICITY,
+        }
+    }
 }
 
 impl From<SerializationError> for Error {
@@ -197,10 +490,66 @@ impl From<SerializationError> for Error {
     }
 }
 
+/// Identifies the powers of tau ceremony a setup came from, see [`traits::Ceremony`]
+pub type CeremonyId = [u8; 32];
+
 /// A KZG commitment, consisting of a single G1 group element
-#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Debug, Clone, Copy, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Commitment<E: Pairing>(pub E::G1Affine);
 
+impl<E: Pairing> Commitment<E> {
+    fn compressed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.0.compressed_size());
+        self.0
+            .serialize_compressed(&mut bytes)
+            .expect("serializing to a vec can't fail");
+        bytes
+    }
+}
+
+/// Commitments are equal if their points are equal
+impl<E: Pairing> PartialEq for Commitment<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<E: Pairing> Eq for Commitment<E> {}
+
+/// Hashes the compressed encoding of the point. Affine points have a single canonical encoding,
+/// so this agrees with `Eq`.
+impl<E: Pairing> Hash for Commitment<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.compressed_bytes().hash(state)
+    }
+}
+
+/// Shows the first 8 bytes of the compressed encoding in hex, e.g. `Commitment(0x1a2b3c4d5e6f7a8b…)`.
+/// Serialize the commitment for the full encoding.
+impl<E: Pairing> fmt::Display for Commitment<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Commitment(0x")?;
+        for b in self.compressed_bytes().iter().take(8) {
+            write!(f, "{:02x}", b)?;
+        }
+        f.write_str("…)")
+    }
+}
+
+impl<E: Pairing> PartialOrd for Commitment<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders commitments lexicographically by the compressed encoding of their points. This has no
+/// meaning beyond being a consistent total order, e.g. for use in a `BTreeMap`.
+impl<E: Pairing> Ord for Commitment<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compressed_bytes().cmp(&other.compressed_bytes())
+    }
+}
+
 impl<E: Pairing> Commitment<E> {
     /// Given a set of commitments and a target output size that is a power of 2,
     /// extend the commitments to the target size using FFTs.
@@ -208,30 +557,439 @@ impl<E: Pairing> Commitment<E> {
         commits: impl AsRef<[Commitment<E>]>,
         output_size: usize,
     ) -> Result<Vec<Self>, Error> {
-        let mut vals: Vec<E::G1> = commits
-            .as_ref()
-            .iter()
-            .map(|x| x.0.into())
-            .collect::<Vec<_>>();
-        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(vals.len())
-            .ok_or(Error::DomainConstructionFailed(vals.len()))?;
+        let n = commits.as_ref().len();
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(n)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(n))?;
         let domain_ext = GeneralEvaluationDomain::<E::ScalarField>::new(output_size)
-            .ok_or(Error::DomainConstructionFailed(output_size))?;
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(output_size))?;
+        Ok(Self::extend_over(commits.as_ref(), &domain, &domain_ext))
+    }
+
+    /// Like [`Self::extend_commitments`], but over prebuilt domains. `commits` must fill
+    /// `domain`, and are extended to the size of `domain_ext`.
+    pub fn extend_commitments_in(
+        commits: impl AsRef<[Commitment<E>]>,
+        domain: &KzgDomain<E::ScalarField>,
+        domain_ext: &KzgDomain<E::ScalarField>,
+    ) -> Result<Vec<Self>, Error> {
+        if commits.as_ref().len() != domain.size() {
+            return Err(Error::InvalidInputLength {
+                expected: domain.size(),
+                got: commits.as_ref().len(),
+            });
+        }
+        Ok(Self::extend_over(
+            commits.as_ref(),
+            domain.domain(),
+            domain_ext.domain(),
+        ))
+    }
+
+    /// Like [`Self::extend_commitments`], but runs the group FFTs on affine points. Each FFT
+    /// layer is normalized with a batch inversion, so its butterflies are mixed additions, which
+    /// need fewer field multiplications than projective ones. Only radix-2 domains are supported.
+    pub fn extend_commitments_affine_fft(
+        commits: impl AsRef<[Commitment<E>]>,
+        output_size: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let n = commits.as_ref().len();
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(n))?;
+        let domain_ext = Radix2EvaluationDomain::<E::ScalarField>::new(output_size)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(output_size))?;
+
+        let mut vals = commits.as_ref().iter().map(|c| c.0).collect::<Vec<_>>();
+        vals.resize(domain.size(), E::G1Affine::zero());
+        affine_fft_in_place::<E::G1>(&mut vals, domain.group_gen_inv());
+        let size_inv = domain.size_inv();
+        let scaled = vals.iter().map(|v| *v * size_inv).collect::<Vec<_>>();
+        let mut vals = E::G1::normalize_batch(&scaled);
+        vals.resize(domain_ext.size(), E::G1Affine::zero());
+        affine_fft_in_place::<E::G1>(&mut vals, domain_ext.group_gen());
+        Ok(vals.into_iter().map(Commitment).collect())
+    }
+
+    fn extend_over(
+        commits: &[Commitment<E>],
+        domain: &GeneralEvaluationDomain<E::ScalarField>,
+        domain_ext: &GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Vec<Self> {
+        let mut vals: Vec<E::G1> = commits.iter().map(|x| x.0.into()).collect::<Vec<_>>();
         domain.ifft_in_place(&mut vals);
         domain_ext.fft_in_place(&mut vals);
-        Ok(vals
-            .into_iter()
+        vals.into_iter()
             .map(|x| Commitment(x.into()))
-            .collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    }
+
+    /// Checks that `extended` is the extension of `original` made by [`Self::extend_commitments`],
+    /// i.e. that interpolating `extended` over its domain gives a polynomial of degree less than
+    /// `original.len()` which is `original` over the smaller domain. Since the smaller domain is a
+    /// subgroup of the larger one, the originals appear in `extended` every
+    /// `extended.len() / original.len()` entries rather than as a prefix. Both lengths must be
+    /// the size of a domain, otherwise this errors with [`Error::InvalidInputLength`].
+    pub fn verify_extension(original: &[Self], extended: &[Self]) -> Result<bool, Error> {
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(original.len())
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(original.len()))?;
+        let domain_ext = GeneralEvaluationDomain::<E::ScalarField>::new(extended.len())
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(extended.len()))?;
+        for (d, len) in [
+            (domain.size(), original.len()),
+            (domain_ext.size(), extended.len()),
+        ] {
+            if d != len {
+                return Err(Error::InvalidInputLength {
+                    expected: d,
+                    got: len,
+                });
+            }
+        }
+        if extended.len() < original.len() {
+            return Ok(false);
+        }
+        let mut coeffs: Vec<E::G1> = extended.iter().map(|x| x.0.into()).collect();
+        domain_ext.ifft_in_place(&mut coeffs);
+        if coeffs[original.len()..].iter().any(|c| !c.is_zero()) {
+            return Ok(false);
+        }
+        coeffs.truncate(original.len());
+        domain.fft_in_place(&mut coeffs);
+        Ok(coeffs
+            .iter()
+            .zip(original)
+            .all(|(c, o)| *c == o.0.into_group()))
+    }
+
+    /// Recovers every commitment of an extension made with [`Self::extend_commitments`] from
+    /// `n_original` of them, given as `(index, commitment)` pairs indexing into `domain`. This is
+    /// erasure decoding in the commitment group: the samples are interpolated and evaluated over
+    /// the rest of the domain. Only the first `n_original` samples are used.
+    pub fn recover_commitment_from_partial<D: EvaluationDomain<E::ScalarField>>(
+        known: &[(usize, Commitment<E>)],
+        n_original: usize,
+        domain: &D,
+    ) -> Result<Vec<Self>, Error> {
+        if n_original == 0 {
+            return Err(Error::NoPointsGiven);
+        }
+        if known.len() < n_original {
+            return Err(Error::NotEnoughSamples {
+                n_samples: known.len(),
+                needed: n_original,
+            });
+        }
+        let samples = &known[..n_original];
+        let mut recovered = vec![None; domain.size()];
+        for (idx, commit) in samples {
+            match recovered.get_mut(*idx) {
+                Some(slot @ None) => *slot = Some(*commit),
+                _ => {
+                    return Err(Error::InvalidSampleIndex {
+                        idx: *idx,
+                        domain_size: domain.size(),
+                    })
+                }
+            }
+        }
+
+        // Barycentric weights 1 / prod_{j != i} (x_i - x_j) of the sample points
+        let xs = samples
+            .iter()
+            .map(|(idx, _)| domain.element(*idx))
+            .collect::<Vec<_>>();
+        let mut weights = xs
+            .iter()
+            .enumerate()
+            .map(|(i, xi)| {
+                xs.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, xj)| *xi - xj)
+                    .product::<E::ScalarField>()
+            })
+            .collect::<Vec<_>>();
+        ark_ff::batch_inversion(&mut weights);
+        op_count::count_inversion();
+        let bases = samples.iter().map(|(_, c)| c.0).collect::<Vec<_>>();
+
+        domain
+            .elements()
+            .zip(recovered)
+            .map(|(x, known)| match known {
+                Some(commit) => Ok(commit),
+                None => {
+                    // The lagrange basis at x is w_i * prod_j (x - x_j) / (x - x_i)
+                    let mut scalars = xs.iter().map(|xi| x - xi).collect::<Vec<_>>();
+                    let vanishing: E::ScalarField = scalars.iter().product();
+                    ark_ff::batch_inversion(&mut scalars);
+                    op_count::count_inversion();
+                    scalars
+                        .iter_mut()
+                        .zip(&weights)
+                        .for_each(|(s, w)| *s *= vanishing * w);
+                    Ok(Commitment(curve_msm::<E::G1>(&bases, &scalars)?.into()))
+                }
+            })
+            .collect()
+    }
+
+    /// Reed-Solomon encodes `data_commits` in the commitment group into `expansion_factor` times
+    /// as many commitments, any `k` of which recover the data with [`Self::decode_commitments`].
+    /// `k` is the number of data commitments rounded up to a supported domain size, since the
+    /// data is padded with zero commitments to fill its domain.
+    pub fn encode_commitments(
+        data_commits: &[Commitment<E>],
+        expansion_factor: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let n_data = GeneralEvaluationDomain::<E::ScalarField>::new(data_commits.len())
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(data_commits.len()))?
+            .size();
+        Self::extend_commitments(data_commits, n_data * expansion_factor)
+    }
+
+    /// Recovers the `n_original` data commitments encoded with [`Self::encode_commitments`] from
+    /// the commitments received, with `None` for those missing. Errors with
+    /// [`Error::NotEnoughSamples`] if too few were received.
+    pub fn decode_commitments(
+        received: &[Option<Commitment<E>>],
+        n_original: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let data_domain = GeneralEvaluationDomain::<E::ScalarField>::new(n_original)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(n_original))?;
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(received.len())
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(received.len()))?;
+        if domain.size() != received.len() || received.len() % data_domain.size() != 0 {
+            return Err(Error::InvalidInputLength {
+                expected: domain.size(),
+                got: received.len(),
+            });
+        }
+        let known = received
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| c.map(|c| (i, c)))
+            .collect::<Vec<_>>();
+        let all = Self::recover_commitment_from_partial(&known, data_domain.size(), &domain)?;
+        // Data commitment i sits at the i-th element of the data domain, which is every
+        // `step`-th element of the full domain
+        let step = received.len() / data_domain.size();
+        Ok(all.into_iter().step_by(step).take(n_original).collect())
+    }
+
+    /// Folds the commitments into one using powers of `challenge`, computing
+    /// $\sum_i \gamma^i C_i$. This is the same fold the verifiers do.
+    pub fn fold_commitments(
+        commits: &[Commitment<E>],
+        challenge: E::ScalarField,
+    ) -> Result<Self, Error> {
+        if commits.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        let (cms, gammas): (Vec<_>, Vec<_>) = commits
+            .iter()
+            .map(|c| c.0)
+            .zip(powers_iter(challenge, commits.len()))
+            .unzip();
+        Ok(Commitment(curve_msm::<E::G1>(&cms, &gammas)?.into()))
     }
 }
 
-pub(crate) fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
-    let mut powers = vec![F::one(); len];
-    for i in 1..len {
-        powers[i] = element * powers[i - 1];
+/// The sum of some commitments, kept in projective form so that summing many only normalizes
+/// once. Collect commitments into one, then call [`AggregateCommitment::into_commitment`].
+#[derive(Debug, Clone, Copy)]
+pub struct AggregateCommitment<E: Pairing>(pub E::G1);
+
+impl<E: Pairing> AggregateCommitment<E> {
+    /// Normalizes the sum into a commitment
+    pub fn into_commitment(self) -> Commitment<E> {
+        Commitment(self.0.into_affine())
     }
-    powers
+}
+
+impl<E: Pairing> FromIterator<Commitment<E>> for AggregateCommitment<E> {
+    fn from_iter<I: IntoIterator<Item = Commitment<E>>>(iter: I) -> Self {
+        AggregateCommitment(iter.into_iter().fold(E::G1::zero(), |acc, c| acc + c.0))
+    }
+}
+
+/// A commitment given lazily as a linear combination $\sum_j c_j C_j$ of base commitments.
+/// Verifying against these folds every combination into one msm instead of materializing each.
+pub type LazyCommitment<E> = Vec<(Commitment<E>, <E as Pairing>::ScalarField)>;
+
+/// A commitment that can be scaled and added into a verifier's msm
+pub(crate) trait FoldTerms<E: Pairing> {
+    /// Pushes the bases and scalars of `scale` times this commitment
+    fn push_terms(
+        &self,
+        scale: E::ScalarField,
+        bases: &mut Vec<E::G1Affine>,
+        scalars: &mut Vec<E::ScalarField>,
+    );
+
+    /// Whether this is, or is combined from, a commitment to the point at infinity
+    fn has_identity(&self) -> bool;
+}
+
+impl<E: Pairing> FoldTerms<E> for Commitment<E> {
+    fn push_terms(
+        &self,
+        scale: E::ScalarField,
+        bases: &mut Vec<E::G1Affine>,
+        scalars: &mut Vec<E::ScalarField>,
+    ) {
+        bases.push(self.0);
+        scalars.push(scale);
+    }
+
+    fn has_identity(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<E: Pairing> FoldTerms<E> for LazyCommitment<E> {
+    fn push_terms(
+        &self,
+        scale: E::ScalarField,
+        bases: &mut Vec<E::G1Affine>,
+        scalars: &mut Vec<E::ScalarField>,
+    ) {
+        for (c, coeff) in self {
+            bases.push(c.0);
+            scalars.push(scale * coeff);
+        }
+    }
+
+    fn has_identity(&self) -> bool {
+        self.iter().any(|(c, _)| c.0.is_zero())
+    }
+}
+
+/// Errors with [`Error::IdentityCommitment`] on the first commitment at the point at infinity
+pub(crate) fn check_identity_commitments<E: Pairing, C: FoldTerms<E>>(
+    commits: &[C],
+) -> Result<(), Error> {
+    match commits.iter().position(|c| c.has_identity()) {
+        Some(index) => Err(Error::IdentityCommitment { index }),
+        None => Ok(()),
+    }
+}
+
+/// Gets the bases and scalars of the msm $\sum_i \gamma^i C_i$
+#[allow(clippy::type_complexity)]
+pub(crate) fn fold_terms<E: Pairing, C: FoldTerms<E>>(
+    commits: &[C],
+    gammas: &[E::ScalarField],
+) -> (Vec<E::G1Affine>, Vec<E::ScalarField>) {
+    let mut bases = Vec::with_capacity(commits.len());
+    let mut scalars = Vec::with_capacity(commits.len());
+    for (c, g) in commits.iter().zip(gammas) {
+        c.push_terms(*g, &mut bases, &mut scalars);
+    }
+    (bases, scalars)
+}
+
+/// Takes the first `n` of the supplied powers of the challenge `gamma` in place of generating
+/// them. Errors with [`Error::InvalidInputLength`] if fewer than `n` are given. The powers are
+/// otherwise trusted, and only checked against `gamma` with the `debug-checks` feature.
+pub(crate) fn supplied_powers<F: Field>(powers: &[F], gamma: F, n: usize) -> Result<&[F], Error> {
+    let powers = powers.get(..n).ok_or(Error::InvalidInputLength {
+        expected: n,
+        got: powers.len(),
+    })?;
+    if cfg!(feature = "debug-checks") && powers != gen_powers(gamma, n).as_slice() {
+        return Err(Error::WrongChallengePowers);
+    }
+    Ok(powers)
+}
+
+/// An estimate of the work done by a single `verify` call with precomputed point sets, as a
+/// function of the number of points and polynomials. Useful for budgeting verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyCost {
+    /// The number of pairings computed
+    pub pairings: usize,
+    /// The total number of G1 bases across multi-scalar multiplications. A single scalar
+    /// multiplication counts as one.
+    pub g1_msm_size: usize,
+    /// The total number of G2 bases across multi-scalar multiplications. A single scalar
+    /// multiplication counts as one.
+    pub g2_msm_size: usize,
+    /// The approximate number of scalar field multiplications
+    pub field_muls: usize,
+}
+
+/// The inputs to a `verify` call which have passed every check that doesn't need the proof or
+/// any curve arithmetic. Made by
+/// [`prepare_verify`](traits::PolyMultiProofNoPrecomp::prepare_verify) and consumed by
+/// [`finish_verify`](traits::PolyMultiProofNoPrecomp::finish_verify).
+/// A value prepared by one scheme is rejected by the others, see [`Error::PreparedByOtherScheme`].
+#[derive(Debug, Clone)]
+pub struct PreparedVerify<'a, E: Pairing> {
+    pub(crate) commits: &'a [Commitment<E>],
+    pub(crate) points: &'a [E::ScalarField],
+    pub(crate) evals: Vec<&'a [E::ScalarField]>,
+    /// The scheme that prepared this, if it checks it when finishing
+    pub(crate) scheme: Option<SchemeTag<E>>,
+}
+
+/// Identifies the scheme that made a [`PreparedVerify`]: its kind, its setup, and the checks it
+/// applied while preparing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SchemeTag<E: Pairing> {
+    kind: &'static str,
+    tau_g1: Option<E::G1Affine>,
+    limits: SizeLimits,
+    reject_identity_commitments: bool,
+}
+
+impl<E: Pairing> SchemeTag<E> {
+    /// The tag of a scheme of the given kind with the given powers of tau in G1 and settings
+    pub(crate) fn new(
+        kind: &'static str,
+        powers_of_g1: &[E::G1Affine],
+        config: &SchemeConfig,
+    ) -> Self {
+        Self {
+            kind,
+            tau_g1: powers_of_g1.get(1).copied(),
+            limits: config.limits,
+            reject_identity_commitments: config.reject_identity_commitments,
+        }
+    }
+}
+
+impl<'a, E: Pairing> PreparedVerify<'a, E> {
+    pub(crate) fn new(
+        commits: &'a [Commitment<E>],
+        points: &'a [E::ScalarField],
+        evals: &'a [impl AsRef<[E::ScalarField]>],
+    ) -> Result<Self, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+        Ok(Self {
+            commits,
+            points,
+            evals: evals.iter().map(|e| e.as_ref()).collect(),
+            scheme: None,
+        })
+    }
+
+    /// Errors with [`Error::PreparedByOtherScheme`] unless this was prepared by the scheme with
+    /// the given tag
+    pub(crate) fn check_scheme(&self, tag: SchemeTag<E>) -> Result<(), Error> {
+        match self.scheme {
+            Some(scheme) if scheme == tag => Ok(()),
+            _ => Err(Error::PreparedByOtherScheme),
+        }
+    }
+}
+
+/// Strips trailing zero coefficients, which don't change the polynomial but would otherwise make
+/// it look like it has a larger degree than it does.
+pub(crate) fn trim_zeros<F: Zero>(mut scalars: &[F]) -> &[F] {
+    while scalars.last().map(|s| s.is_zero()).unwrap_or(false) {
+        scalars = &scalars[..scalars.len() - 1];
+    }
+    scalars
 }
 
 #[inline]
@@ -239,52 +997,99 @@ pub(crate) fn curve_msm<G: ScalarMul + CurveGroup>(
     bases: &[G::Affine],
     scalars: &[G::ScalarField],
 ) -> Result<G, Error> {
+    let scalars = trim_zeros(scalars);
+    let scalars = scalars.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+    curve_msm_bigint::<G>(bases, &scalars)
+}
+
+/// A radix-2 FFT of the group elements `vals` over the subgroup generated by `omega`, which must
+/// be a primitive `vals.len()`-th root of unity. Each layer's twiddled points and outputs are
+/// normalized with one batch inversion apiece, so every addition has an affine operand.
+fn affine_fft_in_place<G: CurveGroup>(vals: &mut [G::Affine], omega: G::ScalarField) {
+    let n = vals.len();
+    debug_assert!(n.is_power_of_two());
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            vals.swap(i, j);
+        }
+    }
+    let mut m = 1;
+    while m < n {
+        let twiddles = gen_powers(omega.pow([(n / (2 * m)) as u64]), m);
+        let twiddled = vals
+            .chunks(2 * m)
+            .flat_map(|c| {
+                c[m..].iter().zip(&twiddles).enumerate().map(|(j, (b, w))| {
+                    // The first twiddle is always one
+                    if j == 0 {
+                        b.into_group()
+                    } else {
+                        *b * w
+                    }
+                })
+            })
+            .collect::<Vec<G>>();
+        let twiddled = G::normalize_batch(&twiddled);
+        let mut out = vec![G::zero(); n];
+        for (k, (chunk, t)) in vals.chunks(2 * m).zip(twiddled.chunks(m)).enumerate() {
+            for j in 0..m {
+                let u = chunk[j].into_group();
+                out[2 * m * k + j] = u + t[j];
+                out[2 * m * k + j + m] = u - t[j];
+            }
+        }
+        vals.copy_from_slice(&G::normalize_batch(&out));
+        m *= 2;
+    }
+}
+
+/// Like [`curve_msm`], but takes the scalars already out of Montgomery form
+pub(crate) fn curve_msm_bigint<G: ScalarMul + CurveGroup>(
+    bases: &[G::Affine],
+    mut scalars: &[<G::ScalarField as PrimeField>::BigInt],
+) -> Result<G, Error> {
+    while scalars.last().map(|s| s.is_zero()).unwrap_or(false) {
+        scalars = &scalars[..scalars.len() - 1];
+    }
     if scalars.len() > bases.len() {
         return Err(Error::TooManyScalars {
             n_coeffs: scalars.len(),
             expected_max: bases.len(),
         });
     }
-    let scalars = scalars.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
-    let sp = G::msm_bigint(&bases[..scalars.len()], &scalars);
-    Ok(sp)
-}
-
-pub(crate) fn vanishing_polynomial<F: Field>(points: impl AsRef<[F]>) -> DensePolynomial<F> {
-    let one = DensePolynomial::from_coefficients_vec(vec![F::one()]);
-    points
-        .as_ref()
-        .iter()
-        .map(|&point| DensePolynomial::from_coefficients_vec(vec![-point, F::one()]))
-        .fold(one, |x, y| x.naive_mul(&y))
+    op_count::count_msm(scalars.len());
+    Ok(G::msm_bigint(&bases[..scalars.len()], scalars))
 }
 
-/// Does polynomial division, returning q, r
-pub(crate) fn poly_div_q_r<F: Field>(
-    num: DenseOrSparsePolynomial<F>,
-    denom: DenseOrSparsePolynomial<F>,
-) -> Result<(Vec<F>, Vec<F>), Error> {
-    if denom.is_zero() {
-        return Err(Error::DivisorIsZero);
+/// Computes an msm over only the given `(index, scalar)` terms, using `bases[index]` for each
+pub(crate) fn sparse_curve_msm<G: ScalarMul + CurveGroup>(
+    bases: &[G::Affine],
+    terms: &[(usize, G::ScalarField)],
+) -> Result<G, Error> {
+    if let Some(max_idx) = terms.iter().map(|(i, _)| *i).max() {
+        if max_idx >= bases.len() {
+            return Err(Error::TooManyScalars {
+                n_coeffs: max_idx.saturating_add(1),
+                expected_max: bases.len(),
+            });
+        }
     }
-    let (q, r) = num.divide_with_q_and_r(&denom).expect("Cannot return none");
-    Ok((q.coeffs, r.coeffs))
-}
-
-pub(crate) fn linear_combination<F: Field>(
-    polynomials: &[impl AsRef<[F]>],
-    challenges: &[F],
-) -> Option<Vec<F>> {
-    polynomials
-        .as_ref()
+    let sparse_bases = terms.iter().map(|(i, _)| bases[*i]).collect::<Vec<_>>();
+    let scalars = terms
         .iter()
-        .zip(challenges.iter())
-        .map(|(p, &c)| &DensePolynomial::from_coefficients_slice(p.as_ref()) * c)
-        .reduce(|x, y| x + y)?
-        .coeffs
-        .into()
+        .map(|(_, s)| s.into_bigint())
+        .collect::<Vec<_>>();
+    op_count::count_msm(scalars.len());
+    Ok(G::msm_bigint(&sparse_bases, &scalars))
 }
 
+// With `parallel` this is only used to check the chunked version against
+#[cfg_attr(feature = "parallel", allow(dead_code))]
 pub(crate) fn gen_curve_powers_proj<G: ScalarMul + CurveGroup>(
     powers: &[G::ScalarField],
     base: G,
@@ -295,11 +1100,36 @@ pub(crate) fn gen_curve_powers_proj<G: ScalarMul + CurveGroup>(
     FixedBase::msm::<G>(scalar_size, window_size, &g_table, powers)
 }
 
+/// Like [`gen_curve_powers_proj`], but splits `powers` into chunks of `chunk_size` which are
+/// multiplied on separate threads, sharing one window table.
+#[cfg(feature = "parallel")]
+pub(crate) fn gen_curve_powers_proj_chunked<G: ScalarMul + CurveGroup>(
+    powers: &[G::ScalarField],
+    base: G,
+    chunk_size: usize,
+) -> Vec<G> {
+    let window_size = FixedBase::get_mul_window_size(powers.len());
+    let scalar_size = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let g_table = FixedBase::get_window_table::<G>(scalar_size, window_size, base);
+    powers
+        .par_chunks(chunk_size.max(1))
+        .flat_map_iter(|chunk| FixedBase::msm::<G>(scalar_size, window_size, &g_table, chunk))
+        .collect()
+}
+
 pub(crate) fn gen_curve_powers<G: ScalarMul + CurveGroup>(
     powers: &[G::ScalarField],
     base: G,
 ) -> Vec<G::Affine> {
-    G::normalize_batch(&gen_curve_powers_proj(powers, base))
+    #[cfg(feature = "parallel")]
+    let proj = gen_curve_powers_proj_chunked(
+        powers,
+        base,
+        powers.len().div_ceil(rayon::current_num_threads()),
+    );
+    #[cfg(not(feature = "parallel"))]
+    let proj = gen_curve_powers_proj(powers, base);
+    G::normalize_batch(&proj)
 }
 
 pub(crate) fn get_field_size<F: Field + CanonicalSerialize>() -> usize {
@@ -309,30 +1139,37 @@ pub(crate) fn get_field_size<F: Field + CanonicalSerialize>() -> usize {
 pub(crate) fn transcribe_points_and_evals<F: CanonicalSerialize>(
     transcript: &mut Transcript,
     points: &[F],
-    evals: &[impl AsRef<[F]>],
+    evals: &(impl EvalMatrix<F> + ?Sized),
     field_size_bytes: usize,
+    compress: Compress,
 ) -> Result<(), Error> {
     let n_points = points.len();
-    let mut eval_bytes = vec![0u8; field_size_bytes * n_points * evals.len()];
-    for (i, e) in evals.iter().enumerate() {
-        if e.as_ref().len() != n_points {
+    let mut eval_bytes = vec![0u8; field_size_bytes * n_points * evals.n_polys()];
+    for i in 0..evals.n_polys() {
+        if evals.n_evals(i) != n_points {
             return Err(Error::EvalsIncorrectSize {
                 poly: i,
-                n_evals: e.as_ref().len(),
+                n_evals: evals.n_evals(i),
                 expected: n_points,
             });
         }
-        for (j, p) in e.as_ref().iter().enumerate() {
+        // Always absorbed poly-major, whatever the layout
+        for j in 0..n_points {
             let start = (i * n_points + j) * field_size_bytes;
-            p.serialize_compressed(&mut eval_bytes[start..start + field_size_bytes])?;
+            evals
+                .eval(i, j)
+                .serialize_with_mode(&mut eval_bytes[start..start + field_size_bytes], compress)?;
         }
     }
-    transcript.append_message(b"open evals", &eval_bytes);
+    transcript::append_message(transcript, b"open evals", &eval_bytes);
     let mut point_bytes = vec![0u8; field_size_bytes * n_points];
     for (i, p) in points.iter().enumerate() {
-        p.serialize_compressed(&mut point_bytes[i * field_size_bytes..(i + 1) * field_size_bytes])?;
+        p.serialize_with_mode(
+            &mut point_bytes[i * field_size_bytes..(i + 1) * field_size_bytes],
+            compress,
+        )?;
     }
-    transcript.append_message(b"open points", &point_bytes);
+    transcript::append_message(transcript, b"open points", &point_bytes);
     Ok(())
 }
 
@@ -341,38 +1178,335 @@ pub(crate) fn transcribe_generic<F: CanonicalSerialize>(
     label: &'static [u8],
     f: &F,
 ) -> Result<(), Error> {
-    let elt_size = f.serialized_size(Compress::Yes);
-    let mut buf = vec![0u8; elt_size];
-    f.serialize_compressed(&mut buf)?;
-    transcript.append_message(label, &buf);
+    transcribe_generic_as(transcript, label, f, Compress::Yes)
+}
+
+/// Formats a [`Compress`], which doesn't implement `Debug` itself
+pub(crate) struct DebugCompress(pub Compress);
+
+impl core::fmt::Debug for DebugCompress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0 {
+            Compress::Yes => f.write_str("Yes"),
+            Compress::No => f.write_str("No"),
+        }
+    }
+}
+
+/// Like [`transcribe_generic`], serializing `f` with the given compression
+pub(crate) fn transcribe_generic_as<F: CanonicalSerialize>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    f: &F,
+    compress: Compress,
+) -> Result<(), Error> {
+    transcribe_generic_with(transcript, label, f, &mut Vec::new(), compress)
+}
+
+/// Absorbs the compressed serialization of `f` into the transcript under `label`, serializing
+/// into `scratch` so that a buffer can be reused across many calls. Any contents of `scratch` are
+/// discarded.
+pub fn transcribe_generic_into<F: CanonicalSerialize>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    f: &F,
+    scratch: &mut Vec<u8>,
+) -> Result<(), Error> {
+    transcribe_generic_with(transcript, label, f, scratch, Compress::Yes)
+}
+
+fn transcribe_generic_with<F: CanonicalSerialize>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    f: &F,
+    scratch: &mut Vec<u8>,
+    compress: Compress,
+) -> Result<(), Error> {
+    // The serialization is appended after `elt_size` zeros rather than written over them. Every
+    // transcript absorbs it this way, so it's kept to keep challenges the same.
+    let elt_size = f.serialized_size(compress);
+    scratch.clear();
+    scratch.resize(elt_size, 0);
+    f.serialize_with_mode(&mut *scratch, compress)?;
+    transcript::append_message(transcript, label, scratch);
     Ok(())
 }
 
+/// Draws a nonzero challenge from the transcript. A zero challenge would collapse the
+/// linear combinations it's used in, so if one is drawn a retry counter is appended and the
+/// transcript is squeezed again.
 pub(crate) fn get_challenge<F: PrimeField>(
     transcript: &mut Transcript,
     label: &'static [u8],
     field_size_bytes: usize,
 ) -> F {
+    #[cfg(all(
+        any(test, feature = "insecure-deterministic-challenges"),
+        feature = "std"
+    ))]
+    if let Some(c) = transcript::counter::next() {
+        return F::from(c);
+    }
+    let mut retries = 0u64;
+    draw_nonzero(field_size_bytes, |challenge_bytes| {
+        if retries > 0 {
+            transcript::append_u64(transcript, b"challenge retry", retries);
+        }
+        retries += 1;
+        transcript::challenge_bytes(transcript, label, challenge_bytes);
+    })
+}
+
+/// Like [`get_challenge`], but with at most `challenge_bits` bits when given, which must be
+/// nonzero. Only `challenge_bits.div_ceil(8)` bytes are squeezed, and the excess high bits are
+/// masked off. A cap at or above the field size draws the same challenge as [`get_challenge`].
+pub(crate) fn get_challenge_bits<F: PrimeField>(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    field_size_bytes: usize,
+    challenge_bits: Option<usize>,
+) -> F {
+    #[cfg(all(
+        any(test, feature = "insecure-deterministic-challenges"),
+        feature = "std"
+    ))]
+    if let Some(c) = transcript::counter::next() {
+        return F::from(c);
+    }
+    let bits = match challenge_bits {
+        Some(bits) if bits < 8 * field_size_bytes => bits,
+        _ => return get_challenge(transcript, label, field_size_bytes),
+    };
+    debug_assert!(bits > 0, "challenges need at least one bit");
+    let n_bytes = bits.div_ceil(8);
+    let mut retries = 0u64;
+    draw_nonzero(n_bytes, |challenge_bytes| {
+        if retries > 0 {
+            transcript::append_u64(transcript, b"challenge retry", retries);
+        }
+        retries += 1;
+        transcript::challenge_bytes(transcript, label, challenge_bytes);
+        // Big-endian, so the excess bits are at the top of the first byte
+        challenge_bytes[0] &= 0xff >> (8 * n_bytes - bits);
+    })
+}
+
+fn draw_nonzero<F: PrimeField>(field_size_bytes: usize, mut squeeze: impl FnMut(&mut [u8])) -> F {
     let mut challenge_bytes = vec![0u8; field_size_bytes];
-    transcript.challenge_bytes(label, &mut challenge_bytes);
-    F::from_be_bytes_mod_order(&challenge_bytes)
+    loop {
+        squeeze(&mut challenge_bytes);
+        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
+        if !challenge.is_zero() {
+            return challenge;
+        }
+    }
+}
+
+/// Caps on the number of polynomials and points a scheme will open or verify. These are checked
+/// before any work is done, so a prover exposed to untrusted requests can bound its memory use.
+/// Both are unlimited by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeLimits {
+    /// The maximum number of polynomials (or commitments) per opening
+    pub max_polys: Option<usize>,
+    /// The maximum number of points per opening
+    pub max_points: Option<usize>,
+}
+
+impl SizeLimits {
+    pub(crate) fn check(&self, n_polys: usize, n_points: usize) -> Result<(), Error> {
+        if let Some(max) = self.max_polys.filter(|&max| n_polys > max) {
+            return Err(Error::TooManyPolynomials { n_polys, max });
+        }
+        if let Some(max) = self.max_points.filter(|&max| n_points > max) {
+            return Err(Error::TooManyPoints { n_points, max });
+        }
+        Ok(())
+    }
+}
+
+/// The settings shared by the schemes without precomputation, set with the builders on
+/// [`traits::ConfigurableScheme`]
+#[derive(Clone, Copy)]
+pub struct SchemeConfig {
+    /// Caps on the size of openings the scheme will make or verify
+    pub limits: SizeLimits,
+    /// Whether verifying rejects commitments to the point at infinity, see
+    /// [`Error::IdentityCommitment`]. Off by default.
+    pub reject_identity_commitments: bool,
+    /// How group elements are serialized when absorbed into the transcript. The prover and
+    /// verifier must agree on this. Defaults to [`Compress::Yes`].
+    pub transcript_compress: Compress,
+    /// The most bits the gamma challenge folding the polynomials has, set with
+    /// [`traits::ConfigurableScheme::with_challenge_bits`] so it is never below
+    /// [`Self::MIN_CHALLENGE_BITS`]. Uncapped by default.
+    pub(crate) challenge_bits: Option<usize>,
+}
+
+impl Default for SchemeConfig {
+    fn default() -> Self {
+        Self {
+            limits: SizeLimits::default(),
+            reject_identity_commitments: false,
+            transcript_compress: Compress::Yes,
+            challenge_bits: None,
+        }
+    }
+}
+
+// Written out since `Compress` isn't `Debug`
+impl core::fmt::Debug for SchemeConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SchemeConfig")
+            .field("limits", &self.limits)
+            .field(
+                "reject_identity_commitments",
+                &self.reject_identity_commitments,
+            )
+            .field(
+                "transcript_compress",
+                &DebugCompress(self.transcript_compress),
+            )
+            .field("challenge_bits", &self.challenge_bits)
+            .finish()
+    }
+}
+
+impl SchemeConfig {
+    /// The fewest bits the gamma challenge can be capped at. A wrong opening of $n$ polynomials
+    /// passes with probability about $n / 2^{bits}$, so below this forging is within reach of a
+    /// brute-force search.
+    pub const MIN_CHALLENGE_BITS: usize = 40;
+
+    /// The cap on the bits of the gamma challenge, if any
+    pub fn challenge_bits(&self) -> Option<usize> {
+        self.challenge_bits
+    }
+
+    /// Draws the gamma challenge that folds the polynomials, with at most
+    /// [`Self::challenge_bits`] bits
+    pub(crate) fn gamma_challenge<F: PrimeField>(
+        &self,
+        transcript: &mut Transcript,
+        field_size_bytes: usize,
+    ) -> F {
+        get_challenge_bits(
+            transcript,
+            b"open gamma",
+            field_size_bytes,
+            self.challenge_bits,
+        )
+    }
+
+    /// Runs the checks of `verify` that don't need the proof: [`Self::limits`], the sizes of the
+    /// inputs, and [`Self::reject_identity_commitments`]. The result is tagged with `scheme`, which
+    /// has to finish it.
+    pub(crate) fn prepare_verify<'a, E: Pairing>(
+        &self,
+        scheme: SchemeTag<E>,
+        commits: &'a [Commitment<E>],
+        points: &'a [E::ScalarField],
+        evals: &'a [impl AsRef<[E::ScalarField]>],
+    ) -> Result<PreparedVerify<'a, E>, Error> {
+        self.limits.check(commits.len(), points.len())?;
+        let prepared = PreparedVerify::new(commits, points, evals)?;
+        self.check_commits(commits)?;
+        Ok(PreparedVerify {
+            scheme: Some(scheme),
+            ..prepared
+        })
+    }
+
+    /// Checks `commits` against [`Self::reject_identity_commitments`]
+    pub(crate) fn check_commits<E: Pairing, C: FoldTerms<E>>(
+        &self,
+        commits: &[C],
+    ) -> Result<(), Error> {
+        if self.reject_identity_commitments {
+            check_identity_commitments(commits)?;
+        }
+        Ok(())
+    }
+}
+
+/// A matrix of evals, where entry `(i, j)` is polynomial `i` at point `j`. This lets openings take
+/// evals in either layout without transposing them.
+pub(crate) trait EvalMatrix<F> {
+    /// The number of polynomials
+    fn n_polys(&self) -> usize;
+    /// The number of evals given for polynomial `i`
+    fn n_evals(&self, i: usize) -> usize;
+    /// Polynomial `i` at point `j`
+    fn eval(&self, i: usize, j: usize) -> &F;
+}
+
+/// Poly-major evals, the layout `open` takes
+impl<F, T: AsRef<[F]>> EvalMatrix<F> for [T] {
+    fn n_polys(&self) -> usize {
+        self.len()
+    }
+
+    fn n_evals(&self, i: usize) -> usize {
+        self[i].as_ref().len()
+    }
+
+    fn eval(&self, i: usize, j: usize) -> &F {
+        &self[i].as_ref()[j]
+    }
+}
+
+/// Point-major evals, where `by_point[j][i]` is polynomial `i` at point `j`
+pub(crate) struct PointMajor<'a, F> {
+    by_point: &'a [Vec<F>],
+    n_polys: usize,
+}
+
+impl<'a, F> PointMajor<'a, F> {
+    /// Wraps point-major evals of `n_polys` polynomials, checking each point has an eval for
+    /// every polynomial
+    pub(crate) fn new(by_point: &'a [Vec<F>], n_polys: usize) -> Result<Self, Error> {
+        for (j, row) in by_point.iter().enumerate() {
+            if row.len() != n_polys {
+                return Err(Error::PointEvalsIncorrectSize {
+                    point: j,
+                    n_evals: row.len(),
+                    expected: n_polys,
+                });
+            }
+        }
+        Ok(Self { by_point, n_polys })
+    }
+}
+
+impl<F> EvalMatrix<F> for PointMajor<'_, F> {
+    fn n_polys(&self) -> usize {
+        self.n_polys
+    }
+
+    fn n_evals(&self, _i: usize) -> usize {
+        self.by_point.len()
+    }
+
+    fn eval(&self, i: usize, j: usize) -> &F {
+        &self.by_point[j][i]
+    }
 }
 
 pub(crate) fn check_opening_sizes<F>(
-    evals: &[impl AsRef<[F]>],
+    evals: &(impl EvalMatrix<F> + ?Sized),
     polys: &[impl AsRef<[F]>],
     n_points: usize,
 ) -> Result<(), Error> {
-    if evals.len() != polys.len() {
+    if evals.n_polys() != polys.len() {
         return Err(Error::EvalsAndPolysDifferentSizes {
-            n_eval_rows: evals.len(),
+            n_eval_rows: evals.n_polys(),
             n_polys: polys.len(),
         });
     }
-    for e in evals {
-        if e.as_ref().len() != n_points {
+    for i in 0..evals.n_polys() {
+        if evals.n_evals(i) != n_points {
             return Err(Error::EvalsAndPointsDifferentSizes {
-                n_evals: e.as_ref().len(),
+                n_evals: evals.n_evals(i),
                 n_points,
             });
         }
@@ -380,6 +1514,25 @@ pub(crate) fn check_opening_sizes<F>(
     Ok(())
 }
 
+/// Checks that each polynomial, ignoring trailing zeros, fits in a setup with `max_coeffs` powers
+/// of tau. Otherwise opening would only fail once an msm is too big, if at all.
+pub(crate) fn check_poly_sizes<F: Zero>(
+    polys: &[impl AsRef<[F]>],
+    max_coeffs: usize,
+) -> Result<(), Error> {
+    for (poly, p) in polys.iter().enumerate() {
+        let n_coeffs = trim_zeros(p.as_ref()).len();
+        if n_coeffs > max_coeffs {
+            return Err(Error::PolynomialTooLarge {
+                poly,
+                n_coeffs,
+                expected_max: max_coeffs,
+            });
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn check_verify_sizes<F, C>(
     commits: &[C],
     evals: &[impl AsRef<[F]>],
@@ -402,6 +1555,86 @@ pub(crate) fn check_verify_sizes<F, C>(
     Ok(())
 }
 
+/// Maps indices into `domain` to the corresponding domain elements
+pub(crate) fn domain_points<F: FftField>(
+    domain: &impl EvaluationDomain<F>,
+    indices: &[usize],
+) -> Result<Vec<F>, Error> {
+    indices
+        .iter()
+        .map(|&idx| {
+            if idx >= domain.size() {
+                return Err(Error::IndexOutOfDomain {
+                    idx,
+                    domain_size: domain.size(),
+                });
+            }
+            Ok(domain.element(idx))
+        })
+        .collect()
+}
+
+/// The ranges of polynomial indices each shard of a sharded opening covers, see
+/// [`traits::PolyMultiProofNoPrecomp::open_sharded`]. The shards are contiguous, nonempty, and
+/// differ in size by at most one.
+pub fn shard_ranges(n_polys: usize, n_shards: usize) -> Result<Vec<Range<usize>>, Error> {
+    if n_shards == 0 || n_shards > n_polys {
+        return Err(Error::InvalidShardCount { n_shards, n_polys });
+    }
+    Ok((0..n_shards)
+        .map(|i| i * n_polys / n_shards..(i + 1) * n_polys / n_shards)
+        .collect())
+}
+
+/// The transcript shard `shard` of `n_shards` is opened and verified with, forked from `base`
+pub(crate) fn shard_transcript(base: &Transcript, shard: usize, n_shards: usize) -> Transcript {
+    let mut transcript = base.clone();
+    transcript::append_u64(&mut transcript, b"shard count", n_shards as u64);
+    transcript::append_u64(&mut transcript, b"shard index", shard as u64);
+    transcript
+}
+
+/// Checks that each polynomial evaluates to its evals at the points. The quotient computed while
+/// opening throws away the remainder, so wrong evals would otherwise make a proof that just fails
+/// to verify.
+pub(crate) fn check_evals_consistent<F: Field>(
+    evals: &(impl EvalMatrix<F> + ?Sized),
+    polys: &[impl AsRef<[F]>],
+    points: &[F],
+) -> Result<(), Error> {
+    for (poly_index, poly) in polys.iter().enumerate() {
+        let mismatch = points.iter().enumerate().position(|(j, x)| {
+            // Horner's rule
+            let ev = poly
+                .as_ref()
+                .iter()
+                .rev()
+                .fold(F::zero(), |acc, c| acc * x + c);
+            ev != *evals.eval(poly_index, j)
+        });
+        if let Some(point_index) = mismatch {
+            return Err(Error::InconsistentEvals {
+                poly_index,
+                point_index,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `evals[i][j]` is the polynomial with coefficients `coeffs[i]` evaluated at
+/// `points[j]`, erroring with [`Error::InconsistentEvals`] on the first that isn't. This is the
+/// check `open` does with the `debug-checks` feature, for callers that want it without the
+/// feature. Errors if the sizes don't match, like `open`.
+pub fn check_evals_match_coeffs<F: Field>(
+    coeffs: &[impl AsRef<[F]>],
+    points: &[F],
+    evals: &[impl AsRef<[F]>],
+) -> Result<(), Error> {
+    check_opening_sizes(evals, coeffs, points.len())?;
+    check_evals_consistent(evals, coeffs, points)
+}
+
 /// This macro is used to iterate over a slice in parallel if the `parallel` feature is enabled.
 #[macro_export]
 macro_rules! cfg_iter {
@@ -415,3 +1648,493 @@ macro_rules! cfg_iter {
         result
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        gen_powers,
+        method2::M2NoPrecomp,
+        msm::ArkMSMEngine,
+        test_rng,
+        traits::{AsBytes, Committer, MSMEngine},
+        Commitment, Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::PrimeField;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+
+    #[test]
+    fn test_transcribe_generic_into_matches() {
+        use super::{transcribe_generic, transcribe_generic_into};
+        use ark_serialize::{CanonicalSerialize, Compress};
+        use ark_std::UniformRand;
+        use merlin::Transcript;
+
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..20)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+                    .0
+            })
+            .collect::<Vec<_>>();
+        let scalars = (0..3)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+
+        let mut fresh = Transcript::new(b"testing");
+        let mut reused = Transcript::new(b"testing");
+        // What transcribe_generic absorbed before it took a scratch buffer
+        let mut original = Transcript::new(b"testing");
+        let mut scratch = vec![0xffu8; 7];
+        for c in &commits {
+            transcribe_generic(&mut fresh, b"commit", c).unwrap();
+            transcribe_generic_into(&mut reused, b"commit", c, &mut scratch).unwrap();
+            let mut buf = vec![0u8; c.serialized_size(Compress::Yes)];
+            c.serialize_compressed(&mut buf).unwrap();
+            original.append_message(b"commit", &buf);
+        }
+        // Elements of a different size through the same buffer
+        transcribe_generic(&mut fresh, b"scalars", &scalars).unwrap();
+        transcribe_generic_into(&mut reused, b"scalars", &scalars, &mut scratch).unwrap();
+        let mut buf = vec![0u8; scalars.serialized_size(Compress::Yes)];
+        scalars.serialize_compressed(&mut buf).unwrap();
+        original.append_message(b"scalars", &buf);
+
+        let challenge = |t: &mut Transcript| {
+            let mut out = [0u8; 32];
+            t.challenge_bytes(b"check", &mut out);
+            out
+        };
+        let expected = challenge(&mut original);
+        assert_eq!(expected, challenge(&mut fresh));
+        assert_eq!(expected, challenge(&mut reused));
+    }
+
+    #[test]
+    fn test_commitment_is_copy() {
+        fn take(c: Commitment<Bls12_381>) -> Commitment<Bls12_381> {
+            c
+        }
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let poly = DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs;
+        let commit = s.commit(&poly).expect("Commit failed");
+        let moved = take(commit);
+        assert_eq!(commit.0, moved.0);
+    }
+
+    #[test]
+    fn test_commitment_display() {
+        let g = Commitment::<Bls12_381>(ark_bls12_381::G1Affine::generator());
+        assert_eq!("Commitment(0x97f1d3a73197d794…)", g.to_string());
+    }
+
+    #[test]
+    fn test_fold_commitments() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+            .map(|p| s.commit(&p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let gamma = Fr::from(7u64);
+        let folded = Commitment::fold_commitments(&commits, gamma).expect("Fold failed");
+
+        let cms_prep = ArkMSMEngine::<Bls12_381>::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let expected =
+            ArkMSMEngine::<Bls12_381>::multi_scalar_mul_g1(&cms_prep, gen_powers(gamma, 5))
+                .unwrap();
+        assert_eq!(folded.0, expected.into_affine());
+        assert_eq!(
+            Err(Error::NoPolynomialsGiven),
+            Commitment::<Bls12_381>::fold_commitments(&[], gamma).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_extend_commitments_beyond_two_adicity() {
+        use ark_ff::FftField;
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..4)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let max_size = 1usize << Fr::TWO_ADICITY;
+        // Fr also has a mixed radix domain with a factor of 3, so go just past that
+        let size = 3 * max_size + 1;
+        assert_eq!(
+            Err(Error::DomainConstructionFailed {
+                size,
+                nearest_supported: max_size,
+                two_adicity: Fr::TWO_ADICITY,
+            }),
+            Commitment::extend_commitments(&commits, size).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_extend_commitments_affine_fft() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..16)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (n, output_size) in [(16, 64), (16, 16), (5, 16), (1, 4)] {
+            assert_eq!(
+                Commitment::extend_commitments(&commits[..n], output_size),
+                Commitment::extend_commitments_affine_fft(&commits[..n], output_size)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cached_domain_matches_fresh() {
+        use crate::{poly_ops::KzgDomain, traits::PolyMultiProofNoPrecomp};
+        use ark_ff::Field;
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+        use merlin::Transcript;
+
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let domain = KzgDomain::<Fr>::new(4).unwrap();
+        let domain_ext = KzgDomain::<Fr>::new(16).unwrap();
+        let fresh = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        assert_eq!(fresh.elements().collect::<Vec<_>>(), domain_ext.elements());
+        for (x, x_inv) in domain_ext.elements().iter().zip(domain_ext.inv_elements()) {
+            assert_eq!(x.inverse().unwrap(), *x_inv);
+        }
+
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(31, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(&p.coeffs).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Commitment::extend_commitments(&commits, 16),
+            Commitment::extend_commitments_in(&commits, &domain, &domain_ext)
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 4,
+                got: 3
+            }),
+            Commitment::extend_commitments_in(&commits[..3], &domain, &domain_ext)
+        );
+
+        let indices = [1, 6, 11];
+        let evals = polys
+            .iter()
+            .map(|p| {
+                indices
+                    .iter()
+                    .map(|&i| p.evaluate(&fresh.element(i)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let cached = s
+            .open_on_domain(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &domain_ext,
+                &indices,
+            )
+            .unwrap();
+        let by_indices = s
+            .open_by_indices(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &fresh,
+                &indices,
+            )
+            .unwrap();
+        assert_eq!((cached.0, cached.1), (by_indices.0, by_indices.1));
+        assert_eq!(
+            Ok(true),
+            s.verify_on_domain(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &domain_ext,
+                &indices,
+                &evals,
+                &by_indices
+            )
+        );
+        assert_eq!(
+            Err(Error::IndexOutOfDomain {
+                idx: 16,
+                domain_size: 16
+            }),
+            domain_ext.points(&[0, 16])
+        );
+    }
+
+    #[test]
+    fn test_recover_commitment_from_partial() {
+        use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..4)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let extended = Commitment::extend_commitments(&commits, 8).unwrap();
+        let domain = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+
+        // Erase half of them
+        let known = [7, 2, 5, 0]
+            .into_iter()
+            .map(|i| (i, extended[i]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(extended.clone()),
+            Commitment::recover_commitment_from_partial(&known, 4, &domain)
+        );
+
+        assert_eq!(
+            Err(Error::NotEnoughSamples {
+                n_samples: 3,
+                needed: 4
+            }),
+            Commitment::recover_commitment_from_partial(&known[..3], 4, &domain)
+        );
+        let repeated = [known[0], known[1], known[2], known[0]];
+        assert_eq!(
+            Err(Error::InvalidSampleIndex {
+                idx: 7,
+                domain_size: 8
+            }),
+            Commitment::recover_commitment_from_partial(&repeated, 4, &domain)
+        );
+        let out_of_range = [known[0], known[1], known[2], (8, extended[0])];
+        assert_eq!(
+            Err(Error::InvalidSampleIndex {
+                idx: 8,
+                domain_size: 8
+            }),
+            Commitment::recover_commitment_from_partial(&out_of_range, 4, &domain)
+        );
+    }
+
+    #[test]
+    fn test_verify_extension() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..4)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let extended = Commitment::extend_commitments(&commits, 16).unwrap();
+        assert_eq!(Ok(true), Commitment::verify_extension(&commits, &extended));
+        assert_eq!(Ok(true), Commitment::verify_extension(&commits, &commits));
+        // The originals are every fourth entry
+        for (i, c) in commits.iter().enumerate() {
+            assert_eq!(*c, extended[4 * i]);
+        }
+
+        // Tampering with an extension entry raises the degree
+        let mut tampered = extended.clone();
+        tampered[3] = commits[0];
+        assert_eq!(Ok(false), Commitment::verify_extension(&commits, &tampered));
+        // A valid extension of different commitments
+        let mut others = commits.clone();
+        others.swap(0, 1);
+        assert_eq!(Ok(false), Commitment::verify_extension(&others, &extended));
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 16,
+                got: 15
+            }),
+            Commitment::verify_extension(&commits, &extended[..15])
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_commitments() {
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        for n_data in [8, 6] {
+            let data = (0..n_data)
+                .map(|_| {
+                    s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            let encoded = Commitment::encode_commitments(&data, 4).unwrap();
+            assert_eq!(32, encoded.len());
+
+            // Keep only 8 of the 32, none of which are data commitments
+            let mut received = vec![None; 32];
+            for i in [1, 3, 6, 10, 17, 23, 29, 31] {
+                received[i] = Some(encoded[i]);
+            }
+            assert_eq!(
+                Ok(data.clone()),
+                Commitment::decode_commitments(&received, n_data)
+            );
+
+            received[31] = None;
+            assert_eq!(
+                Err(Error::NotEnoughSamples {
+                    n_samples: 7,
+                    needed: 8
+                }),
+                Commitment::decode_commitments(&received, n_data)
+            );
+        }
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 32,
+                got: 30
+            }),
+            Commitment::<Bls12_381>::decode_commitments(&[None; 30], 8)
+        );
+    }
+
+    #[test]
+    fn test_aggregate_commitment() {
+        use crate::AggregateCommitment;
+        use ark_ff::Zero;
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let commits = (0..5)
+            .map(|_| {
+                s.commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let manual = commits
+            .iter()
+            .fold(ark_bls12_381::G1Projective::zero(), |acc, c| acc + c.0);
+        let agg: AggregateCommitment<Bls12_381> = commits.into_iter().collect();
+        assert_eq!(manual, agg.0);
+        assert_eq!(Commitment(manual.into_affine()), agg.into_commitment());
+
+        let empty: AggregateCommitment<Bls12_381> = core::iter::empty().collect();
+        assert_eq!(
+            Commitment(ark_bls12_381::G1Affine::zero()),
+            empty.into_commitment()
+        );
+    }
+
+    #[test]
+    fn test_zero_challenge_is_redrawn() {
+        let mut draws = 0;
+        let challenge: Fr = crate::draw_nonzero(32, |bytes| {
+            let fill = if draws == 0 { 0 } else { 1 };
+            bytes.iter_mut().for_each(|b| *b = fill);
+            draws += 1;
+        });
+        assert_eq!(2, draws);
+        assert_eq!(Fr::from_be_bytes_mod_order(&[1u8; 32]), challenge);
+    }
+
+    #[test]
+    fn test_get_challenge_bits() {
+        use crate::{get_challenge, get_challenge_bits};
+        use ark_ff::{BigInteger, Zero};
+        use merlin::Transcript;
+
+        for bits in [1, 7, 8, 9, 64, 128, 254] {
+            let mut transcript = Transcript::new(b"testing");
+            for _ in 0..20 {
+                let c: Fr = get_challenge_bits(&mut transcript, b"c", 32, Some(bits));
+                assert!(!c.is_zero());
+                assert!(
+                    c.into_bigint().num_bits() as usize <= bits,
+                    "bits: {}",
+                    bits
+                );
+            }
+        }
+        // Caps the field size already meets draw the uncapped challenge
+        for bits in [None, Some(256), Some(1000)] {
+            assert_eq!(
+                get_challenge::<Fr>(&mut Transcript::new(b"testing"), b"c", 32),
+                get_challenge_bits::<Fr>(&mut Transcript::new(b"testing"), b"c", 32, bits)
+            );
+        }
+    }
+
+    #[test]
+    fn test_commitment_map_keys() {
+        use std::collections::{BTreeSet, HashSet};
+        let s = M2NoPrecomp::<Bls12_381>::new(32, &mut test_rng());
+        let a = s
+            .commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+            .unwrap();
+        let b = s
+            .commit(DensePolynomial::<Fr>::rand(31, &mut test_rng()).coeffs)
+            .unwrap();
+        // The same point computed differently
+        let a_again = Commitment::<Bls12_381>((a.0.into_group() + b.0 - b.0).into_affine());
+        assert_eq!(a, a_again);
+        assert_ne!(a, b);
+
+        let hashed = [a, b, a_again].into_iter().collect::<HashSet<_>>();
+        assert_eq!(2, hashed.len());
+        let ordered = [a, b, a_again].into_iter().collect::<BTreeSet<_>>();
+        assert_eq!(2, ordered.len());
+        assert_eq!(a < b, a.to_bytes().unwrap() < b.to_bytes().unwrap());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_gen_curve_powers_chunked() {
+        use crate::{gen_curve_powers_proj, gen_curve_powers_proj_chunked};
+        use ark_bls12_381::G1Projective;
+        use ark_std::UniformRand;
+        let powers = gen_powers(Fr::rand(&mut test_rng()), 1000);
+        let base = G1Projective::rand(&mut test_rng());
+        let serial = gen_curve_powers_proj(&powers, base);
+        for chunk_size in [1, 7, 128, 1000, 2000] {
+            assert_eq!(
+                serial,
+                gen_curve_powers_proj_chunked(&powers, base, chunk_size)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_evals_match_coeffs() {
+        use super::check_evals_match_coeffs;
+        use ark_poly::Polynomial;
+        use ark_std::UniformRand;
+
+        let points = (0..6)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(20, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let mut evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        assert_eq!(Ok(()), check_evals_match_coeffs(&coeffs, &points, &evals));
+
+        evals[2][4] += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::InconsistentEvals {
+                poly_index: 2,
+                point_index: 4
+            }),
+            check_evals_match_coeffs(&coeffs, &points, &evals)
+        );
+        assert_eq!(
+            Err(Error::EvalsAndPointsDifferentSizes {
+                n_points: 5,
+                n_evals: 6
+            }),
+            check_evals_match_coeffs(&coeffs, &points[..5], &evals)
+        );
+    }
+}
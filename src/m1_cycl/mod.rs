@@ -4,8 +4,7 @@ use ark_ec::CurveGroup;
 use ark_ff::Zero;
 use core::ops::Deref;
 
-use ark_poly::univariate::DensePolynomial;
-use ark_poly::{DenseUVPolynomial, EvaluationDomain, Radix2EvaluationDomain};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
 use ark_std::vec::Vec;
 use core::ops::Mul;
 use merlin::Transcript;
@@ -14,11 +13,10 @@ use merlin::Transcript;
 use rayon::prelude::*;
 
 use crate::method1::{M1NoPrecomp, Proof};
-use crate::poly_ops::{ev_points, SplitEvalDomain};
-use crate::traits::{Committer, MSMEngine, PolyMultiProof};
+use crate::poly_ops::SplitEvalDomain;
+use crate::traits::{Ceremony, Committer, MSMEngine, PairingEquationProof, PolyMultiProof};
 use crate::{
-    cfg_iter, check_opening_sizes, check_verify_sizes, gen_powers, get_challenge, get_field_size,
-    linear_combination, transcribe_points_and_evals, Commitment, Error,
+    batch::PairingEquation, cfg_iter, check_opening_sizes, check_verify_sizes, Commitment, Error,
 };
 
 /// Method 1 with blst optimization and precomputed lagrange polynomials/vanishing polys
@@ -44,14 +42,18 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1CyclPrecomp<E, M> {
         base_size: usize,
         num_point_sets: usize,
     ) -> Result<Self, Error> {
-        if !is_power_of_two(base_size) || !is_power_of_two(num_point_sets) {
-            todo!() // return error
+        if !is_power_of_two(base_size) || inner.powers_of_g1.len() < base_size {
+            return Err(Error::domain_construction_failed::<E::ScalarField>(
+                base_size,
+            ));
         }
-        if inner.powers_of_g1.len() < base_size {
-            return Err(Error::DomainConstructionFailed(base_size));
+        if !is_power_of_two(num_point_sets) {
+            return Err(Error::domain_construction_failed::<E::ScalarField>(
+                num_point_sets,
+            ));
         }
         let split_domain = SplitEvalDomain::<E::ScalarField>::new(base_size, num_point_sets)
-            .ok_or(Error::DomainConstructionFailed(inner.powers_of_g1.len()))?;
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(base_size))?;
         let point_set_groups = split_domain.subgroups();
         let vanishing_polys: Vec<_> = cfg_iter!(point_set_groups)
             .map(|(_, sg)| sg.vanishing_polynomial())
@@ -92,10 +94,24 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1CyclPrecomp<E, M> {
     }
 }
 
+impl<E: Pairing, M: MSMEngine<E = E>> Ceremony<E> for M1CyclPrecomp<E, M> {
+    fn g2_tau(&self) -> Result<E::G2Affine, Error> {
+        self.inner.g2_tau()
+    }
+}
+
 impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1CyclPrecomp<E, M> {
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
         self.inner.commit(poly)
     }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        self.inner.powers_of_g1()
+    }
+
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M> {
@@ -110,33 +126,12 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M>
     ) -> Result<Self::Proof, Error> {
         check_opening_sizes(evals, polys, self.base_size / self.num_point_sets)?;
 
-        // Commit the evals and the points to the transcript
         // TODO: better error
         let subgroup = self
             .point_set_groups
             .get(point_set_index)
             .ok_or(Error::NoPointsGiven)?;
-        let points = ev_points(subgroup);
-        let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, &points, evals, field_size_bytes)?;
-
-        // Read the challenge
-        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
-        // Make the gamma powers
-        let gammas = gen_powers::<E::ScalarField>(gamma, self.inner.powers_of_g1.len());
-        // Take a linear combo of gammas with the polynomials
-        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
-            .ok_or(Error::NoPolynomialsGiven)?;
-
-        // Polynomial divide, the remained would contain the gamma * ri_s,
-        // The result is the correct quotient
-        let (q, _) = DensePolynomial::from_coefficients_vec(fsum)
-            .divide_by_vanishing_poly(*subgroup)
-            .expect("This always succeeds");
-        // Open to the resulting polynomial
-        Ok(Proof(
-            M::multi_scalar_mul_g1(&self.inner.g1_precomp, &q.deref())?.into_affine(),
-        ))
+        self.inner.open_on_coset(transcript, evals, polys, subgroup)
     }
 
     fn verify(
@@ -147,37 +142,36 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1CyclPrecomp<E, M>
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error> {
+        Ok(self
+            .verify_equation(transcript, commits, point_set_index, evals, proof)?
+            .check::<M>())
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> PairingEquationProof<E> for M1CyclPrecomp<E, M> {
+    fn verify_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<PairingEquation<E>, Error> {
         check_verify_sizes(commits, evals, self.base_size / self.num_point_sets)?;
 
-        let field_size_bytes = get_field_size::<E::ScalarField>();
         // TODO: better error
         let subgroup = self
             .point_set_groups
             .get(point_set_index)
             .ok_or(Error::NoPointsGiven)?;
-        let points = ev_points(subgroup);
-        transcribe_points_and_evals(transcript, &points, evals, field_size_bytes)?;
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        // Aggregate the r_is and then do a single msm of just the ri's and gammas
-        let gammas = gen_powers(gamma, evals.len());
-
-        // We first get the values of sum_i gamma^i-1 r_i,j (z_j)
-        let mut gamma_ris = linear_combination(&evals, &gammas).expect("TODO");
-        // Then we find the coefficients
-        subgroup.ifft_in_place(&mut gamma_ris);
-        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.inner.g1_precomp, &gamma_ris)?;
-
-        // Then do a single msm of the gammas and commitments
-        let cms_prep = M::prepare_g1(commits.into_iter().map(|i| i.0).collect());
-        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, &gammas)?;
-
-        let g2 = self.inner.powers_of_g2[0];
-
-        let lhsg1 = (gamma_cm_pt - gamma_ris_pt).into_affine();
-        let lhsg2 = g2;
-        let rhsg1 = proof.0;
-        let rhsg2 = self.g2_zeros[point_set_index];
-        Ok(M::pairing_eq_check(lhsg1, lhsg2, rhsg1, rhsg2))
+        self.inner.equation_on_coset(
+            transcript,
+            commits,
+            evals,
+            proof,
+            subgroup,
+            self.g2_zeros[point_set_index],
+        )
     }
 }
 
@@ -255,4 +249,42 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_high_degree_open_works() {
+        // Subgroups of size 8 with degree 255 polynomials, so the division by the coset
+        // vanishing polynomial isn't just a shift
+        let s = <M1NoPrecomp<Bls12_381, ArkMSMEngine<Bls12_381>>>::new(256, 256, &mut test_rng());
+        let s = M1CyclPrecomp::from_inner(s, 256, 32).expect("Failed to construct");
+        let polys = (0..2)
+            .map(|_| DensePolynomial::<Fr>::rand(255, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| s.split_domain.base().fft(p))
+            .collect::<Vec<_>>();
+        for gi in [0, 1, 31] {
+            let trimmed_evals: Vec<_> = evals
+                .iter()
+                .map(|ev| s.split_domain.take_subgroup_indices(gi, ev).unwrap())
+                .collect();
+            let proof = s
+                .open(&mut Transcript::new(b"test"), &trimmed_evals, &polys, gi)
+                .expect("Failed to open");
+            assert_eq!(
+                Ok(true),
+                s.verify(
+                    &mut Transcript::new(b"test"),
+                    &commits,
+                    gi,
+                    &trimmed_evals,
+                    &proof
+                )
+            );
+        }
+    }
 }
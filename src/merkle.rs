@@ -0,0 +1,143 @@
+//! # Merkle roots over evals
+//! Computes a Merkle root over the evals of an opening, so a proof can be bound to evals that
+//! were also committed to in a Merkle tree elsewhere, see
+//! [`PolyMultiProofNoPrecomp::verify_with_eval_root`](crate::traits::PolyMultiProofNoPrecomp::verify_with_eval_root).
+//!
+//! The leaves are the compressed serializations of each eval, row by row: every eval of the
+//! first polynomial, then every eval of the second, and so on. Each level hashes adjacent pairs
+//! of nodes, and an odd node out at the end of a level is carried up unchanged.
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::Error;
+
+/// The hash function a Merkle tree is built with
+pub trait MerkleHasher {
+    /// Hashes the serialized bytes of a leaf
+    fn hash_leaf(&self, leaf: &[u8]) -> [u8; 32];
+    /// Hashes two child nodes into their parent
+    fn hash_node(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32];
+}
+
+/// A [`MerkleHasher`] built on merlin's STROBE hash. This isn't a standard hash, so trees built
+/// elsewhere will usually need their own hasher.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MerlinHasher;
+
+impl MerkleHasher for MerlinHasher {
+    fn hash_leaf(&self, leaf: &[u8]) -> [u8; 32] {
+        let mut transcript = Transcript::new(b"poly-multiproof merkle leaf");
+        transcript.append_message(b"leaf", leaf);
+        let mut out = [0u8; 32];
+        transcript.challenge_bytes(b"hash", &mut out);
+        out
+    }
+
+    fn hash_node(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut transcript = Transcript::new(b"poly-multiproof merkle node");
+        transcript.append_message(b"left", left);
+        transcript.append_message(b"right", right);
+        let mut out = [0u8; 32];
+        transcript.challenge_bytes(b"hash", &mut out);
+        out
+    }
+}
+
+/// Computes the Merkle root over `evals` with `hasher`. Errors if there are no evals.
+pub fn eval_root<F: CanonicalSerialize>(
+    hasher: &impl MerkleHasher,
+    evals: &[impl AsRef<[F]>],
+) -> Result<[u8; 32], Error> {
+    let mut bytes = Vec::new();
+    let mut level = Vec::new();
+    for eval in evals.iter().flat_map(|row| row.as_ref()) {
+        bytes.clear();
+        eval.serialize_compressed(&mut bytes)?;
+        level.push(hasher.hash_leaf(&bytes));
+    }
+    if level.is_empty() {
+        return Err(Error::NoPolynomialsGiven);
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hasher.hash_node(left, right),
+                [odd] => *odd,
+                _ => unreachable!("chunks of 2"),
+            })
+            .collect();
+    }
+    Ok(level[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eval_root, MerkleHasher, MerlinHasher};
+    use crate::{
+        method2::M2NoPrecomp, test_rng, testing::random_valid_opening,
+        traits::PolyMultiProofNoPrecomp, Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::One;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::{vec, vec::Vec};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_verify_with_eval_root() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let (commits, points, evals, open) = random_valid_opening(&s, 3, 3, 30, &mut test_rng());
+        let root = eval_root(&MerlinHasher, &evals).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_with_eval_root(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+                root,
+                &MerlinHasher
+            )
+        );
+
+        let mut bad_root = root;
+        bad_root[0] ^= 1;
+        assert_eq!(
+            Err(Error::EvalRootMismatch),
+            s.verify_with_eval_root(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+                bad_root,
+                &MerlinHasher
+            )
+        );
+
+        // The root binds every eval
+        let mut other_evals = evals.clone();
+        other_evals[2][1] += Fr::one();
+        assert_ne!(root, eval_root(&MerlinHasher, &other_evals).unwrap());
+    }
+
+    #[test]
+    fn test_eval_root_shape() {
+        let evals = [vec![Fr::from(1u64), Fr::from(2u64)], vec![Fr::from(3u64)]];
+        let h = MerlinHasher;
+        let leaf = |x: u64| {
+            let mut bytes = Vec::new();
+            Fr::from(x).serialize_compressed(&mut bytes).unwrap();
+            h.hash_leaf(&bytes)
+        };
+        let expected = h.hash_node(&h.hash_node(&leaf(1), &leaf(2)), &leaf(3));
+        assert_eq!(Ok(expected), eval_root(&h, &evals));
+        assert_eq!(
+            Err(Error::NoPolynomialsGiven),
+            eval_root::<Fr>(&h, &[] as &[&[Fr]])
+        );
+    }
+}
@@ -1,27 +1,43 @@
 //! # BDFG Method 1
 //! This contains a pure ark implementation of BDFG21 method 1
 use crate::{
-    check_opening_sizes, check_verify_sizes,
+    batch::PairingEquation,
+    check_opening_sizes, check_poly_sizes, check_verify_sizes,
     lagrange::LagrangeInterpContext,
-    traits::{Committer, MSMEngine, PolyMultiProofNoPrecomp},
+    poly_ops::{div_by_binomial, divide_poly_auto, ev_points},
+    traits::{Ceremony, Committer, ConfigurableScheme, MSMEngine, PolyMultiProofNoPrecomp},
 };
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{marker::PhantomData, rand::RngCore, vec::Vec, UniformRand};
+use ark_ff::{FftField, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ark_std::{
+    marker::PhantomData,
+    rand::{CryptoRng, RngCore},
+    vec,
+    vec::Vec,
+    UniformRand,
+};
+use core::fmt;
 use merlin::Transcript;
 
-use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 
-use crate::{get_challenge, get_field_size, transcribe_points_and_evals, Commitment};
+use crate::{
+    fold_terms, get_challenge, get_field_size, supplied_powers, transcribe_generic_as,
+    transcribe_points_and_evals, Commitment, EvalMatrix, FoldTerms, LazyCommitment, PointMajor,
+    PreparedVerify, SchemeConfig, SchemeTag, VerifyCost,
+};
 
 use super::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
+    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, powers_iter,
+    vanishing_polynomial, Error,
 };
 
 pub mod precompute;
+pub mod stream;
 
 /// A method 1 proof scheme with no precomputation of lagrange polynomials
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct M1NoPrecomp<E: Pairing, M: MSMEngine<E = E>> {
     /// The given powers tau in G1
     pub powers_of_g1: Vec<E::G1Affine>,
@@ -33,22 +49,70 @@ pub struct M1NoPrecomp<E: Pairing, M: MSMEngine<E = E>> {
     pub(crate) g1_precomp: M::G1Prepared,
     pub(crate) g2_precomp: M::G2Prepared,
 
+    /// The settings for opening and verifying, see [`ConfigurableScheme`]
+    pub config: SchemeConfig,
+
     _marker: PhantomData<M>,
 }
 
+// Written out since `Compress` isn't `Debug`
+impl<E: Pairing, M: MSMEngine<E = E> + fmt::Debug> fmt::Debug for M1NoPrecomp<E, M>
+where
+    M::G1Prepared: fmt::Debug,
+    M::G2Prepared: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("M1NoPrecomp")
+            .field("powers_of_g1", &self.powers_of_g1)
+            .field("powers_of_g2", &self.powers_of_g2)
+            .field("g1_precomp", &self.g1_precomp)
+            .field("g2_precomp", &self.g2_precomp)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
 /// A method 1 proof
 #[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<E: Pairing>(pub E::G1Affine);
 
+/// A method 1 proof which also bounds the degree of polynomials, either the opened ones or, for
+/// [`M1NoPrecomp::open_interpolant_commit`], their interpolants
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DegreeCapProof<E: Pairing> {
+    /// The multipoint opening
+    pub open: Proof<E>,
+    /// A commitment to the combined polynomials shifted up so that they only fit in the setup if
+    /// their degree is at most the cap
+    pub shifted: E::G1Affine,
+}
+
 impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
-    /// Make a new random scheme
-    pub fn new(max_coeffs: usize, max_pts: usize, rng: &mut impl RngCore) -> Self {
+    /// Make a new random scheme. The secret is sampled from the given RNG, which doesn't require
+    /// `std`.
+    pub fn new(max_coeffs: usize, max_pts: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self {
         let x = E::ScalarField::rand(rng);
         let g1 = E::G1::rand(rng);
         let g2 = E::G2::rand(rng);
         Self::new_from_scalar(x, g1, g2, max_coeffs, max_pts)
     }
 
+    /// Make a new scheme whose powers of tau in G1 are multiples of `base`, e.g. a
+    /// nothing-up-my-sleeve point from hashing to the curve, rather than a random point. The
+    /// secret and the G2 generator are sampled from the given RNG. Verification checks
+    /// commitments against the powers of tau in G2 from the same secret, so proofs only verify
+    /// with the scheme made here, not with one sharing `base` but not the secret.
+    pub fn new_with_base(
+        max_coeffs: usize,
+        max_pts: usize,
+        base: E::G1,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Self {
+        let x = E::ScalarField::rand(rng);
+        let g2 = E::G2::rand(rng);
+        Self::new_from_scalar(x, base, g2, max_coeffs, max_pts)
+    }
+
     /// Make a new scheme from a given secret scalar
     pub fn new_from_scalar(
         x: E::ScalarField,
@@ -81,87 +145,811 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1NoPrecomp<E, M> {
             g2_precomp: M::prepare_g2(powers_of_g2.clone()),
             powers_of_g1,
             powers_of_g2,
+            config: SchemeConfig::default(),
             _marker: PhantomData,
         }
     }
 
-    fn open_with_vanishing_poly(
+    /// Make a copy of this scheme that only holds enough powers of tau in G1 to commit to
+    /// polynomials of degree at most `max_degree`. The powers in G2 are kept as-is.
+    pub fn truncate(&self, max_degree: usize) -> Result<Self, Error> {
+        let n_coeffs = match max_degree.checked_add(1) {
+            Some(n) if n <= self.powers_of_g1.len() => n,
+            _ => {
+                return Err(Error::TooManyScalars {
+                    n_coeffs: max_degree.saturating_add(1),
+                    expected_max: self.powers_of_g1.len(),
+                })
+            }
+        };
+        Ok(Self {
+            config: self.config,
+            ..Self::new_from_affine(
+                self.powers_of_g1[..n_coeffs].to_vec(),
+                self.powers_of_g2.clone(),
+            )
+        })
+    }
+
+    /// Estimates the cost of verifying a proof for `n_polys` polynomials at `n_points` points
+    /// with precomputation. Without precomputation, verifying additionally computes the lagrange
+    /// polynomials, the vanishing polynomial, and a G2 MSM of size `n_points + 1`.
+    pub fn verify_cost(n_points: usize, n_polys: usize) -> VerifyCost {
+        VerifyCost {
+            pairings: 2,
+            // One for the interpolated r_i's, one for the commitments
+            g1_msm_size: n_points + n_polys,
+            g2_msm_size: 0,
+            // Gamma powers, folding the evals, and summing the lagrange polynomials
+            field_muls: n_polys + n_polys * n_points + n_points * n_points,
+        }
+    }
+
+    /// Opens the given polynomials at `points` using a precomputed vanishing polynomial for
+    /// the points, given as its coefficients. The vanishing polynomial is trusted to be correct,
+    /// which is only checked in debug builds.
+    pub fn open_with_vanishing(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        vanishing_coeffs: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        let vp = DensePolynomial::from_coefficients_slice(vanishing_coeffs);
+        debug_assert_eq!(vp, vanishing_polynomial(points));
+        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+    }
+
+    /// Returns the polynomial `open` folds `polys` into, $\sum_i \gamma^i f_i$, drawing $\gamma$
+    /// from the transcript exactly as `open` does. Its commitment is the verifier's
+    /// $\sum_i \gamma^i C_i$ for the same transcript. With no points no challenge is drawn and
+    /// the aggregate is the zero polynomial.
+    pub fn aggregate_polynomial(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Vec<E::ScalarField>, Error> {
+        match self.open_challenge(transcript, evals, polys, points)? {
+            Some(gamma) => self.fold_polys(gamma, polys),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Opens the given polynomials at `points`, binding the transcript to commitments to their
+    /// interpolants instead of to their evaluations. Entry `i` of `interpolant_commits` commits
+    /// to the polynomial of degree less than `points.len()` that agrees with `polys[i]` on
+    /// `points`. The proof also bounds the degree of the interpolants as
+    /// [`Self::open_with_degree_cap`] does, since without the bound a commitment to `polys[i]`
+    /// itself would pass as its interpolant. Errors if the setup doesn't have the G2 power to
+    /// check the bound. The proof is checked with [`Self::verify_interpolant_commit`].
+    pub fn open_interpolant_commit(
+        &self,
+        transcript: &mut Transcript,
+        interpolant_commits: &[Commitment<E>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<DegreeCapProof<E>, Error> {
+        self.config.limits.check(polys.len(), points.len())?;
+        if interpolant_commits.len() != polys.len() {
+            return Err(Error::InvalidInputLength {
+                expected: polys.len(),
+                got: interpolant_commits.len(),
+            });
+        }
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        if points.is_empty() {
+            return Ok(DegreeCapProof {
+                open: Proof(E::G1Affine::zero()),
+                shifted: E::G1Affine::zero(),
+            });
+        }
+        let shift = self.degree_cap_shift(points.len() - 1)?;
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_points_and_interpolants(
+            transcript,
+            points,
+            interpolant_commits,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        let vp = vanishing_polynomial(points);
+        let open = self.open_with_gamma(gamma, polys, &vp)?;
+
+        // The interpolants are the remainders by the vanishing polynomial, so their combination
+        // is the remainder of the combined polynomials
+        let chal = get_challenge(transcript, b"interpolant degree gamma", field_size_bytes);
+        let fsum = linear_combination(polys, &gen_powers(chal, polys.len()))
+            .ok_or(Error::NoPolynomialsGiven)?;
+        let (_, rsum) = poly_div_q_r(
+            DensePolynomial::from_coefficients_vec(fsum).into(),
+            (&vp).into(),
+        )?;
+        let mut shifted = vec![E::ScalarField::zero(); shift];
+        shifted.extend(rsum);
+        Ok(DegreeCapProof {
+            open,
+            shifted: self.commit(shifted)?.0,
+        })
+    }
+
+    /// Verifies a proof from [`Self::open_interpolant_commit`] without the evaluations. The
+    /// pairing check uses the commitments $[r_i(\tau)]_1$ to the interpolants directly, rather
+    /// than interpolating them from the evaluations, which is what's needed when the evaluations
+    /// are themselves only known by commitment. The degree bound on the interpolants is checked
+    /// too, and errors like in [`Self::open_interpolant_commit`].
+    pub fn verify_interpolant_commit(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        interpolant_commits: &[Commitment<E>],
+        proof: &DegreeCapProof<E>,
+    ) -> Result<bool, Error> {
+        self.config.limits.check(commits.len(), points.len())?;
+        if interpolant_commits.len() != commits.len() {
+            return Err(Error::InvalidInputLength {
+                expected: commits.len(),
+                got: interpolant_commits.len(),
+            });
+        }
+        self.config.check_commits(commits)?;
+        // With no points every interpolant is the zero polynomial
+        if points.is_empty() {
+            return Ok(proof.open.0.is_zero()
+                && proof.shifted.is_zero()
+                && interpolant_commits.iter().all(|c| c.0.is_zero()));
+        }
+        let shift = self.degree_cap_shift(points.len() - 1)?;
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_points_and_interpolants(
+            transcript,
+            points,
+            interpolant_commits,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        let gammas = gen_powers(gamma, commits.len());
+
+        // Fold the C_i - [r_i(tau)] in a single msm
+        let bases = commits
+            .iter()
+            .chain(interpolant_commits)
+            .map(|c| c.0)
+            .collect::<Vec<_>>();
+        let scalars = gammas
+            .iter()
+            .copied()
+            .chain(gammas.iter().map(|g| -*g))
+            .collect::<Vec<_>>();
+        let lhs = M::multi_scalar_mul_g1(&M::prepare_g1(bases), scalars)?;
+
+        let vp = vanishing_polynomial(points);
+        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
+        if !M::pairing_eq_check(
+            lhs.into_affine(),
+            self.powers_of_g2[0],
+            proof.open.0,
+            g2_zeros.into_affine(),
+        ) {
+            return Ok(false);
+        }
+
+        let chal = get_challenge(transcript, b"interpolant degree gamma", field_size_bytes);
+        let folded = Commitment::fold_commitments(interpolant_commits, chal)?;
+        // e(shifted, [1]) == e(folded, [tau^shift])
+        Ok(M::pairing_eq_check(
+            proof.shifted,
+            self.powers_of_g2[0],
+            folded.0,
+            self.powers_of_g2[shift],
+        ))
+    }
+
+    /// Computes the vanishing polynomial and lagrange polynomials for `points`, then the
+    /// pairing equation `verify` checks and the gamma challenge
+    #[allow(clippy::too_many_arguments)]
+    fn folded_equation<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.limits.check(commits.len(), points.len())?;
+        let vp = vanishing_polynomial(points);
+        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.equation_with_lag_ctx_g2_zeros(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            &lag_ctx,
+            &g2_zeros,
+            gamma_powers,
+        )
+    }
+
+    /// Tags the values this scheme's `prepare_verify` makes
+    fn scheme_tag(&self) -> SchemeTag<E> {
+        SchemeTag::new("method1", &self.powers_of_g1, &self.config)
+    }
+
+    /// Like [`ConfigurableScheme::equation`], for inputs already checked by `prepare_verify`
+    fn prepared_equation(
+        &self,
+        transcript: &mut Transcript,
+        prepared: &PreparedVerify<E>,
+        proof: &Proof<E>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        let vp = vanishing_polynomial(prepared.points);
+        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
+        let lag_ctx = LagrangeInterpContext::new_from_points(prepared.points)?;
+        self.unchecked_equation_with_lag_ctx_g2_zeros(
+            transcript,
+            prepared.commits,
+            prepared.points,
+            &prepared.evals,
+            proof,
+            &lag_ctx,
+            &g2_zeros,
+            None,
+        )
+    }
+
+    /// Opens the polynomials $p(x) + a_i$ for each shift $a_i$ at `points`, returning their evals
+    /// and the proof. The proof is the same as opening each shifted polynomial with `open`, but
+    /// since they share a quotient by the vanishing polynomial it is only computed once.
+    /// Verify with [`Self::verify_constant_shifts`], or with `verify` on the shifted commitments.
+    /// With no points the evals are empty, the proof is the identity, and the transcript is left
+    /// as-is.
+    #[allow(clippy::type_complexity)]
+    pub fn open_constant_shifts(
+        &self,
+        transcript: &mut Transcript,
+        base_coeffs: &[E::ScalarField],
+        shifts: &[E::ScalarField],
+        points: &[E::ScalarField],
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Proof<E>), Error> {
+        if shifts.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        if points.is_empty() {
+            // As with `open`, opening at no points claims nothing
+            return Ok((vec![vec![]; shifts.len()], Proof(E::G1Affine::zero())));
+        }
+        let base = DensePolynomial::from_coefficients_slice(base_coeffs);
+        let base_evals = points.iter().map(|z| base.evaluate(z)).collect::<Vec<_>>();
+        let evals = shifts
+            .iter()
+            .map(|a| base_evals.iter().map(|e| *e + a).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_points_and_evals(
+            transcript,
+            points,
+            &evals[..],
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        let gamma_sum: E::ScalarField = powers_iter(gamma, shifts.len()).sum();
+
+        // The constant shifts only change the remainder, so every quotient is the base one
+        let (q, _) = poly_div_q_r(base.into(), vanishing_polynomial(points).into())?;
+        let q_pt = M::multi_scalar_mul_g1(&self.g1_precomp, &q)?;
+        Ok((evals, Proof((q_pt * gamma_sum).into_affine())))
+    }
+
+    /// Verifies a proof made with [`Self::open_constant_shifts`] by shifting `base_commit` by
+    /// $a_i [1]_1$ for each shift
+    pub fn verify_constant_shifts(
+        &self,
+        transcript: &mut Transcript,
+        base_commit: &Commitment<E>,
+        shifts: &[E::ScalarField],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let commits = shifts
+            .iter()
+            .map(|a| base_commit.0 + self.powers_of_g1[0] * a)
+            .collect::<Vec<_>>();
+        let commits = E::G1::normalize_batch(&commits)
+            .into_iter()
+            .map(Commitment)
+            .collect::<Vec<_>>();
+        self.verify(transcript, &commits, points, evals, proof)
+    }
+
+    /// Opens the given polynomials at the points of the coset `coset_shift * domain` with the
+    /// given `indices`, i.e. at $h \omega^i$ for each index $i$. The proof is the same as opening
+    /// at those points explicitly. When the indices are the whole coset in order this uses the
+    /// sparse vanishing polynomial $x^n - h^n$ of the coset, otherwise it falls back to the
+    /// vanishing polynomial of the chosen points.
+    pub fn open_coset<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        domain: &D,
+        coset_shift: E::ScalarField,
+        indices: &[usize],
+    ) -> Result<Proof<E>, Error> {
+        self.config.limits.check(polys.len(), indices.len())?;
+        let coset = domain
+            .get_coset(coset_shift)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(domain.size()))?;
+        if is_whole_domain(&coset, indices) {
+            self.open_on_coset(transcript, evals, polys, &coset)
+        } else {
+            self.open_by_indices(transcript, evals, polys, &coset, indices)
+        }
+    }
+
+    /// Verifies a proof made with [`Self::open_coset`], or equivalently an opening at the
+    /// points of the coset `coset_shift * domain` with the given `indices`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_coset<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        domain: &D,
+        coset_shift: E::ScalarField,
+        indices: &[usize],
+    ) -> Result<bool, Error> {
+        self.config.limits.check(commits.len(), indices.len())?;
+        let coset = domain
+            .get_coset(coset_shift)
+            .ok_or_else(|| Error::domain_construction_failed::<E::ScalarField>(domain.size()))?;
+        if is_whole_domain(&coset, indices) {
+            let g2_zeros = self.coset_g2_zeros(&coset)?;
+            self.verify_on_coset(transcript, commits, evals, proof, &coset, g2_zeros)
+        } else {
+            self.verify_by_indices(transcript, commits, &coset, indices, evals, proof)
+        }
+    }
+
+    /// How far polynomials of degree `max_degree` are shifted up so that they fill the setup.
+    /// Errors if there aren't enough powers of tau in G2 to check the shift, or if a polynomial
+    /// of degree `max_degree` has too many coefficients to count.
+    fn degree_cap_shift(&self, max_degree: usize) -> Result<usize, Error> {
+        let n_coeffs = max_degree.checked_add(1).ok_or(Error::TooManyScalars {
+            n_coeffs: usize::MAX,
+            expected_max: self.powers_of_g1.len(),
+        })?;
+        let shift = self.powers_of_g1.len().saturating_sub(n_coeffs);
+        if shift >= self.powers_of_g2.len() {
+            return Err(Error::TooManyScalars {
+                n_coeffs: shift + 1,
+                expected_max: self.powers_of_g2.len(),
+            });
+        }
+        Ok(shift)
+    }
+
+    /// Opens the polynomials at `points` like `open`, and also proves they all have degree at
+    /// most `max_degree`. The cap is bound to the transcript. Errors if a polynomial is over the
+    /// cap, if `max_degree` is so small that the setup doesn't have the G2 power to check it, or
+    /// if it's `usize::MAX`, whose polynomials have more coefficients than a `usize` counts.
+    pub fn open_with_degree_cap(
         &self,
         transcript: &mut Transcript,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
+        max_degree: usize,
+    ) -> Result<DegreeCapProof<E>, Error> {
+        let shift = self.degree_cap_shift(max_degree)?;
+        crate::transcript::append_u64(transcript, b"degree cap", max_degree as u64);
+        let open = self.open(transcript, evals, polys, points)?;
+
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        let chal = get_challenge(transcript, b"degree cap gamma", field_size_bytes);
+        let fsum = linear_combination(polys, &gen_powers(chal, polys.len()))
+            .ok_or(Error::NoPolynomialsGiven)?;
+        let mut shifted = vec![E::ScalarField::zero(); shift];
+        shifted.extend(fsum);
+        Ok(DegreeCapProof {
+            open,
+            shifted: self.commit(shifted)?.0,
+        })
+    }
+
+    /// Verifies a proof made with [`Self::open_with_degree_cap`]
+    pub fn verify_with_degree_cap(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        max_degree: usize,
+        proof: &DegreeCapProof<E>,
+    ) -> Result<bool, Error> {
+        let shift = self.degree_cap_shift(max_degree)?;
+        crate::transcript::append_u64(transcript, b"degree cap", max_degree as u64);
+        if !self.verify(transcript, commits, points, evals, &proof.open)? {
+            return Ok(false);
+        }
+
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        let chal = get_challenge(transcript, b"degree cap gamma", field_size_bytes);
+        let folded = Commitment::fold_commitments(commits, chal)?;
+        // e(shifted, [1]) == e(folded, [tau^shift])
+        Ok(M::pairing_eq_check(
+            proof.shifted,
+            self.powers_of_g2[0],
+            folded.0,
+            self.powers_of_g2[shift],
+        ))
+    }
+
+    /// Computes $[\tau^n - h^n]_2$, the commitment to the vanishing polynomial of the coset
+    pub(crate) fn coset_g2_zeros<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        coset: &D,
+    ) -> Result<E::G2Affine, Error> {
+        let n = coset.size();
+        let g2_n = self.powers_of_g2.get(n).ok_or(Error::TooManyScalars {
+            n_coeffs: n + 1,
+            expected_max: self.powers_of_g2.len(),
+        })?;
+        Ok(
+            (g2_n.into_group() - self.powers_of_g2[0] * coset.coset_offset_pow_size())
+                .into_affine(),
+        )
+    }
+
+    pub(crate) fn open_on_coset<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        coset: &D,
+    ) -> Result<Proof<E>, Error> {
+        self.config.limits.check(polys.len(), coset.size())?;
+        check_opening_sizes(evals, polys, coset.size())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        // Commit the evals and the points to the transcript
+        let points = ev_points(coset);
+        #[cfg(feature = "debug-checks")]
+        crate::check_evals_consistent(evals, polys, &points)?;
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_points_and_evals(
+            transcript,
+            &points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
+
+        // Read the challenge
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        // Make the gamma powers
+        let gammas = gen_powers::<E::ScalarField>(gamma, polys.len());
+        // Take a linear combo of gammas with the polynomials
+        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
+            .ok_or(Error::NoPolynomialsGiven)?;
+
+        // Divide by the sparse vanishing polynomial x^n - h^n, the quotient is the witness
+        let (q, _) = div_by_binomial(
+            &DensePolynomial::from_coefficients_vec(fsum),
+            coset.size(),
+            coset.coset_offset_pow_size(),
+        );
+        // Open to the resulting polynomial
+        Ok(Proof(
+            M::multi_scalar_mul_g1(&self.g1_precomp, &q.coeffs)?.into_affine(),
+        ))
+    }
+
+    pub(crate) fn verify_on_coset<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        coset: &D,
+        g2_zeros: E::G2Affine,
+    ) -> Result<bool, Error> {
+        Ok(self
+            .equation_on_coset(transcript, commits, evals, proof, coset, g2_zeros)?
+            .check::<M>())
+    }
+
+    /// Does all of [`Self::verify_on_coset`] but the pairing check, returning the equation
+    pub(crate) fn equation_on_coset<D: EvaluationDomain<E::ScalarField>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        coset: &D,
+        g2_zeros: E::G2Affine,
+    ) -> Result<PairingEquation<E>, Error> {
+        self.config.limits.check(commits.len(), coset.size())?;
+        check_verify_sizes(commits, evals, coset.size())?;
+        self.config.check_commits(commits)?;
+
+        let points = ev_points(coset);
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_points_and_evals(
+            transcript,
+            &points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        let gammas = gen_powers(gamma, evals.len());
+
+        // We first get the values of sum_i gamma^i-1 r_i,j (z_j)
+        let mut gamma_ris = linear_combination(evals, &gammas).ok_or(Error::NoPolynomialsGiven)?;
+        // Then we find the coefficients
+        coset.ifft_in_place(&mut gamma_ris);
+        let gamma_ris_pt = M::multi_scalar_mul_g1(&self.g1_precomp, &gamma_ris)?;
+
+        // Then do a single msm of the gammas and commitments
+        let cms_prep = M::prepare_g1(commits.iter().map(|i| i.0).collect());
+        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, &gammas)?;
+
+        Ok(PairingEquation {
+            lhs: (
+                (gamma_cm_pt - gamma_ris_pt).into_affine(),
+                self.powers_of_g2[0],
+            ),
+            rhs: (proof.0, g2_zeros),
+        })
+    }
+
+    fn open_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        evals: &(impl EvalMatrix<E::ScalarField> + ?Sized),
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<Proof<E>, Error> {
+        match self.open_challenge(transcript, evals, polys, points)? {
+            Some(gamma) => self.open_with_gamma(gamma, polys, vp),
+            // Opening at no points claims nothing, so the proof is the identity
+            None => Ok(Proof(E::G1Affine::zero())),
+        }
+    }
+
+    /// Checks the sizes of an opening and draws its gamma challenge, or returns `None` without
+    /// touching the transcript when there are no points
+    fn open_challenge(
+        &self,
+        transcript: &mut Transcript,
+        evals: &(impl EvalMatrix<E::ScalarField> + ?Sized),
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Option<E::ScalarField>, Error> {
         // Check sizes
+        self.config.limits.check(polys.len(), points.len())?;
         check_opening_sizes(evals, polys, points.len())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        #[cfg(feature = "debug-checks")]
+        crate::check_evals_consistent(evals, polys, points)?;
+        if points.is_empty() {
+            return Ok(None);
+        }
         // Commit the evals and the points to the transcript
         let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
+        transcribe_points_and_evals(
+            transcript,
+            points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
 
         // Read the challenge
-        let gamma = get_challenge::<E::ScalarField>(transcript, b"open gamma", field_size_bytes);
+        Ok(Some(
+            self.config.gamma_challenge(transcript, field_size_bytes),
+        ))
+    }
+
+    /// Takes the linear combination of `polys` with powers of `gamma`
+    fn fold_polys(
+        &self,
+        gamma: E::ScalarField,
+        polys: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Vec<E::ScalarField>, Error> {
         // Make the gamma powers
         let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
         // Take a linear combo of gammas with the polynomials
-        let fsum = linear_combination::<E::ScalarField>(polys, &gammas)
-            .ok_or(Error::NoPolynomialsGiven)?;
+        linear_combination::<E::ScalarField>(polys, &gammas).ok_or(Error::NoPolynomialsGiven)
+    }
+
+    /// Folds `polys` with powers of `gamma` and opens the result at the roots of `vp`
+    fn open_with_gamma(
+        &self,
+        gamma: E::ScalarField,
+        polys: &[impl AsRef<[E::ScalarField]>],
+        vp: &DensePolynomial<E::ScalarField>,
+    ) -> Result<Proof<E>, Error> {
+        let fsum = self.fold_polys(gamma, polys)?;
 
         // Polynomial divide, the remained would contain the gamma * ri_s,
         // The result is the correct quotient
-        let (q, _) = poly_div_q_r(
-            DensePolynomial::from_coefficients_vec(fsum).into(),
-            vp.into(),
-        )?;
+        let (q, _) = divide_poly_auto(&DensePolynomial::from_coefficients_vec(fsum), vp)?;
         // Open to the resulting polynomial
         Ok(Proof(
-            M::multi_scalar_mul_g1(&self.g1_precomp, &q)?.into_affine(),
+            M::multi_scalar_mul_g1(&self.g1_precomp, &q.coeffs)?.into_affine(),
         ))
     }
 
-    fn verify_with_lag_ctx_g2_zeros(
+    #[allow(clippy::too_many_arguments)]
+    fn verify_with_lag_ctx_g2_zeros<C: FoldTerms<E>>(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: &[C],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
         lag_ctx: &LagrangeInterpContext<E::ScalarField>,
         g2_zeros: &E::G2,
     ) -> Result<bool, Error> {
+        Ok(self
+            .equation_with_lag_ctx_g2_zeros(
+                transcript, commits, points, evals, proof, lag_ctx, g2_zeros, None,
+            )?
+            .0
+            .check::<M>())
+    }
+
+    /// Does all of [`Self::verify_with_lag_ctx_g2_zeros`] but the pairing check, returning the
+    /// equation and the gamma challenge
+    #[allow(clippy::too_many_arguments)]
+    fn equation_with_lag_ctx_g2_zeros<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        g2_zeros: &E::G2,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.limits.check(commits.len(), points.len())?;
         check_verify_sizes(commits, evals, points.len())?;
+        self.unchecked_equation_with_lag_ctx_g2_zeros(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            lag_ctx,
+            g2_zeros,
+            gamma_powers,
+        )
+    }
+
+    /// Like [`Self::equation_with_lag_ctx_g2_zeros`], but trusts that the sizes of the inputs
+    /// were already checked, as `prepare_verify` does
+    #[allow(clippy::too_many_arguments)]
+    fn unchecked_equation_with_lag_ctx_g2_zeros<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        g2_zeros: &E::G2,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.check_commits(commits)?;
+        if points.is_empty() {
+            return Ok((
+                PairingEquation::vacuous(
+                    proof.0.is_zero(),
+                    self.powers_of_g1[0],
+                    self.powers_of_g2[0],
+                ),
+                E::ScalarField::zero(),
+            ));
+        }
 
         let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
+        transcribe_points_and_evals(
+            transcript,
+            points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
         // Aggregate the r_is and then do a single msm of just the ri's and gammas
-        let gammas = gen_powers(gamma, evals.len());
+        let generated;
+        let gammas = match gamma_powers {
+            Some(powers) => supplied_powers(powers, gamma, evals.len())?,
+            None => {
+                generated = gen_powers(gamma, evals.len());
+                &generated
+            }
+        };
 
         // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
         // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, gammas)?.coeffs;
         let gamma_ris_pt = M::multi_scalar_mul_g1(&self.g1_precomp, gamma_ris)?;
 
         // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let cms_prep = M::prepare_g1(cms.clone());
-        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, gammas)?;
+        let (cms, cm_scalars) = fold_terms(commits, gammas);
+        let cms_prep = M::prepare_g1(cms);
+        let gamma_cm_pt = M::multi_scalar_mul_g1(&cms_prep, cm_scalars)?;
 
         let g2 = self.powers_of_g2[0];
 
-        Ok(M::pairing_eq_check(
-            (gamma_cm_pt - gamma_ris_pt).into(),
-            g2,
-            proof.0,
-            g2_zeros.into_affine(),
+        Ok((
+            PairingEquation {
+                lhs: ((gamma_cm_pt - gamma_ris_pt).into(), g2),
+                rhs: (proof.0, g2_zeros.into_affine()),
+            },
+            gamma,
         ))
     }
 }
 
+/// Absorbs the points and the interpolant commitments, in place of the evaluations, for
+/// [`M1NoPrecomp::open_interpolant_commit`]
+fn transcribe_points_and_interpolants<E: Pairing>(
+    transcript: &mut Transcript,
+    points: &[E::ScalarField],
+    interpolant_commits: &[Commitment<E>],
+    compress: Compress,
+) -> Result<(), Error> {
+    transcribe_generic_as(
+        transcript,
+        b"open interpolants",
+        &interpolant_commits.to_vec(),
+        compress,
+    )?;
+    transcribe_generic_as(transcript, b"open points", &points.to_vec(), compress)
+}
+
+/// Whether `indices` are every index of `domain`, in order
+fn is_whole_domain<F: FftField>(domain: &impl EvaluationDomain<F>, indices: &[usize]) -> bool {
+    indices.iter().copied().eq(0..domain.size())
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Ceremony<E> for M1NoPrecomp<E, M> {
+    fn g2_tau(&self) -> Result<E::G2Affine, Error> {
+        self.powers_of_g2
+            .get(1)
+            .copied()
+            .ok_or(Error::SetupTooSmall {
+                needed: 2,
+                got: self.powers_of_g2.len(),
+            })
+    }
+}
+
 impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1NoPrecomp<E, M> {
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = M::multi_scalar_mul_g1(&self.g1_precomp, poly.as_ref())?;
-        Ok(Commitment(res.into_affine()))
+        Ok(Commitment(self.commit_projective(poly)?.into_affine()))
+    }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        &self.powers_of_g1
+    }
+
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        M::multi_scalar_mul_g1(&self.g1_precomp, poly.as_ref())
     }
 }
 
@@ -175,10 +963,34 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProofNoPrecomp<E> for M1NoPrecomp
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
     ) -> Result<Proof<E>, Error> {
-        let vp = vanishing_polynomial(points.as_ref());
+        self.config.limits.check(polys.len(), points.len())?;
+        let vp = vanishing_polynomial(points);
         self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
     }
 
+    fn open_point_major(
+        &self,
+        transcript: &mut Transcript,
+        evals_by_point: &[Vec<E::ScalarField>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        self.config.limits.check(polys.len(), points.len())?;
+        let evals = PointMajor::new(evals_by_point, polys.len())?;
+        let vp = vanishing_polynomial(points);
+        self.open_with_vanishing_poly(transcript, &evals, polys, points, &vp)
+    }
+
+    fn prepare_verify<'a>(
+        &self,
+        commits: &'a [Commitment<E>],
+        points: &'a [E::ScalarField],
+        evals: &'a [impl AsRef<[E::ScalarField]>],
+    ) -> Result<PreparedVerify<'a, E>, Error> {
+        self.config
+            .prepare_verify(self.scheme_tag(), commits, points, evals)
+    }
+
     fn verify(
         &self,
         transcript: &mut Transcript,
@@ -187,33 +999,846 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProofNoPrecomp<E> for M1NoPrecomp
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
-        let vp = vanishing_polynomial(points);
-        let g2_zeros = M::multi_scalar_mul_g2(&self.g2_precomp, &vp.coeffs)?;
-        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_g2_zeros(
-            transcript, commits, points, evals, proof, &lag_ctx, &g2_zeros,
-        )
+        let prepared = self.prepare_verify(commits, points, evals)?;
+        self.finish_verify(transcript, prepared, proof)
+    }
+
+    fn finish_verify(
+        &self,
+        transcript: &mut Transcript,
+        prepared: PreparedVerify<E>,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        prepared.check_scheme(self.scheme_tag())?;
+        let (eq, _) = self.prepared_equation(transcript, &prepared, proof)?;
+        Ok(eq.check::<M>())
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> ConfigurableScheme<E> for M1NoPrecomp<E, M> {
+    fn config(&self) -> &SchemeConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut SchemeConfig {
+        &mut self.config
+    }
+
+    fn equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.folded_equation(transcript, commits, points, evals, proof, gamma_powers)
+    }
+
+    fn lazy_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[LazyCommitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.folded_equation(transcript, commits, points, evals, proof, None)
+    }
+
+    fn check_equation(&self, eq: &PairingEquation<E>) -> bool {
+        eq.check::<M>()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::M1NoPrecomp;
+    use super::{DegreeCapProof, M1NoPrecomp};
     use crate::{
+        lagrange::LagrangeInterpContext,
         msm::{blst::BlstMSMEngine, ArkMSMEngine},
         test_rng,
-        testing::{test_basic_no_precomp, test_size_errors},
+        testing::{
+            test_agree_on_set, test_basic_no_precomp, test_binding, test_challenge_bits,
+            test_commit_and_open_rs, test_commit_batch, test_commit_be, test_commit_bigint,
+            test_commit_sparse, test_constant_polys, test_empty_points, test_identity_commitments,
+            test_linked_rounds, test_matrix_column, test_new_from_seeded_rng, test_open_by_indices,
+            test_open_mixed_domain, test_open_point_major, test_open_sharded,
+            test_open_with_vanishing, test_padded_polys, test_phase_labels, test_prepare_verify,
+            test_size_errors, test_size_limits, test_transform, test_unaggregated,
+            test_vanishes_on, test_verify_lazy, test_verify_miller_output,
+            test_verify_with_challenge_out, test_verify_with_powers,
+        },
+        traits::{Ceremony, Committer, ConfigurableScheme, PolyMultiProofNoPrecomp},
+        Error, SizeLimits,
     };
-    use ark_bls12_381::Bls12_381;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{One, Zero};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
 
     #[test]
     fn test_basic_open_works() {
         let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
         test_basic_no_precomp(&s);
         test_size_errors(&s);
+        test_padded_polys(&s);
 
         let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
         test_basic_no_precomp(&s);
         test_size_errors(&s);
+        test_padded_polys(&s);
+        test_linked_rounds(&s);
+        test_binding(&s);
+        test_transform(&s);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    fn test_inconsistent_evals_error() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        crate::testing::test_inconsistent_evals(&s);
+    }
+
+    #[test]
+    fn test_open_too_large_errors() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        crate::testing::test_open_too_large(&s, 256);
+    }
+
+    #[test]
+    fn test_new_with_base() {
+        use ark_bls12_381::G1Projective;
+        use ark_ec::{CurveGroup, Group};
+
+        let base = G1Projective::generator() * Fr::from(7u64);
+        let s =
+            M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_with_base(256, 30, base, &mut test_rng());
+        assert_eq!(base.into_affine(), s.powers_of_g1[0]);
+        test_basic_no_precomp(&s);
+    }
+
+    #[test]
+    fn test_size_limits_error_early() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        test_size_limits(&s);
+        let limited = s.with_limits(SizeLimits {
+            max_polys: Some(3),
+            max_points: Some(4),
+        });
+        assert_eq!(
+            limited.config.limits,
+            limited.truncate(100).unwrap().config.limits
+        );
+    }
+
+    #[test]
+    fn test_agree_on_set_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_agree_on_set(&s);
+    }
+
+    #[test]
+    fn test_vanishes_on_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_vanishes_on(&s);
+    }
+
+    #[test]
+    fn test_open_by_indices_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_open_by_indices(&s);
+    }
+
+    #[test]
+    fn test_open_point_major_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_open_point_major(&s);
+    }
+
+    #[test]
+    fn test_prepare_verify_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_prepare_verify(&s);
+    }
+
+    #[test]
+    fn test_constant_polys_work() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_constant_polys(&s);
+    }
+
+    #[test]
+    fn test_phase_labels_work() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_phase_labels(&s);
+    }
+
+    #[test]
+    fn test_empty_points_work() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_empty_points(&s);
+    }
+
+    #[test]
+    fn test_open_mixed_domain_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_open_mixed_domain(&s);
+    }
+
+    #[test]
+    fn test_open_sharded_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_open_sharded(&s);
+    }
+
+    #[test]
+    fn test_commit_batch_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_commit_batch(&s, 64);
+    }
+
+    #[test]
+    fn test_unaggregated_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_unaggregated(&s);
+    }
+
+    #[test]
+    fn test_identity_commitments_rejected() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_identity_commitments(&s);
+        let strict = s.with_reject_identity_commitments(true);
+        assert!(
+            strict
+                .truncate(10)
+                .unwrap()
+                .config
+                .reject_identity_commitments
+        );
+    }
+
+    #[test]
+    fn test_challenge_bits_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_challenge_bits(&s);
+        let capped = s.with_challenge_bits(128).unwrap();
+        assert_eq!(
+            Some(128),
+            capped.truncate(31).unwrap().config.challenge_bits
+        );
+    }
+
+    #[test]
+    fn test_verify_miller_output_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_verify_miller_output(&s);
+    }
+
+    #[test]
+    fn test_verify_with_powers_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_verify_with_powers(&s);
+    }
+
+    #[test]
+    fn test_verify_with_challenge_out_works() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        test_verify_with_challenge_out(&s);
+    }
+
+    #[test]
+    fn test_aggregate_polynomial() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+
+        let aggregate = s
+            .aggregate_polynomial(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Aggregate failed");
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        let (valid, gamma) = s
+            .verify_with_challenge_out(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+            .expect("Verify failed");
+        assert!(valid);
+
+        // The verifier's folded commitment
+        let mut folded = G1Projective::zero();
+        let mut gamma_i = Fr::one();
+        for c in commits.iter() {
+            folded += c.0 * gamma_i;
+            gamma_i *= gamma;
+        }
+        assert_eq!(
+            folded.into_affine(),
+            s.commit(&aggregate).expect("Commit failed").0
+        );
+
+        let empty: &[Vec<Fr>] = &[vec![], vec![], vec![]];
+        assert_eq!(
+            Ok(vec![]),
+            s.aggregate_polynomial(&mut Transcript::new(b"testing"), empty, &coeffs, &[])
+        );
+    }
+
+    #[test]
+    fn test_commit_sparse_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 8, &mut test_rng());
+        test_commit_sparse(&s, 256);
+    }
+
+    #[test]
+    fn test_verify_interpolant_commit() {
+        // Bounding the degree of the interpolants of 6 points needs tau^58 in G2
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 58, &mut test_rng());
+        let points = (0..6)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(50, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let mut evals = polys
+            .iter()
+            .map(|p| {
+                let p = DensePolynomial::from_coefficients_slice(p);
+                points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let lag_ctx = LagrangeInterpContext::new_from_points(&points).unwrap();
+        let interpolant_commits = |evals: &[Vec<Fr>]| {
+            evals
+                .iter()
+                .map(|e| {
+                    let r = lag_ctx
+                        .lagrange_interp_linear_combo(&[e], &[Fr::one()])
+                        .unwrap();
+                    s.commit(&r.coeffs).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let proof = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        let rs = interpolant_commits(&evals);
+        let r_proof = s
+            .open_interpolant_commit(&mut Transcript::new(b"testing"), &rs, &polys, &points)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(true),
+            s.verify_interpolant_commit(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &rs,
+                &r_proof
+            )
+        );
+
+        // Both reject a wrong evaluation
+        evals[1][2] += Fr::one();
+        let bad_rs = interpolant_commits(&evals);
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_interpolant_commit(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &bad_rs,
+                &r_proof
+            )
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 3,
+                got: 2
+            }),
+            s.verify_interpolant_commit(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &rs[..2],
+                &r_proof
+            )
+        );
+
+        // The polynomials pass as their own interpolants with an identity opening, but fail
+        // the degree bound
+        let forged = DegreeCapProof {
+            open: super::Proof(G1Projective::zero().into_affine()),
+            shifted: G1Projective::zero().into_affine(),
+        };
+        assert_eq!(
+            Ok(false),
+            s.verify_interpolant_commit(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &commits,
+                &forged
+            )
+        );
+
+        // Setups without the G2 power to check the bound can't be used
+        let small = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        let expected = Err(Error::TooManyScalars {
+            n_coeffs: 59,
+            expected_max: 9,
+        });
+        assert_eq!(
+            expected,
+            small
+                .open_interpolant_commit(&mut Transcript::new(b"testing"), &rs, &polys, &points)
+                .map(|_| ())
+        );
+        assert_eq!(
+            expected,
+            small
+                .verify_interpolant_commit(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &points,
+                    &rs,
+                    &r_proof
+                )
+                .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_commit_bigint_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 8, &mut test_rng());
+        test_commit_bigint(&s, 256);
+    }
+
+    #[test]
+    fn test_degree_cap() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 64, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let open_polys = |degree: usize| {
+            let polys = (0..3)
+                .map(|_| DensePolynomial::<Fr>::rand(degree, &mut test_rng()))
+                .collect::<Vec<_>>();
+            let evals: Vec<Vec<_>> = polys
+                .iter()
+                .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+                .collect();
+            let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+            let commits = coeffs
+                .iter()
+                .map(|p| s.commit(p).unwrap())
+                .collect::<Vec<_>>();
+            (coeffs, evals, commits)
+        };
+
+        let (coeffs, evals, commits) = open_polys(20);
+        let proof = s
+            .open_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &points,
+                20,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                20,
+                &proof
+            )
+        );
+
+        // The evals are correct, but the polynomials are over the cap
+        let (coeffs, evals, commits) = open_polys(30);
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 75,
+                expected_max: 65
+            }),
+            s.open_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &points,
+                20
+            )
+            .map(|_| ())
+        );
+        let proof = s
+            .open_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &points,
+                30,
+            )
+            .unwrap();
+        assert_eq!(
+            Ok(false),
+            s.verify_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                20,
+                &proof
+            )
+        );
+        // A proof for the right cap with a shifted term for a smaller cap fails
+        let lying = DegreeCapProof {
+            open: proof.open.clone(),
+            shifted: s
+                .open_with_degree_cap(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &coeffs,
+                    &points,
+                    40,
+                )
+                .unwrap()
+                .shifted,
+        };
+        assert_eq!(
+            Ok(false),
+            s.verify_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                30,
+                &lying
+            )
+        );
+        let too_large = Err(Error::TooManyScalars {
+            n_coeffs: usize::MAX,
+            expected_max: 65,
+        });
+        assert_eq!(
+            too_large,
+            s.open_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs,
+                &points,
+                usize::MAX
+            )
+            .map(|_| ())
+        );
+        assert_eq!(
+            too_large,
+            s.verify_with_degree_cap(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                usize::MAX,
+                &proof
+            )
+            .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_open_constant_shifts() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        let points = (0..5)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let shifts = (0..3)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let base = DensePolynomial::<Fr>::rand(40, &mut test_rng());
+        let base_commit = s.commit(&base.coeffs).unwrap();
+
+        let (evals, proof) = s
+            .open_constant_shifts(
+                &mut Transcript::new(b"testing"),
+                &base.coeffs,
+                &shifts,
+                &points,
+            )
+            .unwrap();
+
+        // Same as opening each shifted polynomial separately
+        let shifted = shifts
+            .iter()
+            .map(|a| {
+                let mut c = base.coeffs.clone();
+                c[0] += a;
+                c
+            })
+            .collect::<Vec<_>>();
+        let separate_evals: Vec<Vec<_>> = shifted
+            .iter()
+            .map(|c| {
+                let p = DensePolynomial::from_coefficients_slice(c);
+                points.iter().map(|x| p.evaluate(x)).collect()
+            })
+            .collect();
+        let separate = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &separate_evals,
+                &shifted,
+                &points,
+            )
+            .unwrap();
+        assert_eq!(separate_evals, evals);
+        assert_eq!(separate.0, proof.0);
+
+        assert_eq!(
+            Ok(true),
+            s.verify_constant_shifts(
+                &mut Transcript::new(b"testing"),
+                &base_commit,
+                &shifts,
+                &points,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_constant_shifts(
+                &mut Transcript::new(b"testing"),
+                &base_commit,
+                &shifts[..2],
+                &points,
+                &evals[..2],
+                &proof
+            )
+        );
+
+        // No points gives the same vacuous opening as `open`
+        let (evals, proof) = s
+            .open_constant_shifts(&mut Transcript::new(b"testing"), &base.coeffs, &shifts, &[])
+            .unwrap();
+        assert_eq!(vec![Vec::<Fr>::new(); 3], evals);
+        assert!(proof.0.is_zero());
+        assert_eq!(
+            Ok(true),
+            s.verify_constant_shifts(
+                &mut Transcript::new(b"testing"),
+                &base_commit,
+                &shifts,
+                &[],
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_commit_be_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        test_commit_be(&s, 64);
+    }
+
+    #[test]
+    fn test_matrix_column_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        test_matrix_column(&s, 63);
+    }
+
+    #[test]
+    fn test_verify_lazy_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        test_verify_lazy(&s);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        let t = s.truncate(63).expect("Failed to truncate");
+        assert_eq!(t.powers_of_g1.len(), 64);
+        assert_eq!(t.powers_of_g2, s.powers_of_g2);
+
+        let poly = DensePolynomial::<Fr>::rand(63, &mut test_rng()).coeffs;
+        assert_eq!(
+            s.commit(&poly).expect("Commit failed").0,
+            t.commit(&poly).expect("Commit failed").0
+        );
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 257,
+                expected_max: 256
+            }),
+            s.truncate(256).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: usize::MAX,
+                expected_max: 256
+            }),
+            s.truncate(usize::MAX).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_ceremony_id_without_g2_tau_errors() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(16, 0, &mut test_rng());
+        let expected = Err(Error::SetupTooSmall { needed: 2, got: 1 });
+        assert_eq!(expected, s.g2_tau().map(|_| ()));
+        assert_eq!(expected, s.ceremony_id().map(|_| ()));
+        assert_eq!(expected, s.check_ceremony(&[0u8; 32]));
+    }
+
+    #[test]
+    fn test_commit_and_open_rs_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        test_commit_and_open_rs(&s);
+    }
+
+    #[test]
+    fn test_open_with_vanishing_works() {
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(256, 30, &mut test_rng());
+        test_open_with_vanishing(&s, |t, e, p, x, v| s.open_with_vanishing(t, e, p, x, v));
+    }
+
+    #[test]
+    fn test_new_from_seeded_rng_works() {
+        let s = test_new_from_seeded_rng(|rng| {
+            M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(16, 4, rng)
+        });
+        assert_eq!(s.powers_of_g1.len(), 16);
+        assert_eq!(s.powers_of_g2.len(), 5);
+    }
+
+    #[test]
+    fn test_open_coset() {
+        use crate::poly_ops::ev_points;
+        use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(256, 30, &mut test_rng());
+        let domain = Radix2EvaluationDomain::<Fr>::new(16).unwrap();
+        let shift = Fr::rand(&mut test_rng());
+        let coset_points = ev_points(&domain.get_coset(shift).unwrap());
+        // Degree well above twice the coset size
+        let polys = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(100, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+
+        // The whole coset takes the sparse path, a subset the generic one
+        for indices in [(0..16).collect::<Vec<_>>(), vec![1, 4, 9, 15]] {
+            let points = indices.iter().map(|&i| coset_points[i]).collect::<Vec<_>>();
+            let evals: Vec<Vec<_>> = polys
+                .iter()
+                .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+                .collect();
+            let open = s
+                .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                .expect("Open failed");
+            let open_coset = s
+                .open_coset(
+                    &mut Transcript::new(b"testing"),
+                    &evals,
+                    &coeffs,
+                    &domain,
+                    shift,
+                    &indices,
+                )
+                .expect("Open failed");
+            assert_eq!(open.0, open_coset.0);
+            assert_eq!(
+                Ok(true),
+                s.verify_coset(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &evals,
+                    &open_coset,
+                    &domain,
+                    shift,
+                    &indices
+                )
+            );
+            assert_eq!(
+                Ok(false),
+                s.verify_coset(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    &evals,
+                    &open_coset,
+                    &domain,
+                    shift + Fr::from(1u64),
+                    &indices
+                )
+            );
+        }
+        assert_eq!(
+            Err(Error::IndexOutOfDomain {
+                idx: 16,
+                domain_size: 16
+            }),
+            s.open_coset(
+                &mut Transcript::new(b"testing"),
+                &[vec![Fr::from(0u64)]],
+                &[vec![Fr::from(0u64)]],
+                &domain,
+                shift,
+                &[16]
+            )
+            .map(|_| ())
+        );
     }
 }
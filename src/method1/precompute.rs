@@ -2,16 +2,20 @@
 use ark_poly::univariate::DensePolynomial;
 use merlin::Transcript;
 
-use ark_ec::pairing::Pairing;
-use ark_std::vec::Vec;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{One, Zero};
+use ark_std::{vec, vec::Vec};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 use super::{vanishing_polynomial, Error, Proof};
 use crate::lagrange::LagrangeInterpContext;
-use crate::traits::{Committer, MSMEngine, PolyMultiProof};
-use crate::{cfg_iter, Commitment};
+use crate::traits::{Ceremony, Committer, MSMEngine, PairingEquationProof, PolyMultiProof};
+use crate::{
+    batch::PairingEquation, cfg_iter, check_verify_sizes, gen_powers, get_challenge,
+    get_field_size, transcribe_generic_as, transcribe_points_and_evals, Commitment,
+};
 
 /// Method 1 scheme with precomputed lagrange polynomials/vanishing polys
 #[derive(Clone)]
@@ -48,6 +52,132 @@ impl<E: Pairing, M: MSMEngine<E = E>> M1Precomp<E, M> {
             lagrange_ctxs,
         })
     }
+
+    /// The commitment $[Z_S(\tau)]_2$ to the vanishing polynomial of point set `index`, which
+    /// verifying openings at that point set pairs with the proof. Together with $[1]_2$ from
+    /// [`crate::kzg::KzgVerifierKey::g2_elements`] these are all the G2 points a verifier for
+    /// the point set needs.
+    pub fn pointset_g2(&self, index: usize) -> Result<E::G2Affine, Error> {
+        self.g2_zeros
+            .get(index)
+            .map(|g| g.into_affine())
+            .ok_or(Error::InvalidPointSetIndex {
+                idx: index,
+                n_point_sets: self.g2_zeros.len(),
+            })
+    }
+
+    /// Verifies many proofs of openings of the same `commits`, each given as
+    /// `(point_set_index, evals, proof)`. The requests are read from `transcript` in order, as
+    /// if `verify` were called on each in turn, then the equations are folded with powers of a
+    /// challenge drawn after absorbing all the proofs. This takes one msm over the commitments
+    /// and one over the interpolated polynomials for all the requests, and one pairing per point
+    /// set used plus one. Returns `true` only if every proof verifies.
+    #[allow(clippy::type_complexity)]
+    pub fn verify_shared_commitments(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        requests: &[(usize, Vec<Vec<E::ScalarField>>, Proof<E>)],
+    ) -> Result<bool, Error> {
+        self.inner.config.check_commits(commits)?;
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        let mut gammas = Vec::with_capacity(requests.len());
+        let mut vacuous_hold = true;
+        for (idx, evals, proof) in requests {
+            let points = self
+                .point_sets
+                .get(*idx)
+                .ok_or(Error::InvalidPointSetIndex {
+                    idx: *idx,
+                    n_point_sets: self.point_sets.len(),
+                })?;
+            self.inner
+                .config
+                .limits
+                .check(commits.len(), points.len())?;
+            check_verify_sizes(commits, evals, points.len())?;
+            // Openings at no points don't touch the transcript and only hold with the identity
+            if points.is_empty() {
+                vacuous_hold &= proof.0.is_zero();
+                gammas.push(None);
+                continue;
+            }
+            transcribe_points_and_evals(
+                transcript,
+                points,
+                evals.as_slice(),
+                field_size_bytes,
+                self.inner.config.transcript_compress,
+            )?;
+            let gamma: E::ScalarField = self
+                .inner
+                .config
+                .gamma_challenge(transcript, field_size_bytes);
+            gammas.push(Some(gamma));
+        }
+        if !vacuous_hold {
+            return Ok(false);
+        }
+
+        let proofs = requests.iter().map(|(_, _, p)| p.0).collect::<Vec<_>>();
+        transcribe_generic_as(
+            transcript,
+            b"shared proofs",
+            &proofs,
+            self.inner.config.transcript_compress,
+        )?;
+        let rho: E::ScalarField = get_challenge(transcript, b"shared rho", field_size_bytes);
+
+        // Fold e(sum_i gamma_j^i C_i - [r_j(tau)], g2) = e(W_j, [Z_j(tau)]) with weights rho^j
+        let mut cm_scalars = vec![E::ScalarField::zero(); commits.len()];
+        let mut ris = Vec::<E::ScalarField>::new();
+        let mut g2_terms: Vec<(usize, E::G1)> = Vec::new();
+        let mut weight = E::ScalarField::one();
+        for ((idx, evals, proof), gamma) in requests.iter().zip(gammas) {
+            let Some(gamma) = gamma else { continue };
+            let weighted_gammas = gen_powers(gamma, commits.len())
+                .into_iter()
+                .map(|g| g * weight)
+                .collect::<Vec<_>>();
+            for (acc, g) in cm_scalars.iter_mut().zip(&weighted_gammas) {
+                *acc += g;
+            }
+            let r =
+                self.lagrange_ctxs[*idx].lagrange_interp_linear_combo(evals, &weighted_gammas)?;
+            if ris.len() < r.coeffs.len() {
+                ris.resize(r.coeffs.len(), E::ScalarField::zero());
+            }
+            for (acc, c) in ris.iter_mut().zip(&r.coeffs) {
+                *acc += c;
+            }
+            let w = proof.0 * weight;
+            match g2_terms.iter_mut().find(|(i, _)| i == idx) {
+                Some((_, acc)) => *acc += w,
+                None => g2_terms.push((*idx, w)),
+            }
+            weight *= rho;
+        }
+
+        let cms_prep = M::prepare_g1(commits.iter().map(|c| c.0).collect());
+        let lhs = M::multi_scalar_mul_g1(&cms_prep, cm_scalars)?
+            - M::multi_scalar_mul_g1(&self.inner.g1_precomp, ris)?;
+        let (g1s, g2s): (Vec<_>, Vec<_>) = core::iter::once((lhs, self.inner.powers_of_g2[0]))
+            .chain(
+                g2_terms
+                    .into_iter()
+                    .map(|(i, w)| (-w, self.g2_zeros[i].into_affine())),
+            )
+            .unzip();
+        crate::op_count::count_pairings(g1s.len());
+        Ok(E::multi_pairing(E::G1::normalize_batch(&g1s), g2s).is_zero())
+    }
+}
+
+impl<E: Pairing, M: MSMEngine<E = E>> Ceremony<E> for M1Precomp<E, M> {
+    fn g2_tau(&self) -> Result<E::G2Affine, Error> {
+        self.inner.g2_tau()
+    }
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1Precomp<E, M> {
@@ -57,6 +187,17 @@ impl<E: Pairing, M: MSMEngine<E = E>> Committer<E> for M1Precomp<E, M> {
     ) -> Result<Commitment<E>, Error> {
         self.inner.commit(poly)
     }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        self.inner.powers_of_g1()
+    }
+
+    fn commit_projective(
+        &self,
+        poly: impl AsRef<[<E as Pairing>::ScalarField]>,
+    ) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
 }
 
 impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1Precomp<E, M> {
@@ -98,6 +239,30 @@ impl<E: Pairing, M: MSMEngine<E = E>> PolyMultiProof<E> for M1Precomp<E, M> {
     }
 }
 
+impl<E: Pairing, M: MSMEngine<E = E>> PairingEquationProof<E> for M1Precomp<E, M> {
+    fn verify_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<PairingEquation<E>, Error> {
+        self.inner
+            .equation_with_lag_ctx_g2_zeros(
+                transcript,
+                commits,
+                &self.point_sets[point_set_index],
+                evals,
+                proof,
+                &self.lagrange_ctxs[point_set_index],
+                &self.g2_zeros[point_set_index],
+                None,
+            )
+            .map(|(eq, _)| eq)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::M1Precomp;
@@ -123,4 +288,130 @@ mod tests {
         let s = M1Precomp::from_inner(s, vec![points.clone()]).expect("Failed to construct");
         test_basic_precomp(&s, &points);
     }
+
+    #[test]
+    fn test_verify_shared_commitments() {
+        use crate::{
+            traits::{Committer, PolyMultiProof},
+            Error,
+        };
+        use ark_ff::One;
+        use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+        use merlin::Transcript;
+
+        let point_sets = [3, 5, 8]
+            .iter()
+            .map(|&n| {
+                (0..n)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut test_rng());
+        let s = M1Precomp::from_inner(s, point_sets.clone()).unwrap();
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(40, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+
+        // The same commitments opened at point sets over time, on one transcript
+        let mut transcript = Transcript::new(b"testing");
+        let mut requests = [0, 2, 1, 2, 0]
+            .into_iter()
+            .map(|idx| {
+                let evals = polys
+                    .iter()
+                    .map(|p| {
+                        point_sets[idx]
+                            .iter()
+                            .map(|x| p.evaluate(x))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let proof = s.open(&mut transcript, &evals, &coeffs, idx).unwrap();
+                (idx, evals, proof)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            s.verify_shared_commitments(&mut Transcript::new(b"testing"), &commits, &requests)
+        );
+
+        requests[3].1[2][4] += Fr::one();
+        assert_eq!(
+            Ok(false),
+            s.verify_shared_commitments(&mut Transcript::new(b"testing"), &commits, &requests)
+        );
+
+        requests[3].0 = 3;
+        assert_eq!(
+            Err(Error::InvalidPointSetIndex {
+                idx: 3,
+                n_point_sets: 3
+            }),
+            s.verify_shared_commitments(&mut Transcript::new(b"testing"), &commits, &requests)
+        );
+    }
+
+    #[test]
+    fn test_exported_g2_elements() {
+        use crate::{kzg::KzgVerifierKey, vanishing_polynomial, Error};
+        use ark_bls12_381::{G1Projective, G2Affine, G2Projective};
+        use ark_ec::CurveGroup;
+        use ark_poly::Polynomial;
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+        let x = Fr::rand(&mut test_rng());
+        let g2 = G2Projective::rand(&mut test_rng());
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_scalar(
+            x,
+            G1Projective::rand(&mut test_rng()),
+            g2,
+            64,
+            8,
+        );
+        let point_sets = (0..2)
+            .map(|_| {
+                (0..4)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let vk = KzgVerifierKey::try_from(&s).unwrap();
+        let s = M1Precomp::from_inner(s, point_sets.clone()).unwrap();
+
+        let g2_elements = vk.g2_elements();
+        assert_eq!(vec![g2.into_affine(), (g2 * x).into_affine()], g2_elements);
+        let mut exported = g2_elements.clone();
+        for (i, ps) in point_sets.iter().enumerate() {
+            let z_tau = vanishing_polynomial(ps).evaluate(&x);
+            let g2_zeros = s.pointset_g2(i).unwrap();
+            assert_eq!((g2 * z_tau).into_affine(), g2_zeros);
+            exported.push(g2_zeros);
+        }
+        assert_eq!(
+            Err(Error::InvalidPointSetIndex {
+                idx: 2,
+                n_point_sets: 2
+            }),
+            s.pointset_g2(2)
+        );
+
+        let mut bytes = Vec::new();
+        exported.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            exported,
+            Vec::<G2Affine>::deserialize_compressed(&bytes[..]).unwrap()
+        );
+        let mut bytes = Vec::new();
+        vk.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            vk,
+            KzgVerifierKey::deserialize_compressed(&bytes[..]).unwrap()
+        );
+    }
 }
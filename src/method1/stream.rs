@@ -0,0 +1,194 @@
+//! Streaming method 1 openings at a single point
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use super::{M1NoPrecomp, Proof};
+use crate::{
+    gen_powers, get_field_size, poly_ops::div_by_binomial, traits::MSMEngine,
+    transcribe_points_and_evals, trim_zeros, Error, PointMajor,
+};
+
+/// Opens polynomials at a single shared point as they arrive, holding one polynomial at a time.
+/// Each polynomial's quotient by $x - z$ is committed to when it's added, and [`Self::finish`]
+/// folds those commitments with powers of the gamma challenge. The proof is the same as
+/// [`crate::traits::PolyMultiProofNoPrecomp::open`] on all the polynomials at `[z]`, at the
+/// cost of one msm per polynomial instead of one in total.
+///
+/// The quotients can't be folded as they arrive, since gamma is drawn from a transcript that has
+/// absorbed every eval, and so isn't known until the last polynomial is added. That's why the
+/// transcript is only taken by [`Self::finish`], and why a commitment and an eval are kept per
+/// polynomial, which is still far less than the polynomials themselves.
+pub struct SinglePointOpenAccumulator<'a, E: Pairing, M: MSMEngine<E = E>> {
+    scheme: &'a M1NoPrecomp<E, M>,
+    point: E::ScalarField,
+    evals: Vec<E::ScalarField>,
+    witnesses: Vec<E::G1>,
+}
+
+impl<'a, E: Pairing, M: MSMEngine<E = E>> SinglePointOpenAccumulator<'a, E, M> {
+    /// Starts an opening at `point` with the given scheme
+    pub fn new(scheme: &'a M1NoPrecomp<E, M>, point: E::ScalarField) -> Self {
+        Self {
+            scheme,
+            point,
+            evals: Vec::new(),
+            witnesses: Vec::new(),
+        }
+    }
+
+    /// Adds the polynomial with coefficients `coeffs` to the opening, returning its index.
+    /// Errors like `open` if there are too many polynomials or this one is too large.
+    pub fn add(&mut self, coeffs: &[E::ScalarField]) -> Result<usize, Error> {
+        let index = self.evals.len();
+        self.scheme.config.limits.check(index + 1, 1)?;
+        let n_coeffs = trim_zeros(coeffs).len();
+        let expected_max = self.scheme.powers_of_g1.len();
+        if n_coeffs > expected_max {
+            return Err(Error::PolynomialTooLarge {
+                poly: index,
+                n_coeffs,
+                expected_max,
+            });
+        }
+        let poly = DensePolynomial::from_coefficients_slice(coeffs);
+        let (q, r) = div_by_binomial(&poly, 1, self.point);
+        let witness = M::multi_scalar_mul_g1(&self.scheme.g1_precomp, &q.coeffs)?;
+        self.evals
+            .push(r.coeffs.first().copied().unwrap_or_default());
+        self.witnesses.push(witness);
+        Ok(index)
+    }
+
+    /// The evals of the polynomials added so far at the point, in the order they were added
+    pub fn evals(&self) -> &[E::ScalarField] {
+        &self.evals
+    }
+
+    /// Absorbs the point and evals into the transcript exactly as `open` does, then folds the
+    /// quotient commitments into the proof. Returns the evals along with it, which the verifier
+    /// needs. Errors with [`Error::NoPolynomialsGiven`] if nothing was added.
+    pub fn finish(
+        self,
+        transcript: &mut Transcript,
+    ) -> Result<(Vec<E::ScalarField>, Proof<E>), Error> {
+        if self.evals.is_empty() {
+            return Err(Error::NoPolynomialsGiven);
+        }
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        let by_point = [self.evals];
+        transcribe_points_and_evals(
+            transcript,
+            &[self.point],
+            &PointMajor::new(&by_point, by_point[0].len())?,
+            field_size_bytes,
+            self.scheme.config.transcript_compress,
+        )?;
+        let gamma = self
+            .scheme
+            .config
+            .gamma_challenge(transcript, field_size_bytes);
+        let [evals] = by_point;
+
+        let gammas = gen_powers(gamma, evals.len());
+        let bases = E::G1::normalize_batch(&self.witnesses);
+        let proof = crate::curve_msm::<E::G1>(&bases, &gammas)?;
+        Ok((evals, Proof(proof.into_affine())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SinglePointOpenAccumulator;
+    use crate::{
+        method1::M1NoPrecomp,
+        msm::blst::BlstMSMEngine,
+        test_rng,
+        traits::{Committer, ConfigurableScheme, PolyMultiProofNoPrecomp},
+        Error, SizeLimits,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_streamed_matches_batch_open() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 4, &mut test_rng());
+        let z = Fr::rand(&mut test_rng());
+        let polys = (0..20)
+            .map(|i| DensePolynomial::<Fr>::rand(i * 3, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+
+        let mut acc = SinglePointOpenAccumulator::new(&s, z);
+        for (i, p) in polys.iter().enumerate() {
+            assert_eq!(Ok(i), acc.add(p));
+        }
+        let (evals, proof) = acc
+            .finish(&mut Transcript::new(b"testing"))
+            .expect("Open failed");
+
+        let evals = evals.into_iter().map(|y| vec![y]).collect::<Vec<_>>();
+        for (p, y) in polys.iter().zip(&evals) {
+            assert_eq!(
+                DensePolynomial::from_coefficients_slice(p).evaluate(&z),
+                y[0]
+            );
+        }
+        let batch = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &[z])
+            .expect("Open failed");
+        assert_eq!(batch.0, proof.0);
+
+        let commits = polys
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &[z],
+                &evals,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_accumulator_errors() {
+        let s = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(8, 4, &mut test_rng()).with_limits(
+            SizeLimits {
+                max_polys: Some(1),
+                max_points: None,
+            },
+        );
+        let z = Fr::rand(&mut test_rng());
+        assert_eq!(
+            Err(Error::NoPolynomialsGiven),
+            SinglePointOpenAccumulator::new(&s, z)
+                .finish(&mut Transcript::new(b"testing"))
+                .map(|_| ())
+        );
+
+        let mut acc = SinglePointOpenAccumulator::new(&s, z);
+        assert_eq!(
+            Err(Error::PolynomialTooLarge {
+                poly: 0,
+                n_coeffs: 9,
+                expected_max: 8
+            }),
+            acc.add(&DensePolynomial::<Fr>::rand(8, &mut test_rng()).coeffs)
+        );
+        assert_eq!(
+            Ok(0),
+            acc.add(&DensePolynomial::<Fr>::rand(7, &mut test_rng()).coeffs)
+        );
+        assert_eq!(
+            Err(Error::TooManyPolynomials { n_polys: 2, max: 1 }),
+            acc.add(&DensePolynomial::<Fr>::rand(7, &mut test_rng()).coeffs)
+        );
+    }
+}
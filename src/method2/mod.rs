@@ -1,31 +1,35 @@
 //! # BDFG Method 2
 use crate::{
-    check_opening_sizes, check_verify_sizes,
+    batch::PairingEquation,
+    check_opening_sizes, check_poly_sizes, check_verify_sizes,
     lagrange::LagrangeInterpContext,
-    traits::{Committer, PolyMultiProofNoPrecomp},
+    poly_ops::divide_poly_auto,
+    traits::{Ceremony, Committer, ConfigurableScheme, PolyMultiProofNoPrecomp},
 };
-use ark_ff::One;
+use ark_ff::{One, Zero};
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{vec, vec::Vec, UniformRand};
+use core::fmt;
 use core::ops::{Div, Mul, Sub};
 use merlin::Transcript;
 
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
-use ark_std::rand::RngCore;
+use ark_std::rand::{CryptoRng, RngCore};
 
 use crate::{
-    get_challenge, get_field_size, transcribe_generic, transcribe_points_and_evals, Commitment,
+    fold_terms, get_challenge, get_field_size, supplied_powers, transcribe_generic_as,
+    transcribe_points_and_evals, Commitment, EvalMatrix, FoldTerms, LazyCommitment, PointMajor,
+    PreparedVerify, SchemeConfig, SchemeTag, VerifyCost,
 };
 
-use crate::{
-    gen_curve_powers, gen_powers, linear_combination, poly_div_q_r, vanishing_polynomial, Error,
-};
+use crate::{gen_curve_powers, gen_powers, linear_combination, vanishing_polynomial, Error};
 
+pub mod multi_set;
 pub mod precompute;
 
 /// A method 2 proof scheme with no precomputation of lagrange polynomials
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct M2NoPrecomp<E: Pairing> {
     /// The given powers tau in G1
     pub powers_of_g1: Vec<E::G1Affine>,
@@ -33,6 +37,20 @@ pub struct M2NoPrecomp<E: Pairing> {
     pub g2: E::G2Affine,
     /// The G2 generator multiplied by tau
     pub g2x: E::G2Affine,
+    /// The settings for opening and verifying, see [`ConfigurableScheme`]
+    pub config: SchemeConfig,
+}
+
+// Written out since `Compress` isn't `Debug`
+impl<E: Pairing> fmt::Debug for M2NoPrecomp<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("M2NoPrecomp")
+            .field("powers_of_g1", &self.powers_of_g1)
+            .field("g2", &self.g2)
+            .field("g2x", &self.g2x)
+            .field("config", &self.config)
+            .finish()
+    }
 }
 
 impl<E: Pairing> M2NoPrecomp<E> {
@@ -46,6 +64,7 @@ impl<E: Pairing> M2NoPrecomp<E> {
             powers_of_g1,
             g2,
             g2x,
+            config: SchemeConfig::default(),
         }
     }
 
@@ -58,14 +77,30 @@ impl<E: Pairing> M2NoPrecomp<E> {
         )
     }
 
-    /// Generate a new scheme with random generators and powers of tau
-    pub fn new(max_coeffs: usize, rng: &mut impl RngCore) -> Self {
+    /// Generate a new scheme with random generators and powers of tau. The secret is sampled from
+    /// the given RNG, which doesn't require `std`.
+    pub fn new(max_coeffs: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self {
         let x = E::ScalarField::rand(rng);
         let g1 = E::G1::rand(rng);
         let g2 = E::G2::rand(rng);
         Self::new_from_scalar(x, g1, g2, max_coeffs)
     }
 
+    /// Generate a new scheme whose powers of tau in G1 are multiples of `base`, e.g. a
+    /// nothing-up-my-sleeve point from hashing to the curve, rather than a random point. The
+    /// secret and the G2 generator are sampled from the given RNG. Verification checks
+    /// commitments against the G2 generator and its multiple by the same secret, so proofs only
+    /// verify with the scheme made here, not with one sharing `base` but not the secret.
+    pub fn new_with_base(
+        max_coeffs: usize,
+        base: E::G1,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Self {
+        let x = E::ScalarField::rand(rng);
+        let g2 = E::G2::rand(rng);
+        Self::new_from_scalar(x, base, g2, max_coeffs)
+    }
+
     /// Generate a new scheme from a known secret scalar
     pub fn new_from_scalar(x: E::ScalarField, g1: E::G1, g2: E::G2, max_coeffs: usize) -> Self {
         let x_powers = gen_powers(x, max_coeffs);
@@ -74,6 +109,77 @@ impl<E: Pairing> M2NoPrecomp<E> {
 
         Self::new_from_affine(powers_of_g1, g2.into_affine(), g2x)
     }
+
+    /// Computes the vanishing polynomial and lagrange polynomials for `points`, then the
+    /// pairing equation `verify` checks and the gamma challenge
+    #[allow(clippy::too_many_arguments)]
+    fn folded_equation<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.limits.check(commits.len(), points.len())?;
+        let vp = vanishing_polynomial(points);
+        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+        self.equation_with_lag_ctx_vanishing_poly(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            &lag_ctx,
+            &vp,
+            gamma_powers,
+        )
+    }
+
+    /// Tags the values this scheme's `prepare_verify` makes
+    fn scheme_tag(&self) -> SchemeTag<E> {
+        SchemeTag::new("method2", &self.powers_of_g1, &self.config)
+    }
+
+    /// Like [`ConfigurableScheme::equation`], for inputs already checked by `prepare_verify`
+    fn prepared_equation(
+        &self,
+        transcript: &mut Transcript,
+        prepared: &PreparedVerify<E>,
+        proof: &Proof<E>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        let vp = vanishing_polynomial(prepared.points);
+        let lag_ctx = LagrangeInterpContext::new_from_points(prepared.points)?;
+        self.unchecked_equation_with_lag_ctx_vanishing_poly(
+            transcript,
+            prepared.commits,
+            prepared.points,
+            &prepared.evals,
+            proof,
+            &lag_ctx,
+            &vp,
+            None,
+        )
+    }
+
+    /// Make a copy of this scheme that only holds enough powers of tau in G1 to commit to
+    /// polynomials of degree at most `max_degree`.
+    pub fn truncate(&self, max_degree: usize) -> Result<Self, Error> {
+        let n_coeffs = match max_degree.checked_add(1) {
+            Some(n) if n <= self.powers_of_g1.len() => n,
+            _ => {
+                return Err(Error::TooManyScalars {
+                    n_coeffs: max_degree.saturating_add(1),
+                    expected_max: self.powers_of_g1.len(),
+                })
+            }
+        };
+        Ok(Self {
+            config: self.config,
+            ..Self::new_from_affine(self.powers_of_g1[..n_coeffs].to_vec(), self.g2, self.g2x)
+        })
+    }
 }
 
 /// A proof for method 2
@@ -81,31 +187,83 @@ impl<E: Pairing> M2NoPrecomp<E> {
 pub struct Proof<E: Pairing>(pub E::G1Affine, pub E::G1Affine);
 
 impl<E: Pairing> M2NoPrecomp<E> {
-    fn open_with_vanishing_poly(
+    /// Estimates the cost of verifying a proof for `n_polys` polynomials at `n_points` points
+    /// with precomputation. Without precomputation, verifying additionally computes the lagrange
+    /// polynomials and the vanishing polynomial.
+    pub fn verify_cost(n_points: usize, n_polys: usize) -> VerifyCost {
+        VerifyCost {
+            pairings: 2,
+            // An msm of the commitments, plus scalar multiplications for [r(z)]_1 and z(z) W_1
+            g1_msm_size: n_polys + 2,
+            // A scalar multiplication for [x - z]_2
+            g2_msm_size: 1,
+            // Gamma powers, folding the evals, summing the lagrange polynomials, and evaluating
+            // r and the vanishing polynomial at z
+            field_muls: n_polys + n_polys * n_points + n_points * n_points + 2 * n_points + 1,
+        }
+    }
+
+    /// Opens the given polynomials at `points` using a precomputed vanishing polynomial for
+    /// the points, given as its coefficients. The vanishing polynomial is trusted to be correct,
+    /// which is only checked in debug builds.
+    pub fn open_with_vanishing(
         &self,
         transcript: &mut Transcript,
         evals: &[impl AsRef<[E::ScalarField]>],
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
+        vanishing_coeffs: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        let vp = DensePolynomial::from_coefficients_slice(vanishing_coeffs);
+        debug_assert_eq!(vp, vanishing_polynomial(points));
+        self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
+    }
+
+    fn open_with_vanishing_poly(
+        &self,
+        transcript: &mut Transcript,
+        evals: &(impl EvalMatrix<E::ScalarField> + ?Sized),
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<Proof<E>, Error> {
+        self.config.limits.check(polys.len(), points.len())?;
         check_opening_sizes(evals, polys, points.len())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+        #[cfg(feature = "debug-checks")]
+        crate::check_evals_consistent(evals, polys, points)?;
+        // Opening at no points claims nothing, so the proof is the identity
+        if points.is_empty() {
+            return Ok(Proof(E::G1Affine::zero(), E::G1Affine::zero()));
+        }
 
         let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
+        transcribe_points_and_evals(
+            transcript,
+            points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
 
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
 
         let gammas = gen_powers::<E::ScalarField>(gamma, self.powers_of_g1.len());
         let gamma_fis = linear_combination::<E::ScalarField>(polys, &gammas)
             .ok_or(Error::NoPolynomialsGiven)?;
         let gamma_fis_poly = DensePolynomial::from_coefficients_vec(gamma_fis);
 
-        let (h, gamma_ris_over_zs) = poly_div_q_r((&gamma_fis_poly).into(), (vp).into())?;
+        let (h, gamma_ris_over_zs) = divide_poly_auto(&gamma_fis_poly, vp)?;
+        let (h, gamma_ris_over_zs) = (h.coeffs, gamma_ris_over_zs.coeffs);
 
         let w_1 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &h)?.into_affine();
 
-        transcribe_generic(transcript, b"open W1", &w_1)?;
+        transcribe_generic_as(
+            transcript,
+            b"open W1",
+            &w_1,
+            self.config.transcript_compress,
+        )?;
         let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
 
         let gamma_ri_z = DensePolynomial::from_coefficients_vec(gamma_ris_over_zs)
@@ -123,50 +281,147 @@ impl<E: Pairing> M2NoPrecomp<E> {
         Ok(Proof(w_1, w_2))
     }
 
-    fn verify_with_lag_ctx_vanishing_poly(
+    #[allow(clippy::too_many_arguments)]
+    fn verify_with_lag_ctx_vanishing_poly<C: FoldTerms<E>>(
         &self,
         transcript: &mut Transcript,
-        commits: &[Commitment<E>],
+        commits: &[C],
         points: &[E::ScalarField],
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
         lag_ctx: &LagrangeInterpContext<E::ScalarField>,
         vp: &DensePolynomial<E::ScalarField>,
     ) -> Result<bool, Error> {
+        Ok(self
+            .equation_with_lag_ctx_vanishing_poly(
+                transcript, commits, points, evals, proof, lag_ctx, vp, None,
+            )?
+            .0
+            .check_multi_pairing())
+    }
+
+    /// Does all of [`Self::verify_with_lag_ctx_vanishing_poly`] but the pairing check, returning
+    /// the equation and the gamma challenge
+    #[allow(clippy::too_many_arguments)]
+    fn equation_with_lag_ctx_vanishing_poly<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.limits.check(commits.len(), points.len())?;
         check_verify_sizes(commits, evals, points.len())?;
+        self.unchecked_equation_with_lag_ctx_vanishing_poly(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            lag_ctx,
+            vp,
+            gamma_powers,
+        )
+    }
+
+    /// Like [`Self::equation_with_lag_ctx_vanishing_poly`], but trusts that the sizes of the
+    /// inputs were already checked, as `prepare_verify` does
+    #[allow(clippy::too_many_arguments)]
+    fn unchecked_equation_with_lag_ctx_vanishing_poly<C: FoldTerms<E>>(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[C],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        lag_ctx: &LagrangeInterpContext<E::ScalarField>,
+        vp: &DensePolynomial<E::ScalarField>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.config.check_commits(commits)?;
+        if points.is_empty() {
+            let holds = proof.0.is_zero() && proof.1.is_zero();
+            return Ok((
+                PairingEquation::vacuous(holds, self.powers_of_g1[0], self.g2),
+                E::ScalarField::zero(),
+            ));
+        }
 
         let field_size_bytes = get_field_size::<E::ScalarField>();
-        transcribe_points_and_evals(transcript, points, evals, field_size_bytes)?;
+        transcribe_points_and_evals(
+            transcript,
+            points,
+            evals,
+            field_size_bytes,
+            self.config.transcript_compress,
+        )?;
 
-        let gamma = get_challenge(transcript, b"open gamma", field_size_bytes);
-        transcribe_generic(transcript, b"open W1", &proof.0)?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        transcribe_generic_as(
+            transcript,
+            b"open W1",
+            &proof.0,
+            self.config.transcript_compress,
+        )?;
         let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
 
         let zeros_z = vp.evaluate(&chal_z);
 
         // Get the r_i polynomials with lagrange interp. These could be precomputed.
-        let gammas = gen_powers(gamma, evals.len());
+        let generated;
+        let gammas = match gamma_powers {
+            Some(powers) => supplied_powers(powers, gamma, evals.len())?,
+            None => {
+                generated = gen_powers(gamma, evals.len());
+                &generated
+            }
+        };
         // Get the gamma^i r_i polynomials with lagrange interp. This does both the lagrange interp
         // and the gamma mul in one step so we can just lagrange interp once.
-        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, &gammas)?.coeffs;
+        let gamma_ris = lag_ctx.lagrange_interp_linear_combo(evals, gammas)?.coeffs;
         let gamma_ris_z = DensePolynomial::from_coefficients_vec(gamma_ris).evaluate(&chal_z);
+
         let gamma_ris_z_pt = self.powers_of_g1[0].mul(gamma_ris_z);
 
         // Then do a single msm of the gammas and commitments
-        let cms = commits.iter().map(|i| i.0).collect::<Vec<_>>();
-        let gamma_cm_pt = crate::curve_msm::<E::G1>(&cms, gammas.as_ref())?;
+        let (cms, cm_scalars) = fold_terms(commits, gammas);
+        let gamma_cm_pt = crate::curve_msm::<E::G1>(&cms, &cm_scalars)?;
 
         let f = gamma_cm_pt - gamma_ris_z_pt - proof.0.mul(zeros_z);
 
         let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
-        Ok(E::pairing(f, self.g2) == E::pairing(proof.1, x_minus_z))
+        // e(f, g2) == e(W_2, [x - z]_2)
+        Ok((
+            PairingEquation {
+                lhs: (f.into_affine(), self.g2),
+                rhs: (proof.1, x_minus_z.into_affine()),
+            },
+            gamma,
+        ))
+    }
+}
+
+impl<E: Pairing> Ceremony<E> for M2NoPrecomp<E> {
+    fn g2_tau(&self) -> Result<E::G2Affine, Error> {
+        Ok(self.g2x)
     }
 }
 
 impl<E: Pairing> Committer<E> for M2NoPrecomp<E> {
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
-        let res = crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())?;
-        Ok(Commitment(res.into_affine()))
+        Ok(Commitment(self.commit_projective(poly)?.into_affine()))
+    }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        &self.powers_of_g1
+    }
+
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        crate::curve_msm::<E::G1>(&self.powers_of_g1, poly.as_ref())
     }
 }
 
@@ -180,10 +435,34 @@ impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
         polys: &[impl AsRef<[E::ScalarField]>],
         points: &[E::ScalarField],
     ) -> Result<Proof<E>, Error> {
-        let vp = vanishing_polynomial(points.as_ref());
+        self.config.limits.check(polys.len(), points.len())?;
+        let vp = vanishing_polynomial(points);
         self.open_with_vanishing_poly(transcript, evals, polys, points, &vp)
     }
 
+    fn open_point_major(
+        &self,
+        transcript: &mut Transcript,
+        evals_by_point: &[Vec<E::ScalarField>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Proof<E>, Error> {
+        self.config.limits.check(polys.len(), points.len())?;
+        let evals = PointMajor::new(evals_by_point, polys.len())?;
+        let vp = vanishing_polynomial(points);
+        self.open_with_vanishing_poly(transcript, &evals, polys, points, &vp)
+    }
+
+    fn prepare_verify<'a>(
+        &self,
+        commits: &'a [Commitment<E>],
+        points: &'a [E::ScalarField],
+        evals: &'a [impl AsRef<[E::ScalarField]>],
+    ) -> Result<PreparedVerify<'a, E>, Error> {
+        self.config
+            .prepare_verify(self.scheme_tag(), commits, points, evals)
+    }
+
     fn verify(
         &self,
         transcript: &mut Transcript,
@@ -192,27 +471,735 @@ impl<E: Pairing> PolyMultiProofNoPrecomp<E> for M2NoPrecomp<E> {
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Proof<E>,
     ) -> Result<bool, Error> {
-        let vp = vanishing_polynomial(points);
-        let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
-        self.verify_with_lag_ctx_vanishing_poly(
-            transcript, commits, points, evals, proof, &lag_ctx, &vp,
-        )
+        let prepared = self.prepare_verify(commits, points, evals)?;
+        self.finish_verify(transcript, prepared, proof)
+    }
+
+    fn finish_verify(
+        &self,
+        transcript: &mut Transcript,
+        prepared: PreparedVerify<E>,
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        prepared.check_scheme(self.scheme_tag())?;
+        let (eq, _) = self.prepared_equation(transcript, &prepared, proof)?;
+        Ok(eq.check_multi_pairing())
+    }
+}
+
+impl<E: Pairing> ConfigurableScheme<E> for M2NoPrecomp<E> {
+    fn config(&self) -> &SchemeConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut SchemeConfig {
+        &mut self.config
+    }
+
+    fn equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.folded_equation(transcript, commits, points, evals, proof, gamma_powers)
+    }
+
+    fn lazy_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[LazyCommitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error> {
+        self.folded_equation(transcript, commits, points, evals, proof, None)
+    }
+
+    fn check_equation(&self, eq: &PairingEquation<E>) -> bool {
+        eq.check_multi_pairing()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::M2NoPrecomp;
+    use super::{M2NoPrecomp, Proof};
     use crate::{
+        poly_ops::embed_subfield_coeffs,
         test_rng,
-        testing::{test_basic_no_precomp, test_size_errors},
+        testing::{
+            random_valid_opening, test_agree_on_set, test_basic_no_precomp, test_binding,
+            test_challenge_bits, test_commit_and_open_rs, test_commit_batch, test_commit_be,
+            test_commit_bigint, test_commit_sparse, test_constant_polys, test_empty_points,
+            test_identity_commitments, test_linked_rounds, test_matrix_column,
+            test_new_from_seeded_rng, test_open_by_indices, test_open_mixed_domain,
+            test_open_point_major, test_open_sharded, test_open_with_vanishing, test_padded_polys,
+            test_phase_labels, test_prepare_verify, test_size_errors, test_size_limits,
+            test_transform, test_unaggregated, test_vanishes_on, test_verify_lazy,
+            test_verify_miller_output, test_verify_with_challenge_out, test_verify_with_powers,
+        },
+        traits::{Committer, ConfigurableScheme, PolyMultiProofNoPrecomp},
+        vanishing_polynomial, Error, SizeLimits,
     };
-    use ark_bls12_381::Bls12_381;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::One;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
 
     #[test]
     fn test_basic_open_works() {
         let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
         test_basic_no_precomp(&s);
         test_size_errors(&s);
+        test_padded_polys(&s);
+        test_linked_rounds(&s);
+        test_binding(&s);
+        test_transform(&s);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    fn test_inconsistent_evals_error() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        crate::testing::test_inconsistent_evals(&s);
+    }
+
+    #[test]
+    fn test_open_too_large_errors() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        crate::testing::test_open_too_large(&s, 256);
+    }
+
+    #[test]
+    fn test_size_limits_error_early() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        test_size_limits(&s);
+        let limited = s.with_limits(SizeLimits {
+            max_polys: Some(3),
+            max_points: Some(4),
+        });
+        assert_eq!(
+            limited.config.limits,
+            limited.truncate(100).unwrap().config.limits
+        );
+    }
+
+    #[test]
+    fn test_agree_on_set_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_agree_on_set(&s);
+    }
+
+    #[test]
+    fn test_vanishes_on_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_vanishes_on(&s);
+    }
+
+    #[test]
+    fn test_open_by_indices_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_open_by_indices(&s);
+    }
+
+    #[test]
+    fn test_open_point_major_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_open_point_major(&s);
+    }
+
+    #[test]
+    fn test_prepare_verify_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_prepare_verify(&s);
+    }
+
+    #[test]
+    fn test_prepared_by_other_scheme() {
+        use crate::{method1::M1NoPrecomp, msm::blst::BlstMSMEngine};
+        use ark_ec::Group;
+        let (x, g1, g2) = (
+            Fr::rand(&mut test_rng()),
+            G1Projective::generator(),
+            ark_bls12_381::G2Projective::generator(),
+        );
+        let s = M2NoPrecomp::<Bls12_381>::new_from_scalar(x, g1, g2, 64);
+        let (commits, points, evals, open) = random_valid_opening(&s, 5, 3, 30, &mut test_rng());
+        let finish = |prepared| s.finish_verify(&mut Transcript::new(b"testing"), prepared, &open);
+
+        // Method 1 over the same setup, and method 2 over a different one
+        let m1 = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new_from_scalar(x, g1, g2, 64, 8);
+        let other = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        for prepared in [
+            m1.prepare_verify(&commits, &points, &evals).unwrap(),
+            other.prepare_verify(&commits, &points, &evals).unwrap(),
+        ] {
+            assert_eq!(Err(Error::PreparedByOtherScheme), finish(prepared));
+        }
+        let prepared = s.prepare_verify(&commits, &points, &evals).unwrap();
+        assert_eq!(Ok(true), finish(prepared));
+    }
+
+    #[test]
+    fn test_constant_polys_work() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_constant_polys(&s);
+    }
+
+    #[test]
+    fn test_phase_labels_work() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_phase_labels(&s);
+    }
+
+    #[test]
+    fn test_empty_points_work() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_empty_points(&s);
+    }
+
+    #[test]
+    fn test_open_mixed_domain_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_open_mixed_domain(&s);
+    }
+
+    #[test]
+    fn test_open_sharded_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_open_sharded(&s);
+    }
+
+    #[test]
+    fn test_unaggregated_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_unaggregated(&s);
+    }
+
+    #[test]
+    fn test_identity_commitments_rejected() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_identity_commitments(&s);
+        let strict = s.with_reject_identity_commitments(true);
+        assert!(
+            strict
+                .truncate(10)
+                .unwrap()
+                .config
+                .reject_identity_commitments
+        );
+    }
+
+    #[test]
+    fn test_verify_miller_output_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_verify_miller_output(&s);
+    }
+
+    #[test]
+    fn test_embed_subfield_coeffs() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        // Small integers stand in for the subfield, small enough that evaluating doesn't overflow
+        let sub_coeffs = (0..11u64).map(|i| (i * 37) % 256).collect::<Vec<_>>();
+        let sub_points = (1..=5u64).collect::<Vec<_>>();
+        let sub_evals = sub_points
+            .iter()
+            .map(|x| sub_coeffs.iter().rev().fold(0u64, |acc, c| acc * x + c))
+            .collect::<Vec<_>>();
+
+        let coeffs = embed_subfield_coeffs::<u64, Fr>(&sub_coeffs);
+        let points = embed_subfield_coeffs::<u64, Fr>(&sub_points);
+        let evals = vec![embed_subfield_coeffs::<u64, Fr>(&sub_evals)];
+        let poly = DensePolynomial::from_coefficients_slice(&coeffs);
+        assert!(points
+            .iter()
+            .zip(&evals[0])
+            .all(|(x, y)| poly.evaluate(x) == *y));
+
+        let commit = s.commit(&coeffs).unwrap();
+        let open = s
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &[&coeffs],
+                &points,
+            )
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &[commit],
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_verify_with_powers_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_verify_with_powers(&s);
+    }
+
+    #[test]
+    fn test_verify_with_challenge_out_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_verify_with_challenge_out(&s);
+    }
+
+    #[test]
+    fn test_commit_be_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_commit_be(&s, 64);
+    }
+
+    #[test]
+    fn test_lagrange_basis_commitments() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        // Degree below the number of points, so this is its own interpolant
+        let poly = DensePolynomial::<Fr>::rand(7, &mut test_rng());
+        let evals = points.iter().map(|x| poly.evaluate(x)).collect::<Vec<_>>();
+        let basis = s.lagrange_basis_commitments(&points).unwrap();
+        assert_eq!(points.len(), basis.len());
+        let combined = basis
+            .iter()
+            .zip(evals.iter())
+            .map(|(c, y)| c.0 * y)
+            .sum::<G1Projective>();
+        assert_eq!(s.commit(&poly.coeffs).unwrap().0, combined.into_affine());
+
+        let mut dup = points.clone();
+        dup[1] = dup[0];
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            s.lagrange_basis_commitments(&dup)
+        );
+    }
+
+    #[test]
+    fn test_versioned_proofs() {
+        use crate::traits::{VersionedProof, PROOF_FORMAT_VERSION};
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let (commits, points, evals, open) = random_valid_opening(&s, 4, 3, 30, &mut test_rng());
+        let bytes = open.serialize_versioned().unwrap();
+        assert_eq!(PROOF_FORMAT_VERSION, bytes[0]);
+        let read = Proof::deserialize_versioned(&bytes).unwrap();
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &read
+            )
+        );
+
+        // A later version of the crate that bumped the format rejects the stored proof
+        #[derive(CanonicalSerialize, CanonicalDeserialize)]
+        struct NextProof(Proof<Bls12_381>);
+        impl VersionedProof for NextProof {
+            const VERSION: u8 = 2;
+        }
+        assert_eq!(
+            Err(Error::UnsupportedProofVersion {
+                found: 1,
+                supported: 2
+            }),
+            NextProof::deserialize_versioned(&bytes).map(|_| ())
+        );
+        assert_eq!(
+            Err(Error::SerializationError),
+            Proof::<Bls12_381>::deserialize_versioned(&[]).map(|_| ())
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            Err(Error::SerializationError),
+            Proof::<Bls12_381>::deserialize_versioned(&trailing).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_new_with_base() {
+        use ark_ec::Group;
+
+        let base = G1Projective::generator() * Fr::from(7u64);
+        let s = M2NoPrecomp::<Bls12_381>::new_with_base(64, base, &mut test_rng());
+        assert_eq!(base.into_affine(), s.powers_of_g1[0]);
+        test_basic_no_precomp(&s);
+        // The constant polynomial one commits to the base
+        assert_eq!(base.into_affine(), s.commit([Fr::one()]).unwrap().0);
+    }
+
+    #[test]
+    fn test_commit_sparse_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        test_commit_sparse(&s, 256);
+    }
+
+    #[test]
+    fn test_commit_bigint_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        test_commit_bigint(&s, 256);
+    }
+
+    #[test]
+    fn test_verify_in_ceremony() {
+        use crate::traits::Ceremony;
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let other = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        // A smaller setup from the same ceremony still verifies
+        let small = s.truncate(63).unwrap();
+        let id = s.ceremony_id().unwrap();
+        assert_eq!(Ok(id), small.ceremony_id());
+        assert_ne!(Ok(id), other.ceremony_id());
+
+        let (commits, points, evals, open) = random_valid_opening(&s, 4, 3, 50, &mut test_rng());
+        assert_eq!(
+            Ok(true),
+            small.verify_in_ceremony(
+                &id,
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Err(Error::CeremonyMismatch),
+            other.verify_in_ceremony(
+                &id,
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_matrix_column_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_matrix_column(&s, 63);
+    }
+
+    #[test]
+    fn test_verify_lazy_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_verify_lazy(&s);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let t = s.truncate(63).expect("Failed to truncate");
+        assert_eq!(t.powers_of_g1.len(), 64);
+
+        let poly = DensePolynomial::<Fr>::rand(63, &mut test_rng()).coeffs;
+        assert_eq!(
+            s.commit(&poly).expect("Commit failed").0,
+            t.commit(&poly).expect("Commit failed").0
+        );
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 257,
+                expected_max: 256
+            }),
+            s.truncate(256).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_commit_and_open_rs_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        test_commit_and_open_rs(&s);
+    }
+
+    #[test]
+    fn test_open_with_vanishing_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        test_open_with_vanishing(&s, |t, e, p, x, v| s.open_with_vanishing(t, e, p, x, v));
+    }
+
+    #[test]
+    fn test_commit_batch_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_commit_batch(&s, 64);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_commit_batch_in_pool() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..8)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| {
+                let p = DensePolynomial::from_coefficients_slice(p);
+                points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let in_pool = s.commit_batch_in_pool(&pool, &polys);
+        let global = s.commit_batch(&polys);
+        assert_eq!(in_pool, global);
+
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+            .unwrap();
+        let open_in_pool = s
+            .open_in_pool(
+                &pool,
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &polys,
+                &points,
+            )
+            .unwrap();
+        assert_eq!((open.0, open.1), (open_in_pool.0, open_in_pool.1));
+    }
+
+    #[test]
+    fn test_new_from_seeded_rng_works() {
+        let s = test_new_from_seeded_rng(|rng| M2NoPrecomp::<Bls12_381>::new(16, rng));
+        assert_eq!(s.powers_of_g1.len(), 16);
+    }
+
+    #[test]
+    fn test_verify_from_commit_bytes() {
+        use crate::traits::AsBytes;
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commit_bytes = coeffs
+            .iter()
+            .flat_map(|p| s.commit(p).unwrap().to_bytes().unwrap())
+            .collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+
+        assert_eq!(
+            Ok(true),
+            s.verify_from_commit_bytes(
+                &mut Transcript::new(b"testing"),
+                &commit_bytes,
+                3,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Err(Error::InvalidCommitBytesLength {
+                n_bytes: 143,
+                point_size: 48
+            }),
+            s.verify_from_commit_bytes(
+                &mut Transcript::new(b"testing"),
+                &commit_bytes[1..],
+                3,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 4,
+                got: 3
+            }),
+            s.verify_from_commit_bytes(
+                &mut Transcript::new(b"testing"),
+                &commit_bytes,
+                4,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_commit_from_roots() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        for n in [0, 1, 40, 63] {
+            let roots = (0..n)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                s.commit(vanishing_polynomial(&roots).coeffs),
+                s.commit_from_roots(&roots)
+            );
+        }
+        let roots = (0..64)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Err(Error::TooManyScalars {
+                n_coeffs: 65,
+                expected_max: 64
+            }),
+            s.commit_from_roots(&roots)
+        );
+    }
+
+    #[test]
+    fn test_verify_raw() {
+        use crate::traits::AsBytes;
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let (commits, points, evals, open) = random_valid_opening(&s, 4, 3, 30, &mut test_rng());
+        let commit_bytes = commits
+            .iter()
+            .map(|c| c.to_bytes().unwrap())
+            .collect::<Vec<_>>();
+        let raw = commit_bytes.iter().map(|b| &b[..]).collect::<Vec<_>>();
+        assert_eq!(
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            ),
+            s.verify_raw(
+                &mut Transcript::new(b"testing"),
+                &raw,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify_raw(
+                &mut Transcript::new(b"testing"),
+                &[raw[1], raw[0], raw[2]],
+                &points,
+                &evals,
+                &open
+            )
+        );
+
+        let mut bad = raw.clone();
+        bad[1] = &commit_bytes[1][1..];
+        assert_eq!(
+            Err(Error::SerializationError),
+            s.verify_raw(
+                &mut Transcript::new(b"testing"),
+                &bad,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        let mut long = commit_bytes[1].to_vec();
+        long.push(0);
+        bad[1] = &long;
+        assert_eq!(
+            Err(Error::SerializationError),
+            s.verify_raw(
+                &mut Transcript::new(b"testing"),
+                &bad,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_challenge_bits_works() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        test_challenge_bits(&s);
+        let capped = s.with_challenge_bits(128).unwrap();
+        assert_eq!(
+            Some(128),
+            capped.truncate(31).unwrap().config.challenge_bits
+        );
+    }
+
+    #[test]
+    fn test_transcript_compress() {
+        use ark_serialize::Compress;
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let u = s.clone().with_transcript_compress(Compress::No);
+        assert!(matches!(
+            u.truncate(31).unwrap().config.transcript_compress,
+            Compress::No
+        ));
+
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let open = u
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+
+        assert_eq!(
+            Ok(true),
+            u.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        // W1 is absorbed uncompressed by the prover, so the challenges diverge
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
     }
 }
@@ -0,0 +1,391 @@
+//! # Openings across several point sets
+//! The general case of BDFG21 method 2, where each polynomial is opened at its own point set and
+//! the whole opening is still a single two element [`Proof`].
+//!
+//! An opening is given as a list of assignments `(poly, point_set)`, with one row of evals per
+//! assignment holding polynomial `poly` at each point of `point_sets[point_set]`. A polynomial
+//! can be assigned to more than one point set. With $T$ the union of the point sets used, the
+//! prover commits to $h = \sum_j \gamma_j (f_j - r_j) / Z_{S_j}$ and the verifier checks it
+//! against the combination weighted by $Z_{T \setminus S_j}(z)$.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{One, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::Compress;
+use ark_std::{vec, vec::Vec};
+use core::ops::{Div, Mul};
+use merlin::Transcript;
+
+use super::{M2NoPrecomp, Proof};
+use crate::{
+    batch::PairingEquation, check_poly_sizes, gen_powers, get_challenge, get_field_size,
+    lagrange::LagrangeInterpContext, poly_div_q_r, transcribe_generic_as, transcript,
+    vanishing_polynomial, Commitment, Error,
+};
+
+impl<E: Pairing> M2NoPrecomp<E> {
+    /// Opens `polys` at several point sets in one proof. `evals[k]` holds polynomial
+    /// `assignments[k].0` at each point of `point_sets[assignments[k].1]`.
+    pub fn open_multi_set(
+        &self,
+        transcript: &mut Transcript,
+        assignments: &[(usize, usize)],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+    ) -> Result<Proof<E>, Error> {
+        let union = check_multi_set_sizes(assignments, evals, polys.len(), point_sets)?;
+        self.config.limits.check(assignments.len(), union.len())?;
+        check_poly_sizes(polys, self.powers_of_g1.len())?;
+
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_multi_set(
+            transcript,
+            assignments,
+            evals,
+            point_sets,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        let gammas = gen_powers(gamma, assignments.len());
+
+        // The gamma weighted sum of the polynomials opened at each point set
+        let mut set_polys = vec![DensePolynomial::<E::ScalarField>::zero(); point_sets.len()];
+        for (&(poly, set), g) in assignments.iter().zip(&gammas) {
+            let poly = DensePolynomial::from_coefficients_slice(polys[poly].as_ref());
+            set_polys[set] += &(&poly * *g);
+        }
+
+        // h is the sum of the quotients of each set's polynomial by the set's vanishing polynomial
+        let mut h = DensePolynomial::zero();
+        let mut remainders = Vec::new();
+        for set in used_sets(assignments, point_sets.len()) {
+            let vp = vanishing_polynomial(point_sets[set].as_ref());
+            let (q, r) = poly_div_q_r((&set_polys[set]).into(), (&vp).into())?;
+            h += &DensePolynomial::from_coefficients_vec(q);
+            remainders.push((set, DensePolynomial::from_coefficients_vec(r)));
+        }
+        let w_1 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &h)?.into_affine();
+
+        transcribe_generic_as(
+            transcript,
+            b"open W1",
+            &w_1,
+            self.config.transcript_compress,
+        )?;
+        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+
+        // L = sum_j Z_{T \ S_j}(z) (f_j - r_j(z)) - Z_T(z) h, which is zero at z
+        let mut l = &h * -vanishing_polynomial(&union).evaluate(&chal_z);
+        for (set, r) in remainders {
+            let weight = vanishing_outside(&union, point_sets[set].as_ref(), chal_z);
+            let r_z = DensePolynomial::from_coefficients_vec(vec![r.evaluate(&chal_z)]);
+            l += &(&(&set_polys[set] - &r_z) * weight);
+        }
+        let x_minus_z =
+            DensePolynomial::from_coefficients_vec(vec![-chal_z, E::ScalarField::one()]);
+        let l_quotient = l.div(&x_minus_z);
+
+        let w_2 = crate::curve_msm::<E::G1>(&self.powers_of_g1, &l_quotient)?.into_affine();
+        Ok(Proof(w_1, w_2))
+    }
+
+    /// Verifies a proof made with [`Self::open_multi_set`]. `commits[i]` is the commitment to
+    /// polynomial `i`.
+    pub fn verify_multi_set(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        assignments: &[(usize, usize)],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        point_sets: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        let union = check_multi_set_sizes(assignments, evals, commits.len(), point_sets)?;
+        self.config.limits.check(assignments.len(), union.len())?;
+        self.config.check_commits(commits)?;
+
+        let field_size_bytes = get_field_size::<E::ScalarField>();
+        transcribe_multi_set(
+            transcript,
+            assignments,
+            evals,
+            point_sets,
+            self.config.transcript_compress,
+        )?;
+        let gamma: E::ScalarField = self.config.gamma_challenge(transcript, field_size_bytes);
+        transcribe_generic_as(
+            transcript,
+            b"open W1",
+            &proof.0,
+            self.config.transcript_compress,
+        )?;
+        let chal_z = get_challenge(transcript, b"open z", field_size_bytes);
+        let gammas = gen_powers(gamma, assignments.len());
+
+        let mut set_weights = vec![E::ScalarField::zero(); point_sets.len()];
+        let mut ris_z = E::ScalarField::zero();
+        for set in used_sets(assignments, point_sets.len()) {
+            let points = point_sets[set].as_ref();
+            let weight = vanishing_outside(&union, points, chal_z);
+            let (rows, row_gammas): (Vec<_>, Vec<_>) = assignments
+                .iter()
+                .zip(evals.iter().zip(&gammas))
+                .filter(|((_, s), _)| *s == set)
+                .map(|(_, (e, g))| (e.as_ref(), *g))
+                .unzip();
+            let lag_ctx = LagrangeInterpContext::new_from_points(points)?;
+            let r = lag_ctx.lagrange_interp_linear_combo(&rows, &row_gammas)?;
+            ris_z += weight * r.evaluate(&chal_z);
+            set_weights[set] = weight;
+        }
+
+        let (cms, cm_scalars): (Vec<_>, Vec<_>) = assignments
+            .iter()
+            .zip(&gammas)
+            .map(|(&(poly, set), g)| (commits[poly].0, *g * set_weights[set]))
+            .unzip();
+        let cm_pt = crate::curve_msm::<E::G1>(&cms, &cm_scalars)?;
+        let zeros_z = vanishing_polynomial(&union).evaluate(&chal_z);
+        let f = cm_pt - self.powers_of_g1[0].mul(ris_z) - proof.0.mul(zeros_z);
+
+        let x_minus_z = self.g2x.into_group() - self.g2.into_group().mul(&chal_z);
+        // e(f, g2) == e(W_2, [x - z]_2)
+        Ok(PairingEquation::<E> {
+            lhs: (f.into_affine(), self.g2),
+            rhs: (proof.1, x_minus_z.into_affine()),
+        }
+        .check_multi_pairing())
+    }
+}
+
+/// Checks the assignments index valid polynomials and point sets with the right number of evals,
+/// returning the sorted union of the point sets used
+fn check_multi_set_sizes<F: Ord + Copy>(
+    assignments: &[(usize, usize)],
+    evals: &[impl AsRef<[F]>],
+    n_polys: usize,
+    point_sets: &[impl AsRef<[F]>],
+) -> Result<Vec<F>, Error> {
+    if assignments.is_empty() {
+        return Err(Error::NoPolynomialsGiven);
+    }
+    if evals.len() != assignments.len() {
+        return Err(Error::EvalsAndPolysDifferentSizes {
+            n_eval_rows: evals.len(),
+            n_polys: assignments.len(),
+        });
+    }
+    for (k, (&(poly, set), e)) in assignments.iter().zip(evals).enumerate() {
+        if poly >= n_polys {
+            return Err(Error::InvalidPolyIndex { idx: poly, n_polys });
+        }
+        let points = point_sets
+            .get(set)
+            .ok_or(Error::InvalidPointSetIndex {
+                idx: set,
+                n_point_sets: point_sets.len(),
+            })?
+            .as_ref();
+        if points.is_empty() {
+            return Err(Error::NoPointsGiven);
+        }
+        if e.as_ref().len() != points.len() {
+            return Err(Error::EvalsIncorrectSize {
+                poly: k,
+                n_evals: e.as_ref().len(),
+                expected: points.len(),
+            });
+        }
+    }
+    let mut union = used_sets(assignments, point_sets.len())
+        .flat_map(|set| point_sets[set].as_ref().iter().copied())
+        .collect::<Vec<_>>();
+    union.sort_unstable();
+    union.dedup();
+    Ok(union)
+}
+
+/// The indices of the point sets with at least one polynomial assigned, in order
+fn used_sets(assignments: &[(usize, usize)], n_sets: usize) -> impl Iterator<Item = usize> {
+    let mut used = vec![false; n_sets];
+    for &(_, set) in assignments {
+        used[set] = true;
+    }
+    (0..n_sets).filter(move |&set| used[set])
+}
+
+/// $Z_{T \setminus S}(z)$, the vanishing polynomial of the points of `union` not in `points`
+fn vanishing_outside<F: Ord + One + Copy + core::ops::Sub<Output = F>>(
+    union: &[F],
+    points: &[F],
+    z: F,
+) -> F {
+    union
+        .iter()
+        .filter(|t| !points.contains(t))
+        .fold(F::one(), |acc, &t| acc * (z - t))
+}
+
+fn transcribe_multi_set<F: ark_serialize::CanonicalSerialize>(
+    transcript: &mut Transcript,
+    assignments: &[(usize, usize)],
+    evals: &[impl AsRef<[F]>],
+    point_sets: &[impl AsRef<[F]>],
+    compress: Compress,
+) -> Result<(), Error> {
+    transcript::append_u64(transcript, b"open n point sets", point_sets.len() as u64);
+    for points in point_sets {
+        transcribe_generic_as(transcript, b"open points", &points.as_ref(), compress)?;
+    }
+    for &(poly, set) in assignments {
+        transcript::append_u64(transcript, b"open poly", poly as u64);
+        transcript::append_u64(transcript, b"open point set", set as u64);
+    }
+    let rows = evals.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+    transcribe_generic_as(transcript, b"open evals", &rows, compress)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{method2::M2NoPrecomp, test_rng, traits::Committer, Error};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::One;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_multi_set_open_verify() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let rand_points = |n: usize| {
+            (0..n)
+                .map(|_| Fr::rand(&mut test_rng()))
+                .collect::<Vec<_>>()
+        };
+        let mut point_sets = vec![rand_points(4), rand_points(3)];
+        let polys = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let evals_for = |assignments: &[(usize, usize)], point_sets: &[Vec<Fr>]| {
+            assignments
+                .iter()
+                .map(|&(poly, set)| {
+                    point_sets[set]
+                        .iter()
+                        .map(|x| polys[poly].evaluate(x))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let open_verify = |assignments: &[(usize, usize)], point_sets: &[Vec<Fr>]| {
+            let evals = evals_for(assignments, point_sets);
+            let proof = s
+                .open_multi_set(
+                    &mut Transcript::new(b"testing"),
+                    assignments,
+                    &evals,
+                    &coeffs,
+                    point_sets,
+                )
+                .unwrap();
+            let verify = |evals: &[Vec<Fr>]| {
+                s.verify_multi_set(
+                    &mut Transcript::new(b"testing"),
+                    &commits,
+                    assignments,
+                    evals,
+                    point_sets,
+                    &proof,
+                )
+            };
+            assert_eq!(Ok(true), verify(&evals));
+            let mut bad_evals = evals.clone();
+            bad_evals[assignments.len() - 1][0] += Fr::one();
+            assert_eq!(Ok(false), verify(&bad_evals));
+        };
+
+        // Two disjoint groups of polynomials, each at its own point set
+        let assignments = [(0, 0), (1, 0), (2, 1), (3, 1), (4, 1)];
+        open_verify(&assignments, &point_sets);
+        // Only one of the point sets used
+        open_verify(&[(0, 1), (3, 1)], &point_sets);
+        // A polynomial at two overlapping point sets
+        point_sets[1][0] = point_sets[0][2];
+        open_verify(&[(0, 0), (0, 1), (1, 1)], &point_sets);
+
+        // Verifying against different points fails
+        let evals = evals_for(&assignments, &point_sets);
+        let proof = s
+            .open_multi_set(
+                &mut Transcript::new(b"testing"),
+                &assignments,
+                &evals,
+                &coeffs,
+                &point_sets,
+            )
+            .unwrap();
+        let mut other_points = point_sets.clone();
+        other_points[0][1] = Fr::rand(&mut test_rng());
+        assert_eq!(
+            Ok(false),
+            s.verify_multi_set(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &assignments,
+                &evals,
+                &other_points,
+                &proof
+            )
+        );
+    }
+
+    #[test]
+    fn test_multi_set_errors() {
+        let s = M2NoPrecomp::<Bls12_381>::new(16, &mut test_rng());
+        let point_sets = vec![vec![Fr::one()]];
+        let coeffs = vec![vec![Fr::one(); 4]; 2];
+        let open = |assignments: &[(usize, usize)], evals: &[Vec<Fr>]| {
+            s.open_multi_set(
+                &mut Transcript::new(b"testing"),
+                assignments,
+                evals,
+                &coeffs,
+                &point_sets,
+            )
+            .map(|_| ())
+        };
+        let evals = vec![vec![Fr::one()]];
+        assert_eq!(
+            Err(Error::InvalidPolyIndex { idx: 2, n_polys: 2 }),
+            open(&[(2, 0)], &evals)
+        );
+        assert_eq!(
+            Err(Error::InvalidPointSetIndex {
+                idx: 1,
+                n_point_sets: 1
+            }),
+            open(&[(0, 1)], &evals)
+        );
+        assert_eq!(
+            Err(Error::EvalsAndPolysDifferentSizes {
+                n_eval_rows: 1,
+                n_polys: 2
+            }),
+            open(&[(0, 0), (1, 0)], &evals)
+        );
+        assert_eq!(
+            Err(Error::EvalsIncorrectSize {
+                poly: 0,
+                n_evals: 2,
+                expected: 1
+            }),
+            open(&[(0, 0)], &[vec![Fr::one(); 2]])
+        );
+        assert_eq!(Err(Error::NoPolynomialsGiven), open(&[], &[]));
+    }
+}
@@ -1,6 +1,8 @@
 //! # BDFG Method 2 with precomputation
 use ark_ec::pairing::Pairing;
-use ark_poly::univariate::DensePolynomial;
+use ark_ff::{Field, One};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::vec::Vec;
 use merlin::Transcript;
 
@@ -9,8 +11,8 @@ use rayon::prelude::*;
 
 use super::{vanishing_polynomial, Error, Proof};
 use crate::lagrange::LagrangeInterpContext;
-use crate::traits::{Committer, PolyMultiProof};
-use crate::{cfg_iter, Commitment};
+use crate::traits::{Ceremony, Committer, PairingEquationProof, PolyMultiProof};
+use crate::{batch::PairingEquation, cfg_iter, Commitment};
 
 /// Method 2 with precomputation
 #[derive(Clone, Debug)]
@@ -42,6 +44,162 @@ impl<E: Pairing> M2Precomp<E> {
             lagrange_ctxs,
         })
     }
+
+    /// Exports the data precomputed for each point set. None of it depends on the setup, so it
+    /// can be shared and cached, then loaded with [`Self::with_precomp_table`].
+    pub fn precomp_table(&self) -> PrecompTable<E> {
+        let coeffs = |p: &DensePolynomial<E::ScalarField>| p.coeffs.clone();
+        PrecompTable {
+            point_sets: self.point_sets.clone(),
+            vanishing_polys: self.vanishing_polys.iter().map(coeffs).collect(),
+            lagrange_polys: self
+                .lagrange_ctxs
+                .iter()
+                .map(|ctx| match ctx.lag_polys() {
+                    Some(polys) => polys.iter().map(coeffs).collect(),
+                    None => ctx
+                        .lag_polys_from_matrix()
+                        .into_iter()
+                        .map(|p| p.coeffs)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Like [`Self::from_inner`], but takes the precomputed data from a table made by
+    /// [`Self::precomp_table`] instead of computing it. A table that was tampered with would
+    /// change what `verify` accepts, so each entry is checked against its point set: the
+    /// vanishing polynomial must be monic with degree the number of points and vanish on them,
+    /// and each lagrange polynomial must be one at its own point and zero at the others. Errors
+    /// with [`Error::InvalidPrecompTable`] if an entry isn't right.
+    pub fn with_precomp_table(
+        inner: super::M2NoPrecomp<E>,
+        table: PrecompTable<E>,
+    ) -> Result<Self, Error> {
+        let n_point_sets = table.point_sets.len();
+        if table.vanishing_polys.len() != n_point_sets || table.lagrange_polys.len() != n_point_sets
+        {
+            return Err(Error::InvalidInputLength {
+                expected: n_point_sets,
+                got: table.vanishing_polys.len().min(table.lagrange_polys.len()),
+            });
+        }
+        for (points, lag_polys) in table.point_sets.iter().zip(&table.lagrange_polys) {
+            if lag_polys.len() != points.len() {
+                return Err(Error::InvalidInputLength {
+                    expected: points.len(),
+                    got: lag_polys.len(),
+                });
+            }
+        }
+        let poly = |coeffs: Vec<E::ScalarField>| DensePolynomial::from_coefficients_vec(coeffs);
+        let vanishing_polys = table
+            .vanishing_polys
+            .into_iter()
+            .map(poly)
+            .collect::<Vec<_>>();
+        let lagrange_polys = table
+            .lagrange_polys
+            .into_iter()
+            .map(|ps| ps.into_iter().map(poly).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        cfg_iter!(table.point_sets)
+            .map(|(i, points)| {
+                if table_entry_is_valid(points, &vanishing_polys[i], &lagrange_polys[i]) {
+                    Ok(())
+                } else {
+                    Err(Error::InvalidPrecompTable { point_set: i })
+                }
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(M2Precomp {
+            inner,
+            point_sets: table.point_sets,
+            vanishing_polys,
+            lagrange_ctxs: lagrange_polys
+                .into_iter()
+                .map(LagrangeInterpContext::from_lag_polys)
+                .collect(),
+        })
+    }
+
+    /// Precomputes the interpolation matrix for the point set at `point_set_index`, the inverse
+    /// of its Vandermonde matrix, so verifying against the point set interpolates the evals with
+    /// a single matrix-vector product. The matrix is quadratic in the number of points, so this
+    /// suits point sets of up to a few hundred points.
+    pub fn precompute_interp_matrix(&mut self, point_set_index: usize) -> Result<(), Error> {
+        let n_point_sets = self.point_sets.len();
+        self.lagrange_ctxs
+            .get_mut(point_set_index)
+            .ok_or(Error::InvalidPointSetIndex {
+                idx: point_set_index,
+                n_point_sets,
+            })?
+            .precompute_interp_matrix();
+        Ok(())
+    }
+
+    /// Commits to the interpolant $r(x)$ of `evals` over the point set at `point_set_index`,
+    /// i.e. computes $[r(\tau)]_1$.
+    pub fn interpolant_commitment(
+        &self,
+        point_set_index: usize,
+        evals: &[E::ScalarField],
+    ) -> Result<Commitment<E>, Error> {
+        let lag_ctx =
+            self.lagrange_ctxs
+                .get(point_set_index)
+                .ok_or(Error::InvalidPointSetIndex {
+                    idx: point_set_index,
+                    n_point_sets: self.point_sets.len(),
+                })?;
+        let n_points = self.point_sets[point_set_index].len();
+        if evals.len() != n_points {
+            return Err(Error::EvalsAndPointsDifferentSizes {
+                n_points,
+                n_evals: evals.len(),
+            });
+        }
+        let r = lag_ctx.lagrange_interp_linear_combo(&[evals], &[E::ScalarField::one()])?;
+        self.inner.commit(&r.coeffs)
+    }
+}
+
+/// Whether `vanishing_poly` and `lag_polys` are the vanishing and lagrange polynomials of `points`
+fn table_entry_is_valid<F: Field>(
+    points: &[F],
+    vanishing_poly: &DensePolynomial<F>,
+    lag_polys: &[DensePolynomial<F>],
+) -> bool {
+    let n = points.len();
+    // A monic degree n polynomial vanishing on n distinct points is their vanishing polynomial,
+    // and the lagrange checks below fail if any points repeat
+    vanishing_poly.degree() == n
+        && vanishing_poly.coeffs.last() == Some(&F::one())
+        && points.iter().all(|x| vanishing_poly.evaluate(x).is_zero())
+        && lag_polys.iter().enumerate().all(|(j, l)| {
+            l.coeffs.len() <= n
+                && points.iter().enumerate().all(|(k, x)| {
+                    let expected = if j == k { F::one() } else { F::zero() };
+                    l.evaluate(x) == expected
+                })
+        })
+}
+
+/// The data [`M2Precomp`] precomputes for its point sets: the points, their vanishing
+/// polynomials, and their lagrange polynomials, all as coefficients
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PrecompTable<E: Pairing> {
+    point_sets: Vec<Vec<E::ScalarField>>,
+    vanishing_polys: Vec<Vec<E::ScalarField>>,
+    lagrange_polys: Vec<Vec<Vec<E::ScalarField>>>,
+}
+
+impl<E: Pairing> Ceremony<E> for M2Precomp<E> {
+    fn g2_tau(&self) -> Result<E::G2Affine, Error> {
+        self.inner.g2_tau()
+    }
 }
 
 impl<E: Pairing> Committer<E> for M2Precomp<E> {
@@ -51,6 +209,17 @@ impl<E: Pairing> Committer<E> for M2Precomp<E> {
     ) -> Result<Commitment<E>, Error> {
         self.inner.commit(poly)
     }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        self.inner.powers_of_g1()
+    }
+
+    fn commit_projective(
+        &self,
+        poly: impl AsRef<[<E as Pairing>::ScalarField]>,
+    ) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
 }
 
 impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
@@ -92,12 +261,45 @@ impl<E: Pairing> PolyMultiProof<E> for M2Precomp<E> {
     }
 }
 
+impl<E: Pairing> PairingEquationProof<E> for M2Precomp<E> {
+    fn verify_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Proof<E>,
+    ) -> Result<PairingEquation<E>, Error> {
+        self.inner
+            .equation_with_lag_ctx_vanishing_poly(
+                transcript,
+                commits,
+                &self.point_sets[point_set_index],
+                evals,
+                proof,
+                &self.lagrange_ctxs[point_set_index],
+                &self.vanishing_polys[point_set_index],
+                None,
+            )
+            .map(|(eq, _)| eq)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::M2Precomp;
-    use crate::{method2::M2NoPrecomp, test_rng, testing::test_basic_precomp};
+    use super::{M2Precomp, PrecompTable};
+    use crate::{
+        method2::M2NoPrecomp,
+        test_rng,
+        testing::test_basic_precomp,
+        traits::{Committer, PolyMultiProof},
+        Error,
+    };
     use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::{vec, vec::Vec, UniformRand};
+    use merlin::Transcript;
 
     #[test]
     fn test_basic_open_works() {
@@ -109,4 +311,136 @@ mod tests {
             .expect("Failed to construct");
         test_basic_precomp(&s, &points)
     }
+
+    #[test]
+    fn test_precomp_table_round_trip() {
+        let point_sets = (0..3)
+            .map(|_| {
+                (0..10)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let prover = M2Precomp::from_inner(s.clone(), point_sets.clone()).unwrap();
+
+        let mut bytes = Vec::new();
+        prover
+            .precomp_table()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        let table = PrecompTable::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(prover.precomp_table(), table);
+        let verifier = M2Precomp::with_precomp_table(s.clone(), table).unwrap();
+
+        let polys = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| point_sets[2].iter().map(|x| p.evaluate(x)).collect())
+            .collect::<Vec<Vec<_>>>();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let proof = prover
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, 2)
+            .unwrap();
+        assert_eq!(
+            Ok(true),
+            verifier.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                2,
+                &evals,
+                &proof
+            )
+        );
+
+        let mut bad_table = prover.precomp_table();
+        bad_table.lagrange_polys[1].pop();
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 10,
+                got: 9
+            }),
+            M2Precomp::with_precomp_table(s.clone(), bad_table).map(|_| ())
+        );
+
+        // Tables with the right shape but the wrong polynomials are rejected
+        let mut bad_table = prover.precomp_table();
+        bad_table.vanishing_polys[1][0] += Fr::from(1u64);
+        assert_eq!(
+            Err(Error::InvalidPrecompTable { point_set: 1 }),
+            M2Precomp::with_precomp_table(s.clone(), bad_table).map(|_| ())
+        );
+        let mut bad_table = prover.precomp_table();
+        bad_table.vanishing_polys[2].push(Fr::from(0u64));
+        bad_table.vanishing_polys[2].push(Fr::from(1u64));
+        assert_eq!(
+            Err(Error::InvalidPrecompTable { point_set: 2 }),
+            M2Precomp::with_precomp_table(s.clone(), bad_table).map(|_| ())
+        );
+        let mut bad_table = prover.precomp_table();
+        bad_table.lagrange_polys[0].swap(3, 4);
+        assert_eq!(
+            Err(Error::InvalidPrecompTable { point_set: 0 }),
+            M2Precomp::with_precomp_table(s.clone(), bad_table).map(|_| ())
+        );
+        let mut bad_table = prover.precomp_table();
+        bad_table.point_sets[2][5] = Fr::rand(&mut test_rng());
+        assert_eq!(
+            Err(Error::InvalidPrecompTable { point_set: 2 }),
+            M2Precomp::with_precomp_table(s, bad_table).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_precompute_interp_matrix() {
+        let points = (0..30)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let mut s = M2Precomp::<Bls12_381>::from_inner(s, vec![points.clone()])
+            .expect("Failed to construct");
+        let table = s.precomp_table();
+        s.precompute_interp_matrix(0).unwrap();
+        test_basic_precomp(&s, &points);
+        // The lagrange polynomials are read back out of the matrix
+        assert_eq!(table, s.precomp_table());
+        assert_eq!(
+            Err(Error::InvalidPointSetIndex {
+                idx: 1,
+                n_point_sets: 1
+            }),
+            s.precompute_interp_matrix(1)
+        );
+    }
+
+    #[test]
+    fn test_interpolant_commitment() {
+        let points = (0..30)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let s = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let s = M2Precomp::<Bls12_381>::from_inner(s, vec![points.clone()])
+            .expect("Failed to construct");
+        // A degree 29 polynomial is its own interpolant over 30 points
+        let poly = DensePolynomial::<Fr>::rand(29, &mut test_rng());
+        let evals = points.iter().map(|p| poly.evaluate(p)).collect::<Vec<_>>();
+
+        let commit = s
+            .interpolant_commitment(0, &evals)
+            .expect("Failed to commit");
+        assert_eq!(commit.0, s.commit(&poly.coeffs).expect("Commit failed").0);
+        assert_eq!(
+            Err(Error::InvalidPointSetIndex {
+                idx: 1,
+                n_point_sets: 1
+            }),
+            s.interpolant_commitment(1, &evals).map(|_| ())
+        );
+    }
 }
@@ -1,9 +1,13 @@
 //! Multi-scalar multiplication engines
 
-use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr,
+};
+use ark_ff::Zero;
 use ark_std::{marker::PhantomData, vec::Vec};
 
-use crate::{traits::MSMEngine, Error};
+use crate::{op_count::count_pairings, traits::MSMEngine, Error};
 
 /// The MSM engine for the Arkworks library
 #[derive(Clone, Copy)]
@@ -43,17 +47,55 @@ impl<E: Pairing> MSMEngine for ArkMSMEngine<E> {
         c: <Self::E as Pairing>::G1Affine,
         d: <Self::E as Pairing>::G2Affine,
     ) -> bool {
-        Self::E::pairing(a, b) == Self::E::pairing(c, d)
+        // e(a, b) == e(c, d) iff e(a, b) * e(-c, d) == 1, which needs only one final exponentiation
+        count_pairings(2);
+        Self::E::multi_pairing([a.into_group(), -c.into_group()], [b, d]).is_zero()
     }
 
     fn pairing(
         p1: <Self::E as Pairing>::G1Affine,
         q1: <Self::E as Pairing>::G2Affine,
     ) -> PairingOutput<Self::E> {
+        count_pairings(1);
         Self::E::pairing(p1, q1)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::ArkMSMEngine;
+    use crate::{test_rng, traits::MSMEngine};
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn test_pairing_eq_check_matches_naive() {
+        for _ in 0..5 {
+            let g1 = G1Affine::rand(&mut test_rng());
+            let g2 = G2Affine::rand(&mut test_rng());
+            let x = Fr::rand(&mut test_rng());
+            let y = Fr::rand(&mut test_rng());
+            // e(x y g1, g2) == e(x g1, y g2), but not e(x g1, x g2)
+            let a = (g1 * (x * y)).into_affine();
+            let c = (g1 * x).into_affine();
+            let d = (g2 * y).into_affine();
+            let d_bad = (g2 * x).into_affine();
+            for (p1, q1, p2, q2) in [(a, g2, c, d), (a, g2, c, d_bad), (a, g2, a, g2)] {
+                assert_eq!(
+                    Bls12_381::pairing(p1, q1) == Bls12_381::pairing(p2, q2),
+                    ArkMSMEngine::<Bls12_381>::pairing_eq_check(p1, q1, p2, q2)
+                );
+            }
+            assert!(ArkMSMEngine::<Bls12_381>::pairing_eq_check(a, g2, c, d));
+            let zero = G1Affine::zero();
+            assert!(ArkMSMEngine::<Bls12_381>::pairing_eq_check(
+                zero, g2, zero, d
+            ));
+        }
+    }
+}
+
 #[cfg(feature = "blst")]
 /// The MSM engine for the blst library
 pub mod blst {
@@ -62,10 +104,11 @@ pub mod blst {
         blst_p1, blst_p1_affine, blst_p1_mult, blst_p2, blst_p2_affine, blst_p2_mult, MultiPoint,
     };
     use ark_ec::{pairing::PairingOutput, AffineRepr};
-    use ark_ff::{BigInt, Zero};
+    use ark_ff::BigInt;
     use ark_serialize::CanonicalSerialize;
 
     use super::*;
+    use crate::{op_count::count_msm, trim_zeros};
 
     /// The MSM engine for the blst library
     #[derive(Clone, Copy)]
@@ -89,6 +132,7 @@ pub mod blst {
             g: &Self::G1Prepared,
             s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
         ) -> Result<<Self::E as Pairing>::G1, Error> {
+            count_msm(trim_zeros(s.as_ref()).len());
             g.msm(s.as_ref())
         }
 
@@ -96,6 +140,7 @@ pub mod blst {
             g: &Self::G2Prepared,
             s: impl AsRef<[<Self::E as Pairing>::ScalarField]>,
         ) -> Result<<Self::E as Pairing>::G2, Error> {
+            count_msm(trim_zeros(s.as_ref()).len());
             g.msm(s.as_ref())
         }
 
@@ -105,6 +150,7 @@ pub mod blst {
             p2: <Self::E as Pairing>::G1Affine,
             q2: <Self::E as Pairing>::G2Affine,
         ) -> bool {
+            count_pairings(2);
             check_pairings_equal(p1, q1, p2, q2)
         }
 
@@ -112,6 +158,7 @@ pub mod blst {
             p1: <Self::E as Pairing>::G1Affine,
             q1: <Self::E as Pairing>::G2Affine,
         ) -> PairingOutput<Self::E> {
+            count_pairings(1);
             PairingOutput(pairing(p1, q1))
         }
     }
@@ -211,7 +258,7 @@ pub mod blst {
         /// Construct from a set of affine points
         fn from_affines(value: Vec<ark_bls12_381::G1Affine>) -> Self {
             let len = value.len();
-            let first = value.get(0).map(|p1| convert_g1(p1.into_group()));
+            let first = value.first().map(|p1| convert_g1(p1.into_group()));
             let all: Vec<_> = value.into_iter().map(convert_g1_affine).collect();
             Self { first, all, len }
         }
@@ -223,7 +270,7 @@ pub mod blst {
         ) -> Result<ark_bls12_381::G1Projective, Error> {
             scalars = trim_zeros(scalars);
             check_scalars(scalars, self.len)?;
-            if scalars.len() == 0 || self.len == 0 {
+            if scalars.is_empty() || self.len == 0 {
                 return Ok(Zero::zero());
             }
             let scalars_le = prep_scalars(scalars);
@@ -261,7 +308,7 @@ pub mod blst {
     impl PreparedG2s {
         fn from_affines(value: Vec<ark_bls12_381::G2Affine>) -> Self {
             let len = value.len();
-            let first = value.get(0).map(|p2| convert_g2(p2.into_group()));
+            let first = value.first().map(|p2| convert_g2(p2.into_group()));
             let all: Vec<_> = value.into_iter().map(convert_g2_affine).collect();
             Self { first, all, len }
         }
@@ -272,7 +319,7 @@ pub mod blst {
         ) -> Result<ark_bls12_381::G2Projective, Error> {
             scalars = trim_zeros(scalars);
             check_scalars(scalars, self.len)?;
-            if scalars.len() == 0 || self.len == 0 {
+            if scalars.is_empty() || self.len == 0 {
                 return Ok(Zero::zero());
             }
             let scalars_le = prep_scalars(scalars);
@@ -366,13 +413,6 @@ pub mod blst {
         }
         Ok(())
     }
-
-    fn trim_zeros(mut scalars: &[ark_bls12_381::Fr]) -> &[ark_bls12_381::Fr] {
-        while scalars.last().map(|s| s.is_zero()).unwrap_or(false) {
-            scalars = &scalars[..scalars.len() - 1];
-        }
-        scalars
-    }
     #[cfg(test)]
     mod tests {
         use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
@@ -397,7 +437,7 @@ pub mod blst {
 
             fn run(err: bool, g1s: &[ark_bls12_381::G1Affine], scalars: &[ark_bls12_381::Fr]) {
                 let pg1 = PreparedG1s::from_affines(g1s.to_vec());
-                let res = pg1.msm(&scalars);
+                let res = pg1.msm(scalars);
                 assert_eq!(res.is_err(), err);
             }
 
@@ -0,0 +1,213 @@
+//! # Multilinear adapter
+//! Commits to and opens multilinear polynomials, given by their evaluations over the boolean
+//! hypercube $\{0, 1\}^k$, with the univariate schemes.
+//!
+//! The table is indexed little-endian, so entry `i` is the evaluation at the point whose `j`th
+//! coordinate is bit `j` of `i`. A multilinear polynomial is $f(x) = \sum_S c_S \prod_{j \in S}
+//! x_j$ over subsets $S$ of the coordinates, and the encoding is the univariate polynomial
+//! $U(X) = \sum_S c_S X^{\sum_{j \in S} 2^j}$, i.e. the coefficient of $X^i$ is $c_S$ for the
+//! subset $S$ given by the bits of `i`. Then
+//! $$U(z) = f(z, z^2, z^4, \ldots, z^{2^{k-1}})$$
+//! so opening $U$ at $z$ opens $f$ at [`ml_point`]`(z, k)`. Only points of that form can be
+//! opened, which is enough when the point is derived from a single challenge $z$. Full
+//! multilinear KZG, opening at an arbitrary point of $F^k$, is out of scope.
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::{
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Error, Pairing,
+};
+
+/// Converts evaluations over the hypercube to the coefficients of the univariate encoding.
+/// Errors if the number of evaluations isn't a power of two.
+pub fn ml_to_univariate<F: Field>(evals: &[F]) -> Result<Vec<F>, Error> {
+    if !evals.len().is_power_of_two() {
+        return Err(Error::InvalidInputLength {
+            expected: evals.len().next_power_of_two(),
+            got: evals.len(),
+        });
+    }
+    // Mobius transform, going from evaluations to monomial coefficients one variable at a time
+    let mut coeffs = evals.to_vec();
+    let mut bit = 1;
+    while bit < coeffs.len() {
+        for i in 0..coeffs.len() {
+            if i & bit != 0 {
+                let low = coeffs[i ^ bit];
+                coeffs[i] -= low;
+            }
+        }
+        bit <<= 1;
+    }
+    Ok(coeffs)
+}
+
+/// The point $(z, z^2, z^4, \ldots, z^{2^{k-1}})$ in $F^k$ that opening the univariate encoding
+/// of a `k` variable multilinear polynomial at `z` opens it at
+pub fn ml_point<F: Field>(z: F, k: usize) -> Vec<F> {
+    core::iter::successors(Some(z), |p| Some(p.square()))
+        .take(k)
+        .collect()
+}
+
+/// Evaluates the multilinear polynomial with evaluations `evals` over the hypercube at `point`.
+/// Errors if `evals` doesn't have `2^point.len()` entries, reporting `usize::MAX` as the expected
+/// length when that doesn't fit in a `usize`.
+pub fn ml_evaluate<F: Field>(evals: &[F], point: &[F]) -> Result<F, Error> {
+    let expected = u32::try_from(point.len())
+        .ok()
+        .and_then(|k| 1usize.checked_shl(k))
+        .unwrap_or(usize::MAX);
+    if evals.len() != expected {
+        return Err(Error::InvalidInputLength {
+            expected,
+            got: evals.len(),
+        });
+    }
+    // Fix the variables one at a time, halving the table each time
+    let mut table = evals.to_vec();
+    for (j, x) in point.iter().enumerate() {
+        let half = expected >> (j + 1);
+        for i in 0..half {
+            table[i] = table[2 * i] + (table[2 * i + 1] - table[2 * i]) * x;
+        }
+    }
+    Ok(table[0])
+}
+
+/// Commits to the univariate encoding of the multilinear polynomial with evaluations `evals`
+/// over the hypercube
+pub fn ml_commit<E: Pairing>(
+    s: &impl Committer<E>,
+    evals: &[E::ScalarField],
+) -> Result<Commitment<E>, Error> {
+    s.commit(ml_to_univariate(evals)?)
+}
+
+/// Opens the univariate encodings of multilinear polynomials, committed to with [`ml_commit`], at
+/// each of `zs`. Returns the evaluations, where entry `(i, j)` is polynomial `i` at
+/// [`ml_point`]`(zs[j], k)`, and the proof, which `verify` checks against the commitments at
+/// the points `zs`.
+#[allow(clippy::type_complexity)]
+pub fn ml_open<E: Pairing, P: PolyMultiProofNoPrecomp<E>>(
+    s: &P,
+    transcript: &mut Transcript,
+    tables: &[impl AsRef<[E::ScalarField]>],
+    zs: &[E::ScalarField],
+) -> Result<(Vec<Vec<E::ScalarField>>, P::Proof), Error> {
+    let polys = tables
+        .iter()
+        .map(|t| ml_to_univariate(t.as_ref()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let evals = polys
+        .iter()
+        .map(|p| {
+            let p = DensePolynomial::from_coefficients_slice(p);
+            zs.iter().map(|z| p.evaluate(z)).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let proof = s.open(transcript, &evals, &polys, zs)?;
+    Ok((evals, proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ml_commit, ml_evaluate, ml_open, ml_point, ml_to_univariate};
+    use crate::{
+        method2::M2NoPrecomp,
+        test_rng,
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{One, Zero};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{vec, UniformRand};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_encoding_matches_multilinear_eval() {
+        let k = 5;
+        let evals = (0..1 << k)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let coeffs = ml_to_univariate(&evals).unwrap();
+        let poly = DensePolynomial::from_coefficients_slice(&coeffs);
+
+        // On the hypercube the extension agrees with the table
+        for (i, e) in evals.iter().enumerate() {
+            let corner = (0..k)
+                .map(|j| {
+                    if i >> j & 1 == 1 {
+                        Fr::one()
+                    } else {
+                        Fr::zero()
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(*e, ml_evaluate(&evals, &corner).unwrap());
+        }
+        let z = Fr::rand(&mut test_rng());
+        assert_eq!(
+            ml_evaluate(&evals, &ml_point(z, k)).unwrap(),
+            poly.evaluate(&z)
+        );
+
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: 8,
+                got: 6
+            }),
+            ml_to_univariate(&evals[..6])
+        );
+        // Too many variables for the table size to fit in a usize
+        assert_eq!(
+            Err(Error::InvalidInputLength {
+                expected: usize::MAX,
+                got: 32
+            }),
+            ml_evaluate(&evals, &vec![z; usize::BITS as usize])
+        );
+    }
+
+    #[test]
+    fn test_ml_open_verifies() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let k = 4;
+        let tables = (0..3)
+            .map(|_| {
+                (0..1 << k)
+                    .map(|_| Fr::rand(&mut test_rng()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let commits = tables
+            .iter()
+            .map(|t| ml_commit(&s, t).unwrap())
+            .collect::<Vec<_>>();
+        let zs = vec![Fr::rand(&mut test_rng()), Fr::rand(&mut test_rng())];
+        let (evals, proof) = ml_open(&s, &mut Transcript::new(b"testing"), &tables, &zs).unwrap();
+        for (t, e) in tables.iter().zip(&evals) {
+            for (z, y) in zs.iter().zip(e) {
+                assert_eq!(ml_evaluate(t, &ml_point(*z, k)).unwrap(), *y);
+            }
+        }
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &zs,
+                &evals,
+                &proof
+            )
+        );
+        assert_eq!(
+            s.commit(ml_to_univariate(&tables[0]).unwrap()).unwrap(),
+            commits[0]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Counts of the expensive operations done by this crate, for measuring the cost of `open` and
+//! `verify`. With the `op-counting` feature each MSM, pairing and field inversion increments a
+//! global atomic counter, as does the number of bases in each MSM, read with [`op_counts`] and
+//! cleared with [`reset_op_counts`]. Without it the counting compiles to nothing.
+//!
+//! Only operations done by this crate are counted, not ones inside arkworks such as the
+//! inversions in an FFT. A batch inversion counts as a single inversion, and a pairing is
+//! counted per Miller loop, so a check of $e(a, b) = e(c, d)$ counts two.
+//!
+//! The counters are shared by every thread, so measurements should be taken with nothing else
+//! running in the process.
+
+#[cfg(feature = "op-counting")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "op-counting")]
+static MSMS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "op-counting")]
+static MSM_BASES: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "op-counting")]
+static PAIRINGS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "op-counting")]
+static INVERSIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The number of each operation done since the last [`reset_op_counts`]
+#[cfg(feature = "op-counting")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OpCounts {
+    /// Multi-scalar multiplications, in either group
+    pub msms: u64,
+    /// The total number of bases across those MSMs, after dropping trailing zero scalars
+    pub msm_bases: u64,
+    /// Pairings, counted per Miller loop
+    pub pairings: u64,
+    /// Field inversions, with a batch inversion counted once
+    pub inversions: u64,
+}
+
+/// Sets every counter back to zero
+#[cfg(feature = "op-counting")]
+pub fn reset_op_counts() {
+    MSMS.store(0, Ordering::Relaxed);
+    MSM_BASES.store(0, Ordering::Relaxed);
+    PAIRINGS.store(0, Ordering::Relaxed);
+    INVERSIONS.store(0, Ordering::Relaxed);
+}
+
+/// Reads the counters
+#[cfg(feature = "op-counting")]
+pub fn op_counts() -> OpCounts {
+    OpCounts {
+        msms: MSMS.load(Ordering::Relaxed),
+        msm_bases: MSM_BASES.load(Ordering::Relaxed),
+        pairings: PAIRINGS.load(Ordering::Relaxed),
+        inversions: INVERSIONS.load(Ordering::Relaxed),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn count_msm(_n_bases: usize) {
+    #[cfg(feature = "op-counting")]
+    {
+        MSMS.fetch_add(1, Ordering::Relaxed);
+        MSM_BASES.fetch_add(_n_bases as u64, Ordering::Relaxed);
+    }
+}
+
+#[inline(always)]
+pub(crate) fn count_pairings(_n: usize) {
+    #[cfg(feature = "op-counting")]
+    PAIRINGS.fetch_add(_n as u64, Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub(crate) fn count_inversion() {
+    #[cfg(feature = "op-counting")]
+    INVERSIONS.fetch_add(1, Ordering::Relaxed);
+}
@@ -1,9 +1,15 @@
 //! Polynomial operations and utilities
-//! Don't use these unless you're sure you have to
-use crate::{utils::smallest_power_of_2_greater_than, Error};
-use ark_ff::{FftField, Field};
+//! Don't use these unless you're sure you have to. None of them need a pairing, so they can be
+//! used on their own with any [`Field`].
+use crate::{
+    cfg_iter, trim_zeros,
+    utils::{smallest_power_of_2_greater_than, witness_degree},
+    Error,
+};
+use ark_ff::{batch_inversion, FftField, Field};
 use ark_poly::{
-    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
+    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
     Radix2EvaluationDomain,
 };
 use ark_std::{result::Result, vec, vec::Vec};
@@ -11,16 +17,76 @@ use core::{
     iter::StepBy,
     ops::{Mul, Range},
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 fn poly<F: Field>(p: Vec<F>) -> DensePolynomial<F> {
     DensePolynomial::from_coefficients_vec(p)
 }
 
+/// Returns `1, element, element^2, ...`, `len` items in total
+pub fn gen_powers<F: Field>(element: F, len: usize) -> Vec<F> {
+    powers_iter(element, len).collect()
+}
+
+/// Lazily yields `1, element, element^2, ...`, `len` items in total
+pub fn powers_iter<F: Field>(element: F, len: usize) -> impl Iterator<Item = F> {
+    core::iter::successors(Some(F::one()), move |p| Some(*p * element)).take(len)
+}
+
+/// The vanishing polynomial $\prod_i (x - x_i)$ of `points`. This is the constant one when
+/// there are no points.
+pub fn vanishing_polynomial<F: Field>(points: impl AsRef<[F]>) -> DensePolynomial<F> {
+    let one = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+    points
+        .as_ref()
+        .iter()
+        .map(|&point| DensePolynomial::from_coefficients_vec(vec![-point, F::one()]))
+        .fold(one, |x, y| x.naive_mul(&y))
+}
+
+/// Does polynomial division, returning the coefficients of the quotient and remainder. Errors
+/// with [`Error::DivisorIsZero`] if `denom` is zero.
+pub fn poly_div_q_r<F: Field>(
+    num: DenseOrSparsePolynomial<F>,
+    denom: DenseOrSparsePolynomial<F>,
+) -> Result<(Vec<F>, Vec<F>), Error> {
+    if denom.is_zero() {
+        return Err(Error::DivisorIsZero);
+    }
+    let (q, r) = num.divide_with_q_and_r(&denom).expect("Cannot return none");
+    Ok((q.coeffs, r.coeffs))
+}
+
+/// Computes $\sum_i c_i p_i$ for the polynomials $p_i$ given as coefficients and the scalars
+/// $c_i$. Extra polynomials or scalars past the shorter of the two are ignored. Returns `None`
+/// if either is empty.
+pub fn linear_combination<F: Field>(
+    polynomials: &[impl AsRef<[F]>],
+    challenges: &[F],
+) -> Option<Vec<F>> {
+    polynomials
+        .as_ref()
+        .iter()
+        .zip(challenges.iter())
+        .map(|(p, &c)| &DensePolynomial::from_coefficients_slice(p.as_ref()) * c)
+        .reduce(|x, y| x + y)?
+        .coeffs
+        .into()
+}
+
+/// Lifts coefficients from a subfield embedded in `F`, such as a lower level of a tower field,
+/// into `F` so they can be committed to. Evaluations of the lifted polynomial at points of the
+/// subfield are the lifted evaluations in the subfield.
+pub fn embed_subfield_coeffs<S: Copy, F: Field + From<S>>(sub_coeffs: &[S]) -> Vec<F> {
+    sub_coeffs.iter().map(|&c| F::from(c)).collect()
+}
+
 /// Compute the inverse of the monic polynomial $u$ mod $x^l$.
 fn inv_modl<F: FftField>(u: &DensePolynomial<F>, n: usize) -> DensePolynomial<F> {
     debug_assert!(!u[0].is_zero());
 
-    let u0 = u.coeffs[0].clone();
+    let u0 = u.coeffs[0];
     let mut v: Vec<F> = vec![F::one() / u0];
     for _i in 0..=smallest_power_of_2_greater_than(n) {
         let vpoly: DensePolynomial<F> = poly(v.clone());
@@ -47,6 +113,134 @@ pub(crate) fn rev_poly<F: Field>(mut p: DensePolynomial<F>) -> DensePolynomial<F
     poly(p.coeffs)
 }
 
+/// Divides `num` by `denom`, returning the quotient and remainder. Errors with
+/// [`Error::DivisorIsZero`] if `denom` is zero.
+pub fn divide_poly<F: Field>(
+    num: &DensePolynomial<F>,
+    denom: &DensePolynomial<F>,
+) -> Result<(DensePolynomial<F>, DensePolynomial<F>), Error> {
+    let (q, r) = poly_div_q_r(num.into(), denom.into())?;
+    Ok((poly(q), poly(r)))
+}
+
+/// Degree that both the numerator and the divisor have to be above for [`divide_poly_auto`] to
+/// divide with FFTs. Below it schoolbook division is faster.
+pub const FFT_DIVISION_THRESHOLD: usize = 512;
+
+/// Divides `num` by `denom` like [`divide_poly`], using [`fft_divide_poly`] when both degrees are
+/// above [`FFT_DIVISION_THRESHOLD`] and schoolbook division otherwise
+pub fn divide_poly_auto<F: FftField>(
+    num: &DensePolynomial<F>,
+    denom: &DensePolynomial<F>,
+) -> Result<(DensePolynomial<F>, DensePolynomial<F>), Error> {
+    let (n, m) = (
+        trim_zeros(&num.coeffs).len(),
+        trim_zeros(&denom.coeffs).len(),
+    );
+    if n > FFT_DIVISION_THRESHOLD + 1 && m > FFT_DIVISION_THRESHOLD + 1 {
+        fft_divide_poly(num, denom)
+    } else {
+        divide_poly(num, denom)
+    }
+}
+
+/// Divides `num` by `denom` in $O(n \log n)$ with FFT multiplications, returning the quotient
+/// and remainder. The quotient reversed is the reversed numerator over the reversed divisor mod
+/// $x^l$, where $l$ is the number of quotient coefficients, so it's found with a power series
+/// inverse. Errors with [`Error::DivisorIsZero`] if `denom` is zero.
+pub fn fft_divide_poly<F: FftField>(
+    num: &DensePolynomial<F>,
+    denom: &DensePolynomial<F>,
+) -> Result<(DensePolynomial<F>, DensePolynomial<F>), Error> {
+    let (num_c, denom_c) = (trim_zeros(&num.coeffs), trim_zeros(&denom.coeffs));
+    if denom_c.is_empty() {
+        return Err(Error::DivisorIsZero);
+    }
+    if num_c.len() < denom_c.len() {
+        return Ok((poly(vec![]), poly(num_c.to_vec())));
+    }
+    let l = num_c.len() - denom_c.len() + 1;
+    let denom_rev = denom_c.iter().rev().copied().collect::<Vec<_>>();
+    // Only the top l coefficients of the numerator affect the quotient
+    let num_rev = poly(num_c.iter().rev().take(l).copied().collect());
+    let mut q = (&num_rev * &inv_mod_xl(&denom_rev, l)).coeffs;
+    // The product can be shorter than l when its top coefficients are zero
+    q.resize(l, F::zero());
+    q.reverse();
+    let q = poly(q);
+    let r = &poly(num_c.to_vec()) - &(&q * &poly(denom_c.to_vec()));
+    Ok((q, r))
+}
+
+/// Computes the inverse of `f` mod $x^l$ by Newton iteration, doubling the precision each step.
+/// `f` must have a nonzero constant term.
+fn inv_mod_xl<F: FftField>(f: &[F], l: usize) -> DensePolynomial<F> {
+    crate::op_count::count_inversion();
+    let mut g = poly(vec![f[0].inverse().expect("constant term is nonzero")]);
+    let mut k = 1;
+    while k < l {
+        k = (2 * k).min(l);
+        let f_k = poly(f[..k.min(f.len())].to_vec());
+        // g <- g (2 - f g) mod x^k
+        let mut h = (&f_k * &g).coeffs;
+        h.truncate(k);
+        h.iter_mut().for_each(|c| *c = -*c);
+        h[0] += F::from(2u8);
+        let mut next = (&g * &poly(h)).coeffs;
+        next.truncate(k);
+        g = poly(next);
+    }
+    g
+}
+
+/// The coefficients of the formal derivative of the polynomial with coefficients `coeffs`,
+/// $\sum_i i c_i x^{i-1}$
+pub fn derivative<F: Field>(coeffs: &[F]) -> Vec<F> {
+    coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| F::from(i as u64) * c)
+        .collect()
+}
+
+/// Below this many roots [`poly_from_roots`] multiplies out the linear factors directly
+const ROOTS_NAIVE_THRESHOLD: usize = 32;
+
+/// The monic polynomial $\prod_i (x - r_i)$ with the given roots, the same as
+/// [`vanishing_polynomial`]. It's built as a subproduct tree, multiplying the
+/// products of each half of the roots with FFTs, which is quasi-linear rather than quadratic in
+/// the number of roots.
+pub fn poly_from_roots<F: FftField>(roots: &[F]) -> DensePolynomial<F> {
+    if roots.len() <= ROOTS_NAIVE_THRESHOLD {
+        return vanishing_polynomial(roots);
+    }
+    let (left, right) = roots.split_at(roots.len() / 2);
+    &poly_from_roots(left) * &poly_from_roots(right)
+}
+
+/// Divides `p` by the sparse polynomial $x^n - c$ in linear time, returning the quotient and
+/// remainder. This is the vanishing polynomial of a coset of size $n$ with $c = h^n$.
+pub fn div_by_binomial<F: Field>(
+    p: &DensePolynomial<F>,
+    n: usize,
+    c: F,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    debug_assert!(n > 0);
+    if p.coeffs.len() <= n {
+        return (poly(vec![]), p.clone());
+    }
+    let mut rem = p.coeffs.clone();
+    let mut quot = vec![F::zero(); witness_degree(rem.len() - 1, n) + 1];
+    for i in (n..rem.len()).rev() {
+        let t = rem[i];
+        quot[i - n] = t;
+        rem[i - n] += c * t;
+    }
+    rem.truncate(n);
+    (poly(quot), poly(rem))
+}
+
 /// Context for performing polynomial division with a fixed denominator in near-linear time
 pub struct FastDivisionContext<F: Field> {
     denom_rev_inv: DensePolynomial<F>,
@@ -68,6 +262,7 @@ impl<F: FftField> FastDivisionContext<F> {
     }
 
     /// Performs a fast division. This has roughly the runtime of polynomial multiplication.
+    #[allow(clippy::result_unit_err)]
     pub fn fast_div(&self, num_poly: DensePolynomial<F>) -> Result<DensePolynomial<F>, ()> {
         //TODO: Figure out what degrees are ok to use and error otherwise
         if num_poly.degree() > self.max_num_poly_deg {
@@ -111,17 +306,16 @@ impl<F: FftField> SplitEvalDomain<F> {
     /// Get the subgroup with index `idx`
     pub fn subgroup(&self, idx: usize) -> Option<Radix2EvaluationDomain<F>> {
         if idx >= self.n_splits {
-            return None;
+            None
         } else {
             let gen = self.base_domain.group_gen().pow([idx.try_into().unwrap()]);
-            return Radix2EvaluationDomain::new_coset(self.base_size / self.n_splits, gen);
+            Radix2EvaluationDomain::new_coset(self.base_size / self.n_splits, gen)
         }
     }
 
     /// Gets the subgroups of the base cyclic group
     pub fn subgroups(&self) -> Vec<Radix2EvaluationDomain<F>> {
         (0..self.n_splits)
-            .into_iter()
             .map(|idx| self.subgroup(idx).expect("idx < nsplits"))
             .collect()
     }
@@ -157,6 +351,96 @@ impl<F: FftField> SplitEvalDomain<F> {
     }
 }
 
+/// An evaluation domain along with its elements and their inverses, for when the same domain is
+/// used over and over and recomputing them each time adds up
+#[derive(Clone, Debug)]
+pub struct KzgDomain<F: FftField> {
+    domain: GeneralEvaluationDomain<F>,
+    elements: Vec<F>,
+    inv_elements: Vec<F>,
+}
+
+impl<F: FftField> KzgDomain<F> {
+    /// Builds the smallest domain holding at least `size` elements
+    pub fn new(size: usize) -> Result<Self, Error> {
+        let domain = GeneralEvaluationDomain::new(size)
+            .ok_or_else(|| Error::domain_construction_failed::<F>(size))?;
+        Ok(Self::from_domain(domain))
+    }
+
+    /// Caches the elements of an existing domain
+    pub fn from_domain(domain: GeneralEvaluationDomain<F>) -> Self {
+        let elements = ev_points(&domain);
+        let mut inv_elements = elements.clone();
+        batch_inversion(&mut inv_elements);
+        crate::op_count::count_inversion();
+        Self {
+            domain,
+            elements,
+            inv_elements,
+        }
+    }
+
+    /// The underlying domain
+    pub fn domain(&self) -> &GeneralEvaluationDomain<F> {
+        &self.domain
+    }
+
+    /// The number of elements in the domain
+    pub fn size(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// The elements of the domain, in the order `domain().elements()` gives them
+    pub fn elements(&self) -> &[F] {
+        &self.elements
+    }
+
+    /// The inverses of [`Self::elements`]
+    pub fn inv_elements(&self) -> &[F] {
+        &self.inv_elements
+    }
+
+    /// The elements at each of `indices`, erroring if an index is out of the domain
+    pub fn points(&self, indices: &[usize]) -> Result<Vec<F>, Error> {
+        indices
+            .iter()
+            .map(|&idx| {
+                self.elements
+                    .get(idx)
+                    .copied()
+                    .ok_or(Error::IndexOutOfDomain {
+                        idx,
+                        domain_size: self.size(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Evaluates each of `polys` at each of `points`, giving the evals in the layout `open` takes.
+/// The powers of each point are computed once and shared by every polynomial, so each eval is a
+/// dot product instead of a run of Horner's rule. This pays off with many polynomials and few
+/// points. With the `parallel` feature the polynomials are evaluated in parallel.
+pub fn evaluate_matrix_shared_points<F: Field, P: AsRef<[F]> + Sync>(
+    polys: &[P],
+    points: &[F],
+) -> Vec<Vec<F>> {
+    let n_coeffs = polys.iter().map(|p| p.as_ref().len()).max().unwrap_or(0);
+    let point_powers = points
+        .iter()
+        .map(|&x| gen_powers(x, n_coeffs))
+        .collect::<Vec<_>>();
+    cfg_iter!(polys)
+        .map(|(_, p)| {
+            point_powers
+                .iter()
+                .map(|powers| p.as_ref().iter().zip(powers).map(|(c, x)| *c * x).sum())
+                .collect()
+        })
+        .collect()
+}
+
 /// Convenience method to get a vec of points from an evaluation domain
 pub fn ev_points<F: FftField>(ev: &impl EvaluationDomain<F>) -> Vec<F> {
     ev.elements().collect()
@@ -165,13 +449,14 @@ pub fn ev_points<F: FftField>(ev: &impl EvaluationDomain<F>) -> Vec<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::poly_div_q_r;
     use ark_bls12_381::Fr;
-    use ark_ff::{One, Zero};
+    use ark_ff::{One, UniformRand, Zero};
     use rand::thread_rng;
+    // With `parallel` this comes in with `super::*`
+    #[cfg(not(feature = "parallel"))]
     use rayon::prelude::*;
 
-    fn tostr(p: &Vec<Fr>) -> String {
+    fn tostr(p: &[Fr]) -> String {
         let a = p
             .iter()
             .map(|a| {
@@ -228,6 +513,108 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fft_divide_matches_schoolbook() {
+        let pairs = [
+            (0, 0),
+            (5, 0),
+            (3, 7),
+            (7, 7),
+            (8, 7),
+            (100, 1),
+            (100, 99),
+            (257, 100),
+            (1000, 333),
+            (1025, 1024),
+            (3000, 1500),
+        ];
+        for (n, m) in pairs {
+            let num = DensePolynomial::<Fr>::rand(n, &mut thread_rng());
+            let denom = DensePolynomial::<Fr>::rand(m, &mut thread_rng());
+            let fft = fft_divide_poly(&num, &denom).unwrap();
+            assert_eq!(
+                divide_poly(&num, &denom).unwrap(),
+                fft,
+                "n: {}, m: {}",
+                n,
+                m
+            );
+            assert_eq!(divide_poly_auto(&num, &denom).unwrap(), fft);
+        }
+
+        // Leading zero coefficients don't count towards the degree
+        let num = DensePolynomial::<Fr>::rand(40, &mut thread_rng());
+        let denom = DensePolynomial::<Fr>::rand(10, &mut thread_rng());
+        let mut padded = denom.clone();
+        padded.coeffs.extend([Fr::zero(); 5]);
+        assert_eq!(
+            divide_poly(&num, &denom).unwrap(),
+            fft_divide_poly(&num, &padded).unwrap()
+        );
+
+        let zero = DensePolynomial {
+            coeffs: vec![Fr::zero(); 3],
+        };
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            fft_divide_poly(&num, &zero).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_poly_from_roots() {
+        for n in [0, 1, 5, 32, 33, 100, 300] {
+            let roots = (0..n)
+                .map(|_| Fr::rand(&mut thread_rng()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vanishing_polynomial(&roots),
+                poly_from_roots(&roots),
+                "n: {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_matrix_shared_points() {
+        let points = (0..5)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..20)
+            .map(|i| DensePolynomial::<Fr>::rand(i * 2, &mut thread_rng()))
+            .collect::<Vec<_>>();
+        let horner = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+        assert_eq!(horner, evaluate_matrix_shared_points(&coeffs, &points));
+        assert_eq!(
+            vec![Vec::<Fr>::new(); 20],
+            evaluate_matrix_shared_points(&coeffs, &[])
+        );
+        assert!(evaluate_matrix_shared_points::<Fr, Vec<Fr>>(&[], &points).is_empty());
+    }
+
+    #[test]
+    fn test_div_by_binomial() {
+        let c = Fr::rand(&mut thread_rng());
+        for n in [1, 3, 16] {
+            let mut denom = vec![Fr::zero(); n + 1];
+            denom[0] = -c;
+            denom[n] = Fr::one();
+            for deg in [0, n - 1, n, 2 * n + 1, 5 * n] {
+                let num = DensePolynomial::<Fr>::rand(deg, &mut thread_rng());
+                let (q, r) = div_by_binomial(&num, n, c);
+                let (naive_q, naive_r) =
+                    poly_div_q_r(num.clone().into(), poly(denom.clone()).into()).unwrap();
+                assert_eq!(q.coeffs, naive_q, "n: {}, deg: {}", n, deg);
+                assert_eq!(r.coeffs, naive_r, "n: {}, deg: {}", n, deg);
+            }
+        }
+    }
+
     #[test]
     fn test_ev_points() {
         let evd = Radix2EvaluationDomain::<Fr>::new(256).unwrap();
@@ -252,7 +639,7 @@ mod tests {
         let mut inds = Vec::new();
         for i in 0..16 {
             let i_inds = split_evd.subgroup_indices(i);
-            dbg!(i_inds.clone().into_iter().collect::<Vec<_>>());
+            dbg!(i_inds.clone().collect::<Vec<_>>());
             let i_pts = ev_points(&split_evd.subgroup(i).unwrap());
             assert_eq!(i_inds.len(), i_pts.len());
             for (ind, pt) in i_inds.zip(i_pts) {
@@ -263,4 +650,88 @@ mod tests {
         inds.sort();
         assert_eq!(inds, (0..256).collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_divide_poly() {
+        // x^2 - 1 = (x - 1)(x + 1)
+        let num = poly(vec![-Fr::one(), Fr::zero(), Fr::one()]);
+        let denom = poly(vec![-Fr::one(), Fr::one()]);
+        let (q, r) = divide_poly(&num, &denom).unwrap();
+        assert_eq!(q, poly(vec![Fr::one(), Fr::one()]));
+        assert!(r.is_zero());
+
+        // x^2 = (x - 1)(x + 1) + 1
+        let num = poly(vec![Fr::zero(), Fr::zero(), Fr::one()]);
+        let (q, r) = divide_poly(&num, &denom).unwrap();
+        assert_eq!(q, poly(vec![Fr::one(), Fr::one()]));
+        assert_eq!(r, poly(vec![Fr::one()]));
+
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            divide_poly(&num, &DensePolynomial::zero())
+        );
+    }
+
+    #[test]
+    fn test_powers_iter() {
+        let x = Fr::from(7u64);
+        for len in [0, 1, 2, 17] {
+            assert_eq!(gen_powers(x, len), powers_iter(x, len).collect::<Vec<_>>());
+        }
+        assert_eq!(
+            vec![Fr::from(1u64), Fr::from(7u64), Fr::from(49u64)],
+            gen_powers(x, 3)
+        );
+    }
+
+    #[test]
+    fn test_vanishing_polynomial() {
+        let points = (0..8)
+            .map(|_| Fr::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let vp = vanishing_polynomial(&points);
+        assert_eq!(8, vp.degree());
+        assert_eq!(Fr::one(), vp.coeffs[8]);
+        assert!(points.iter().all(|p| vp.evaluate(p).is_zero()));
+        assert!(!vp.evaluate(&Fr::rand(&mut thread_rng())).is_zero());
+        assert_eq!(
+            DensePolynomial::from_coefficients_vec(vec![Fr::one()]),
+            vanishing_polynomial::<Fr>([])
+        );
+    }
+
+    #[test]
+    fn test_poly_div_q_r() {
+        let num = DensePolynomial::<Fr>::rand(20, &mut thread_rng());
+        let denom = DensePolynomial::<Fr>::rand(5, &mut thread_rng());
+        let (q, r) = poly_div_q_r((&num).into(), (&denom).into()).unwrap();
+        let (q, r) = (
+            DensePolynomial::from_coefficients_vec(q),
+            DensePolynomial::from_coefficients_vec(r),
+        );
+        assert!(r.degree() < denom.degree());
+        assert_eq!(num, &(&q * &denom) + &r);
+        assert_eq!(
+            Err(Error::DivisorIsZero),
+            poly_div_q_r((&num).into(), (&DensePolynomial::zero()).into())
+        );
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let polys = (0..3)
+            .map(|d| DensePolynomial::<Fr>::rand(d * 4, &mut thread_rng()).coeffs)
+            .collect::<Vec<_>>();
+        let scalars = gen_powers(Fr::from(3u64), 3);
+        let combo =
+            DensePolynomial::from_coefficients_vec(linear_combination(&polys, &scalars).unwrap());
+        let x = Fr::rand(&mut thread_rng());
+        let expected = polys
+            .iter()
+            .zip(&scalars)
+            .map(|(p, c)| DensePolynomial::from_coefficients_slice(p).evaluate(&x) * c)
+            .sum::<Fr>();
+        assert_eq!(expected, combo.evaluate(&x));
+        assert_eq!(None, linear_combination::<Fr>(&[] as &[Vec<Fr>], &scalars));
+    }
 }
@@ -0,0 +1,304 @@
+//! # Remote proving
+//! A [`RemoteProver`] forwards the expensive `open` to another process over a user supplied
+//! transport while committing and verifying locally. The other end answers requests with
+//! [`serve_open`].
+//!
+//! Merlin transcripts can't be sent over the wire, so the prover absorbs the points and evals
+//! into the caller's transcript and sends a challenge drawn from it. The remote opens on a
+//! transcript made from that challenge, and the proof is then absorbed into the caller's
+//! transcript too, so it ends bound to the statement and the proof, the same after opening as
+//! after verifying. The proof itself is bound to the challenge rather than to the caller's
+//! transcript, so proofs made by a [`RemoteProver`] must be verified with a [`RemoteProver`] (or
+//! anything doing the same).
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ark_std::vec::Vec;
+use merlin::Transcript;
+
+use crate::{
+    get_field_size,
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    transcribe_generic, transcribe_points_and_evals, Commitment, Error,
+};
+
+const CHALLENGE_SIZE: usize = 32;
+
+/// A proof scheme which sends openings to a remote prover. `local` must have the same setup as
+/// the remote prover, and is used for committing and verifying.
+#[derive(Clone, Debug)]
+pub struct RemoteProver<P, T> {
+    /// The local proof scheme
+    pub local: P,
+    /// Sends an encoded request and returns the encoded response. A failure is reported as
+    /// [`Error::RemoteTransportFailed`].
+    pub transport: T,
+}
+
+impl<P, T, TE> RemoteProver<P, T>
+where
+    T: Fn(&[u8]) -> Result<Vec<u8>, TE>,
+{
+    /// Make a new remote prover from a local scheme and a transport
+    pub fn new(local: P, transport: T) -> Self {
+        Self { local, transport }
+    }
+}
+
+/// Absorbs the points and evals into the caller's transcript and draws the challenge the remote
+/// proof is bound to
+fn bind_statement<F: PrimeField>(
+    transcript: &mut Transcript,
+    points: &[F],
+    evals: &[impl AsRef<[F]>],
+) -> Result<[u8; CHALLENGE_SIZE], Error> {
+    transcribe_points_and_evals(
+        transcript,
+        points,
+        evals,
+        get_field_size::<F>(),
+        Compress::Yes,
+    )?;
+    let mut challenge = [0u8; CHALLENGE_SIZE];
+    crate::transcript::challenge_bytes(transcript, b"remote open challenge", &mut challenge);
+    Ok(challenge)
+}
+
+/// The transcript the remote proof is made and verified with
+fn challenge_transcript(challenge: &[u8; CHALLENGE_SIZE]) -> Transcript {
+    let mut transcript = Transcript::new(b"poly-multiproof remote open");
+    crate::transcript::append_message(&mut transcript, b"challenge", challenge);
+    transcript
+}
+
+/// Encodes an open request as `challenge || evals || polys || points`, with the field element
+/// vectors in compressed canonical serialization
+fn encode_open_request<F: CanonicalSerialize>(
+    challenge: &[u8; CHALLENGE_SIZE],
+    evals: &[impl AsRef<[F]>],
+    polys: &[impl AsRef<[F]>],
+    points: &[F],
+) -> Result<Vec<u8>, Error> {
+    let evals = evals.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+    let polys = polys.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
+    let mut out = challenge.to_vec();
+    evals.serialize_compressed(&mut out)?;
+    polys.serialize_compressed(&mut out)?;
+    points.serialize_compressed(&mut out)?;
+    Ok(out)
+}
+
+/// Answers a request sent by a [`RemoteProver`] by opening with `prover`, returning the
+/// compressed proof to send back
+pub fn serve_open<E: Pairing, P: PolyMultiProofNoPrecomp<E>>(
+    prover: &P,
+    request: &[u8],
+) -> Result<Vec<u8>, Error>
+where
+    P::Proof: CanonicalSerialize,
+{
+    if request.len() < CHALLENGE_SIZE {
+        return Err(Error::SerializationError);
+    }
+    let (challenge, mut rest) = request.split_at(CHALLENGE_SIZE);
+    let challenge: [u8; CHALLENGE_SIZE] = challenge.try_into().expect("split at CHALLENGE_SIZE");
+    let evals = Vec::<Vec<E::ScalarField>>::deserialize_compressed(&mut rest)?;
+    let polys = Vec::<Vec<E::ScalarField>>::deserialize_compressed(&mut rest)?;
+    let points = Vec::<E::ScalarField>::deserialize_compressed(&mut rest)?;
+    if !rest.is_empty() {
+        return Err(Error::SerializationError);
+    }
+    let proof = prover.open(
+        &mut challenge_transcript(&challenge),
+        &evals,
+        &polys,
+        &points,
+    )?;
+    let mut out = Vec::new();
+    proof.serialize_compressed(&mut out)?;
+    Ok(out)
+}
+
+impl<E: Pairing, P: Committer<E>, T> Committer<E> for RemoteProver<P, T> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        self.local.commit(poly)
+    }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        self.local.powers_of_g1()
+    }
+
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        self.local.commit_projective(poly)
+    }
+}
+
+impl<E, P, T, TE> PolyMultiProofNoPrecomp<E> for RemoteProver<P, T>
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E>,
+    P::Proof: CanonicalSerialize + CanonicalDeserialize,
+    T: Fn(&[u8]) -> Result<Vec<u8>, TE>,
+{
+    type Proof = P::Proof;
+
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let challenge = bind_statement(transcript, points, evals)?;
+        let request = encode_open_request(&challenge, evals, polys, points)?;
+        let response = (self.transport)(&request).map_err(|_| Error::RemoteTransportFailed)?;
+        let proof = P::Proof::deserialize_compressed(response.as_slice())?;
+        transcribe_generic(transcript, b"remote open proof", &proof)?;
+        Ok(proof)
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let challenge = bind_statement(transcript, points, evals)?;
+        let res = self.local.verify(
+            &mut challenge_transcript(&challenge),
+            commits,
+            points,
+            evals,
+            proof,
+        )?;
+        transcribe_generic(transcript, b"remote open proof", proof)?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{serve_open, RemoteProver};
+    use crate::{
+        method2::M2NoPrecomp,
+        test_rng,
+        testing::{random_valid_opening, test_basic_no_precomp},
+        traits::{Committer, PolyMultiProofNoPrecomp},
+        Error,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{vec::Vec, UniformRand};
+    use merlin::Transcript;
+
+    #[test]
+    fn test_remote_prover_works() {
+        let local = M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng());
+        let server = local.clone();
+        let remote = RemoteProver::new(local, |req: &[u8]| serve_open(&server, req));
+        test_basic_no_precomp(&remote);
+    }
+
+    #[test]
+    fn test_remote_proof_bound_to_transcript() {
+        let local = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let server = local.clone();
+        let remote = RemoteProver::new(local, |req: &[u8]| serve_open(&server, req));
+        let (commits, points, evals, open) =
+            random_valid_opening(&remote, 4, 3, 30, &mut test_rng());
+        assert_eq!(
+            Ok(true),
+            remote.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        let mut transcript = Transcript::new(b"testing");
+        transcript.append_message(b"context", b"other");
+        assert_eq!(
+            Ok(false),
+            remote.verify(&mut transcript, &commits, &points, &evals, &open)
+        );
+        assert_eq!(
+            Err(Error::SerializationError),
+            serve_open::<Bls12_381, _>(&server, &[0u8; 8]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_remote_binds_caller_transcript() {
+        let local = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let server = local.clone();
+        let remote = RemoteProver::new(local, |req: &[u8]| serve_open(&server, req));
+        let next_challenge = |transcript: &mut Transcript| {
+            let mut c = [0u8; 32];
+            transcript.challenge_bytes(b"next", &mut c);
+            c
+        };
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| remote.commit(p).unwrap())
+            .collect::<Vec<_>>();
+
+        // The caller's transcript absorbs the statement and the proof, so it ends the same after
+        // opening as after verifying
+        let mut open_transcript = Transcript::new(b"testing");
+        let open = remote
+            .open(&mut open_transcript, &evals, &coeffs, &points)
+            .unwrap();
+        let mut verify_transcript = Transcript::new(b"testing");
+        assert_eq!(
+            Ok(true),
+            remote.verify(&mut verify_transcript, &commits, &points, &evals, &open)
+        );
+        let after_open = next_challenge(&mut open_transcript);
+        assert_eq!(after_open, next_challenge(&mut verify_transcript));
+
+        // Other evals leave the caller's transcript somewhere else
+        let mut other_evals = evals.clone();
+        other_evals[0][0] += Fr::from(1u64);
+        let mut other_transcript = Transcript::new(b"testing");
+        assert_eq!(
+            Ok(false),
+            remote.verify(
+                &mut other_transcript,
+                &commits,
+                &points,
+                &other_evals,
+                &open
+            )
+        );
+        assert_ne!(after_open, next_challenge(&mut other_transcript));
+    }
+
+    #[test]
+    fn test_remote_transport_failure() {
+        let local = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let remote = RemoteProver::new(local, |_: &[u8]| Err("connection refused"));
+        let points = [Fr::from(1u64), Fr::from(2u64)];
+        let evals = [[Fr::from(3u64), Fr::from(3u64)]];
+        let polys = [[Fr::from(3u64)]];
+        assert_eq!(
+            Err(Error::RemoteTransportFailed),
+            remote
+                .open(&mut Transcript::new(b"testing"), &evals, &polys, &points)
+                .map(|_| ())
+        );
+    }
+}
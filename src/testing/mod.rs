@@ -0,0 +1,96 @@
+//! Helpers for generating valid openings in tests, and with `std` a [`RecordingProver`] that
+//! replays recorded proofs. Enabled with the `testing` feature.
+use crate::{
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Vec,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::RngCore;
+use merlin::Transcript;
+
+#[cfg(test)]
+mod suite;
+#[cfg(test)]
+pub(crate) use suite::*;
+
+#[cfg(feature = "std")]
+mod recording;
+#[cfg(feature = "std")]
+pub use recording::RecordingProver;
+
+/// Generates `n_polys` random polynomials of degree `degree` and `n_points` random points, then
+/// commits to and opens the polynomials at the points using a transcript labeled `b"testing"`.
+/// Returns the commitments, points, evaluations and proof. Panics if committing or opening fails.
+#[allow(clippy::type_complexity)]
+pub fn random_valid_opening<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(
+    s: &P,
+    n_points: usize,
+    n_polys: usize,
+    degree: usize,
+    rng: &mut impl RngCore,
+) -> (
+    Vec<Commitment<E>>,
+    Vec<E::ScalarField>,
+    Vec<Vec<E::ScalarField>>,
+    P::Proof,
+) {
+    let points = (0..n_points)
+        .map(|_| E::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    let polys = (0..n_polys)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(degree, rng))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    (commits, points, evals, open)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{method1::M1NoPrecomp, method2::M2NoPrecomp, msm::ArkMSMEngine};
+    use ark_bls12_381::Bls12_381;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_random_valid_opening_verifies() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut rng);
+        let (commits, points, evals, open) = random_valid_opening(&s, 5, 3, 40, &mut rng);
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+
+        let s = M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(64, 8, &mut rng);
+        let (commits, points, evals, open) = random_valid_opening(&s, 8, 3, 63, &mut rng);
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! A prover that records proofs to disk and replays them, for fast deterministic tests
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    string::String,
+    vec::Vec,
+};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::One;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use core::sync::atomic::{AtomicU64, Ordering};
+use merlin::Transcript;
+
+use crate::{
+    traits::{Committer, PolyMultiProofNoPrecomp},
+    Commitment, Error,
+};
+
+/// Numbers the temporary files recordings are written through, so that concurrent writes of the
+/// same recording don't share one
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps a proof scheme, recording each proof it opens to a file in `dir` keyed by the opening's
+/// inputs, and returning the recorded proof instead of opening again when the same inputs are
+/// seen. Committing and verifying are delegated to `inner`.
+///
+/// Proofs are opened on the caller's transcript, so they verify with `inner` as well. The key
+/// includes the transcript's state, and a recorded proof is only replayed after verifying it on
+/// a copy of the caller's transcript against commitments to the polynomials. Verifying absorbs
+/// the same messages as opening, so this leaves the transcript as a fresh opening would, and a
+/// recording that doesn't deserialize or verify is opened and recorded again. Recordings are
+/// written through a temporary file, so an interrupted run can't leave a partial one.
+///
+/// Recordings are also keyed by the scheme's type and its commitment to $1 + x$, so provers with
+/// another setup open afresh rather than replaying a proof that won't verify for them. I/O
+/// failures are reported as [`Error::SerializationError`].
+#[derive(Clone, Debug)]
+pub struct RecordingProver<P> {
+    /// The proof scheme proofs are recorded from
+    pub inner: P,
+    /// The directory recordings are read from and written to
+    pub dir: PathBuf,
+}
+
+impl<P> RecordingProver<P> {
+    /// Make a new recording prover, recording to `dir`, which must exist
+    pub fn new(inner: P, dir: impl AsRef<Path>) -> Self {
+        Self {
+            inner,
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The file the proof for an encoded open request is recorded in, for a setup identified by
+    /// `setup` and opened on `transcript`
+    fn recording_path(&self, transcript: &Transcript, setup: &[u8], request: &[u8]) -> PathBuf {
+        let mut transcript = transcript.clone();
+        transcript.append_message(b"recording scheme", core::any::type_name::<P>().as_bytes());
+        transcript.append_message(b"recording setup", setup);
+        transcript.append_message(b"recording request", request);
+        let mut key = [0u8; 32];
+        transcript.challenge_bytes(b"recording key", &mut key);
+        let name = key
+            .iter()
+            .map(|b| std::format!("{b:02x}"))
+            .collect::<String>();
+        self.dir.join(name + ".proof")
+    }
+}
+
+/// Writes `proof` to `path` through a temporary file in the same directory
+fn write_recording(path: &Path, proof: &impl CanonicalSerialize) -> Result<(), Error> {
+    let tmp = path.with_extension(std::format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let written = File::create(&tmp)
+        .map_err(SerializationError::from)
+        .and_then(|file| proof.serialize_compressed(file))
+        .and_then(|()| fs::rename(&tmp, path).map_err(SerializationError::from));
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    Ok(written?)
+}
+
+impl<E: Pairing, P: Committer<E>> Committer<E> for RecordingProver<P> {
+    fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error> {
+        self.inner.commit(poly)
+    }
+
+    fn powers_of_g1(&self) -> &[E::G1Affine] {
+        self.inner.powers_of_g1()
+    }
+
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        self.inner.commit_projective(poly)
+    }
+}
+
+impl<P> RecordingProver<P> {
+    /// Reads the proof recorded at `path` and verifies it on a copy of `transcript`. If it
+    /// verifies, `transcript` is advanced as opening would have and the proof is returned.
+    fn replay<E>(
+        &self,
+        path: &Path,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Option<P::Proof>, Error>
+    where
+        E: Pairing,
+        P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+        P::Proof: CanonicalDeserialize,
+    {
+        let Ok(file) = File::open(path) else {
+            return Ok(None);
+        };
+        let Ok(proof) = P::Proof::deserialize_compressed(file) else {
+            return Ok(None);
+        };
+        let commits = polys
+            .iter()
+            .map(|p| self.inner.commit(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut replayed = transcript.clone();
+        if self
+            .inner
+            .verify(&mut replayed, &commits, points, evals, &proof)
+            != Ok(true)
+        {
+            return Ok(None);
+        }
+        *transcript = replayed;
+        Ok(Some(proof))
+    }
+}
+
+impl<E, P> PolyMultiProofNoPrecomp<E> for RecordingProver<P>
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize + CanonicalDeserialize,
+{
+    type Proof = P::Proof;
+
+    fn open(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        // [1 + tau]_1 pins down the setup's generator and tau
+        let mut setup = Vec::new();
+        self.inner
+            .commit([E::ScalarField::one(); 2])?
+            .serialize_compressed(&mut setup)?;
+        let mut request = Vec::new();
+        let evals_ref = evals.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+        let polys_ref = polys.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
+        evals_ref.serialize_compressed(&mut request)?;
+        polys_ref.serialize_compressed(&mut request)?;
+        points.serialize_compressed(&mut request)?;
+        let path = self.recording_path(transcript, &setup, &request);
+        if let Some(proof) = self.replay(&path, transcript, evals, polys, points)? {
+            return Ok(proof);
+        }
+        let proof = self.inner.open(transcript, evals, polys, points)?;
+        write_recording(&path, &proof)?;
+        Ok(proof)
+    }
+
+    fn verify(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        self.inner.verify(transcript, commits, points, evals, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordingProver;
+    use crate::{
+        method2::M2NoPrecomp,
+        test_rng,
+        testing::{random_valid_opening, test_basic_no_precomp},
+        traits::PolyMultiProofNoPrecomp,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+    use rand::RngCore;
+    use std::vec::Vec;
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(std::format!("pmp-recording-{:016x}", test_rng().next_u64()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_recording_prover_works() {
+        let dir = temp_dir();
+        let s = RecordingProver::new(M2NoPrecomp::<Bls12_381>::new(256, &mut test_rng()), &dir);
+        test_basic_no_precomp(&s);
+        let (commits, points, evals, open) = random_valid_opening(&s, 3, 2, 20, &mut test_rng());
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_replayed_proof_matches_recorded() {
+        let dir = temp_dir();
+        let scheme = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let s = RecordingProver::new(scheme.clone(), &dir);
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals: Vec<Vec<_>> = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+            .collect();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| crate::traits::Committer::commit(&scheme, p).unwrap())
+            .collect::<Vec<_>>();
+        let bytes = |p: &crate::method2::Proof<Bls12_381>| {
+            let mut out = Vec::new();
+            p.serialize_compressed(&mut out).unwrap();
+            out
+        };
+
+        let next_challenge = |transcript: &mut Transcript| {
+            let mut c = [0u8; 32];
+            transcript.challenge_bytes(b"next", &mut c);
+            c
+        };
+
+        let mut recorded_transcript = Transcript::new(b"testing");
+        let recorded = s
+            .open(&mut recorded_transcript, &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+        let mut replayed_transcript = Transcript::new(b"testing");
+        let replayed = s
+            .open(&mut replayed_transcript, &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(bytes(&recorded), bytes(&replayed));
+        // Replaying leaves the transcript where opening does
+        assert_eq!(
+            next_challenge(&mut recorded_transcript),
+            next_challenge(&mut replayed_transcript)
+        );
+        // Proofs are made on the caller's transcript, so the inner scheme verifies them
+        assert_eq!(
+            Ok(true),
+            scheme.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &replayed
+            )
+        );
+
+        // A truncated recording is opened and recorded again
+        let recording = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let recorded_bytes = std::fs::read(&recording).unwrap();
+        std::fs::write(&recording, &recorded_bytes[..10]).unwrap();
+        let reopened = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(bytes(&recorded), bytes(&reopened));
+        assert_eq!(recorded_bytes, std::fs::read(&recording).unwrap());
+        assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+
+        // A prover with another setup opens afresh instead of replaying a proof for this one
+        let other_scheme = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let other = RecordingProver::new(other_scheme.clone(), &dir);
+        let fresh = other
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_ne!(bytes(&recorded), bytes(&fresh));
+        assert_eq!(2, std::fs::read_dir(&dir).unwrap().count());
+        let other_commits = coeffs
+            .iter()
+            .map(|p| crate::traits::Committer::commit(&other_scheme, p).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(true),
+            other.verify(
+                &mut Transcript::new(b"testing"),
+                &other_commits,
+                &points,
+                &evals,
+                &fresh
+            )
+        );
+
+        // Different inputs are recorded separately
+        s.open(&mut Transcript::new(b"other"), &evals, &coeffs, &points)
+            .unwrap();
+        assert_eq!(3, std::fs::read_dir(&dir).unwrap().count());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1794 @@
+use super::random_valid_opening;
+use crate::{
+    gen_powers, get_challenge, get_field_size, msm::blst::BlstMSMEngine, test_rng,
+    traits::KZGProof, transcribe_points_and_evals, vanishing_polynomial, vec, Error,
+    LazyCommitment, SchemeConfig, SizeLimits, Vec,
+};
+use ark_bls12_381::Bls12_381;
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing, PairingOutput},
+    AffineRepr,
+};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial,
+};
+use ark_serialize::{CanonicalSerialize, Compress};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use merlin::Transcript;
+
+use crate::traits::{Committer, ConfigurableScheme, PolyMultiProof, PolyMultiProofNoPrecomp};
+
+pub fn test_basic_no_precomp<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let (commits, points, evals, open) = random_valid_opening(s, 30, 20, 50, &mut test_rng());
+    let mut transcript = Transcript::new(b"testing");
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut transcript, &commits, &points, &evals, &open)
+    );
+}
+
+/// Basic test for a precomp. Assumes `points` are the zero-th pointset.
+pub fn test_basic_precomp<E: Pairing, P: PolyMultiProof<E> + Committer<E>>(
+    s: &P,
+    points: &[E::ScalarField],
+) {
+    let polys = (0..20)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let mut transcript = Transcript::new(b"testing");
+    let open = s
+        .open(&mut transcript, &evals, &coeffs, 0)
+        .expect("Open failed");
+    let mut transcript = Transcript::new(b"testing");
+    assert_eq!(
+        Ok(true),
+        s.verify(&mut transcript, &commits, 0, &evals, &open)
+    );
+}
+
+pub fn test_commit_and_open_rs<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let data = (0..16)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let data_domain = GeneralEvaluationDomain::<E::ScalarField>::new(16).unwrap();
+    let ext_domain = GeneralEvaluationDomain::<E::ScalarField>::new(32).unwrap();
+    let encoded = ext_domain.fft(&data_domain.ifft(&data));
+    let sample_idxs = [1, 4, 9, 16, 31];
+    let sample_points = sample_idxs
+        .iter()
+        .map(|&i| ext_domain.element(i))
+        .collect::<Vec<_>>();
+
+    let (commit, evals, open) = s
+        .commit_and_open_rs(
+            &mut Transcript::new(b"testing"),
+            &data,
+            &data_domain,
+            &sample_points,
+        )
+        .expect("Open failed");
+    for (e, &i) in evals.iter().zip(sample_idxs.iter()) {
+        assert_eq!(*e, encoded[i]);
+    }
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &[commit],
+            &sample_points,
+            &[evals],
+            &open
+        )
+    );
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 16,
+            got: 15
+        }),
+        s.commit_and_open_rs(
+            &mut Transcript::new(b"testing"),
+            &data[..15],
+            &data_domain,
+            &sample_points,
+        )
+        .map(|_| ())
+    );
+}
+
+pub fn test_padded_polys<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..5)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(200, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    // Pad the polynomials past the size of the setup
+    let padded = polys
+        .iter()
+        .map(|p| {
+            let mut c = p.coeffs.clone();
+            c.resize(300, E::ScalarField::zero());
+            c
+        })
+        .collect::<Vec<_>>();
+    let commits = padded
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    for (c, p) in commits.iter().zip(polys.iter()) {
+        assert_eq!(c.0, s.commit(&p.coeffs).expect("Commit failed").0);
+    }
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &padded, &points)
+        .expect("Open failed");
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+pub fn test_vanishes_on<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..8)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    // Has the points as roots
+    let r = DensePolynomial::<E::ScalarField>::rand(10, &mut test_rng());
+    let p = &crate::vanishing_polynomial(&points) * &r;
+    let commit = s.commit(&p.coeffs).expect("Commit failed");
+    let proof = s
+        .prove_vanishes_on(&mut Transcript::new(b"testing"), &p.coeffs, &points)
+        .expect("Open failed");
+    assert_eq!(
+        Ok(true),
+        s.verify_vanishes_on(&mut Transcript::new(b"testing"), &commit, &points, &proof)
+    );
+
+    // Missing one of the roots
+    let (q, _) = crate::poly_div_q_r(
+        (&p).into(),
+        (&DensePolynomial::from_coefficients_vec(vec![-points[3], E::ScalarField::one()])).into(),
+    )
+    .unwrap();
+    assert_eq!(
+        Err(Error::PolynomialDoesNotVanish { point_index: 3 }),
+        s.prove_vanishes_on(&mut Transcript::new(b"testing"), &q, &points)
+            .map(|_| ())
+    );
+    let commit_q = s.commit(&q).expect("Commit failed");
+    assert_eq!(
+        Ok(false),
+        s.verify_vanishes_on(&mut Transcript::new(b"testing"), &commit_q, &points, &proof)
+    );
+}
+
+pub fn test_agree_on_set<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..8)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let p = DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng());
+    // q agrees with p on the points but differs everywhere else
+    let r = DensePolynomial::<E::ScalarField>::rand(10, &mut test_rng());
+    let q = &p + &(&crate::vanishing_polynomial(&points) * &r);
+    let other = DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng());
+    let pt = E::ScalarField::rand(&mut test_rng());
+    assert_ne!(p.evaluate(&pt), q.evaluate(&pt));
+
+    let commit_p = s.commit(&p.coeffs).expect("Commit failed");
+    let commit_q = s.commit(&q.coeffs).expect("Commit failed");
+    let commit_other = s.commit(&other.coeffs).expect("Commit failed");
+    let proof = s
+        .prove_agree_on_set(
+            &mut Transcript::new(b"testing"),
+            &p.coeffs,
+            &q.coeffs,
+            &points,
+        )
+        .expect("Open failed");
+    assert_eq!(
+        Ok(true),
+        s.verify_agree_on_set(
+            &mut Transcript::new(b"testing"),
+            &commit_p,
+            &commit_q,
+            &points,
+            &proof
+        )
+    );
+
+    let bad_proof = s.prove_agree_on_set(
+        &mut Transcript::new(b"testing"),
+        &p.coeffs,
+        &other.coeffs,
+        &points,
+    );
+    // With debug checks the disagreement is caught while proving
+    if cfg!(feature = "debug-checks") {
+        assert_eq!(
+            Err(Error::InconsistentEvals {
+                poly_index: 0,
+                point_index: 0
+            }),
+            bad_proof.map(|_| ())
+        );
+        return;
+    }
+    let bad_proof = bad_proof.expect("Open failed");
+    assert_eq!(
+        Ok(false),
+        s.verify_agree_on_set(
+            &mut Transcript::new(b"testing"),
+            &commit_p,
+            &commit_other,
+            &points,
+            &bad_proof
+        )
+    );
+}
+
+/// Opens and verifies constant polynomials, including the zero polynomial given both with no
+/// coefficients and with zero coefficients, where every eval is the same
+pub fn test_constant_polys<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let c = E::ScalarField::rand(&mut test_rng());
+    for coeffs in [
+        vec![vec![c], vec![c], vec![c]],
+        vec![vec![c, E::ScalarField::zero()], vec![E::ScalarField::one()]],
+        vec![vec![], vec![E::ScalarField::zero(); 3]],
+    ] {
+        let evals = coeffs
+            .iter()
+            .map(|p| vec![p.first().copied().unwrap_or_default(); points.len()])
+            .collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).expect("Commit failed"))
+            .collect::<Vec<_>>();
+        let open = s
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .expect("Open failed");
+        assert_eq!(
+            Ok(true),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+        let mut bad_evals = evals.clone();
+        bad_evals[0][1] += E::ScalarField::one();
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &bad_evals,
+                &open
+            )
+        );
+    }
+}
+
+/// Checks each polynomial's proof from `open_unaggregated` verifies on its own, and that
+/// `verify_unaggregated` reports the indices of the polynomials whose proofs don't
+pub fn test_unaggregated<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..5)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(20, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let base = Transcript::new(b"testing");
+    let mut proofs = s
+        .open_unaggregated(&base, &evals, &coeffs, &points)
+        .expect("Open failed");
+    assert_eq!(5, proofs.len());
+    assert_eq!(
+        Ok(vec![]),
+        s.verify_unaggregated(&base, &commits, &points, &evals, &proofs)
+    );
+
+    // Replace the proofs of polynomials 1 and 3 with their neighbors'
+    proofs[1] = proofs[0].clone();
+    proofs[3] = proofs[4].clone();
+    assert_eq!(
+        Ok(vec![1, 3]),
+        s.verify_unaggregated(&base, &commits, &points, &evals, &proofs)
+    );
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 5,
+            got: 4
+        }),
+        s.verify_unaggregated(&base, &commits, &points, &evals, &proofs[..4])
+    );
+}
+
+/// Splits an opening into shards and checks each verifies on its own against its range of the
+/// commitments, and only as its own shard
+pub fn test_open_sharded<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..7)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(20, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let base = Transcript::new(b"testing");
+    let shards = s
+        .open_sharded(&base, &evals, &coeffs, &points, 3)
+        .expect("Open failed");
+    let ranges = crate::shard_ranges(7, 3).unwrap();
+    assert_eq!(vec![0..2, 2..4, 4..7], ranges);
+    assert_eq!(3, shards.len());
+    for (i, (range, shard)) in ranges.iter().zip(&shards).enumerate() {
+        let verify_as = |index: usize, evals: &[Vec<E::ScalarField>]| {
+            s.verify_shard(
+                &base,
+                index,
+                3,
+                &commits[range.clone()],
+                &points,
+                evals,
+                shard,
+            )
+        };
+        assert_eq!(Ok(true), verify_as(i, &evals[range.clone()]));
+        // Shard transcripts are distinct, so a shard doesn't verify in another position
+        assert_eq!(Ok(false), verify_as((i + 1) % 3, &evals[range.clone()]));
+        let mut bad_evals = evals[range.clone()].to_vec();
+        bad_evals[0][0] += E::ScalarField::one();
+        assert_eq!(Ok(false), verify_as(i, &bad_evals));
+    }
+    assert_eq!(
+        Err(Error::InvalidShardIndex {
+            shard: 3,
+            n_shards: 3
+        }),
+        s.verify_shard(&base, 3, 3, &commits[4..], &points, &evals[4..], &shards[2])
+    );
+
+    assert_eq!(
+        Err(Error::InvalidShardCount {
+            n_shards: 8,
+            n_polys: 7
+        }),
+        s.open_sharded(&base, &evals, &coeffs, &points, 8)
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::InvalidShardCount {
+            n_shards: 0,
+            n_polys: 7
+        }),
+        s.open_sharded(&base, &evals, &coeffs, &points, 0)
+            .map(|_| ())
+    );
+}
+
+/// Opens polynomials given as evaluations over domains of different sizes at common points
+pub fn test_open_mixed_domain<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let domains = [16, 32, 16].map(|n| GeneralEvaluationDomain::<E::ScalarField>::new(n).unwrap());
+    let polys = domains
+        .iter()
+        .map(|d| DensePolynomial::<E::ScalarField>::rand(d.size() - 1, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals_and_domains = polys
+        .iter()
+        .zip(domains)
+        .map(|(p, d)| (d.fft(&p.coeffs), d))
+        .collect::<Vec<_>>();
+    let commits = polys
+        .iter()
+        .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let points = (0..5)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+
+    let (evals, open) = s
+        .open_mixed_domain(
+            &mut Transcript::new(b"testing"),
+            &evals_and_domains,
+            &points,
+        )
+        .expect("Open failed");
+    for (p, e) in polys.iter().zip(&evals) {
+        assert_eq!(points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>(), *e);
+    }
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+
+    // Evals must fill their domain
+    let mut short = evals_and_domains.clone();
+    short[1].0.pop();
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 32,
+            got: 31
+        }),
+        s.open_mixed_domain(&mut Transcript::new(b"testing"), &short, &points)
+            .map(|_| ())
+    );
+}
+
+/// Opens polynomials including the zero polynomial, whose commitment is the point at infinity.
+/// `s` accepts it, while the same scheme set to reject identity commitments doesn't.
+pub fn test_identity_commitments<E, P>(s: &P)
+where
+    E: Pairing,
+    P: ConfigurableScheme<E> + Committer<E> + Clone,
+{
+    let strict = &s.clone().with_reject_identity_commitments(true);
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let coeffs = vec![
+        DensePolynomial::<E::ScalarField>::rand(10, &mut test_rng()).coeffs,
+        vec![E::ScalarField::zero(); 3],
+    ];
+    let evals = coeffs
+        .iter()
+        .map(|p| {
+            let p = DensePolynomial::from_coefficients_slice(p);
+            points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    assert!(commits[1].0.is_zero());
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    let verify_with = |s: &P, commits: &[_], evals: &[_]| {
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            commits,
+            &points,
+            evals,
+            &open,
+        )
+    };
+    assert_eq!(Ok(true), verify_with(s, &commits, &evals));
+    assert_eq!(
+        Err(Error::IdentityCommitment { index: 1 }),
+        verify_with(strict, &commits, &evals)
+    );
+    assert_eq!(
+        Err(Error::IdentityCommitment { index: 1 }),
+        strict.prepare_verify(&commits, &points, &evals).map(|_| ())
+    );
+    // Nor can the strict scheme finish what the lenient one prepared
+    let prepared = s.prepare_verify(&commits, &points, &evals).unwrap();
+    assert_eq!(
+        Err(Error::PreparedByOtherScheme),
+        strict.finish_verify(&mut Transcript::new(b"testing"), prepared, &open)
+    );
+
+    // Without the zero polynomial the strict scheme verifies as usual
+    let (commits, points, evals, open) = random_valid_opening(s, 4, 2, 10, &mut test_rng());
+    assert_eq!(
+        Ok(true),
+        strict.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+/// Opens and verifies at no points, which is a vacuous statement with an identity proof
+pub fn test_empty_points<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let coeffs = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(20, &mut test_rng()).coeffs)
+        .collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let evals = vec![Vec::<E::ScalarField>::new(); 3];
+    let mut transcript = Transcript::new(b"testing");
+    let open = s
+        .open(&mut transcript, &evals, &coeffs, &[])
+        .expect("Open failed");
+    // The transcript is left as-is
+    let mut fresh = Transcript::new(b"testing");
+    assert_eq!(
+        get_challenge::<E::ScalarField>(&mut fresh, b"check", 32),
+        get_challenge::<E::ScalarField>(&mut transcript, b"check", 32)
+    );
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &[],
+            &evals,
+            &open
+        )
+    );
+
+    // An identity proof is the only one that verifies
+    let (_, _, other_evals, other) = random_valid_opening(s, 2, 3, 20, &mut test_rng());
+    assert_eq!(
+        Ok(false),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &[],
+            &evals,
+            &other
+        )
+    );
+    // Evals must still be empty for each polynomial
+    assert_eq!(
+        Err(Error::EvalsAndPointsDifferentSizes {
+            n_evals: 2,
+            n_points: 0
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &[],
+            &other_evals,
+            &other
+        )
+    );
+    assert!(s
+        .open(&mut Transcript::new(b"testing"), &other_evals, &coeffs, &[])
+        .is_err());
+}
+
+/// Checks a proof opened under one phase label only verifies under the same label
+pub fn test_phase_labels<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..5)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let open = s
+        .open_in_phase(
+            b"phase1",
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &coeffs,
+            &points,
+        )
+        .expect("Open failed");
+    let verify_in = |label: &[u8]| {
+        s.verify_in_phase(
+            label,
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open,
+        )
+    };
+    assert_eq!(Ok(true), verify_in(b"phase1"));
+    assert_eq!(Ok(false), verify_in(b"phase2"));
+    assert_eq!(
+        Ok(false),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+/// Whether the final exponentiation of a Miller loop output is one
+pub fn miller_output_is_one<E: Pairing>(out: E::TargetField) -> bool {
+    E::final_exponentiation(MillerLoopOutput(out)) == Some(PairingOutput(E::TargetField::one()))
+}
+
+/// Replays the gamma challenge a prover draws when opening at `points` with `evals` on a fresh
+/// `b"testing"` transcript
+pub fn replay_challenge<F: PrimeField>(points: &[F], evals: &[Vec<F>]) -> F {
+    let field_size_bytes = get_field_size::<F>();
+    let mut transcript = Transcript::new(b"testing");
+    transcribe_points_and_evals(
+        &mut transcript,
+        points,
+        evals,
+        field_size_bytes,
+        Compress::Yes,
+    )
+    .unwrap();
+    get_challenge(&mut transcript, b"open gamma", field_size_bytes)
+}
+
+/// Checks verifying with `prepare_verify` then `finish_verify` agrees with `verify`, and that
+/// `prepare_verify` catches bad sizes
+pub fn test_prepare_verify<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let (commits, points, evals, open) = random_valid_opening(s, 5, 3, 30, &mut test_rng());
+    let mut bad_evals = evals.clone();
+    bad_evals[1][0] += E::ScalarField::one();
+    for evals in [&evals, &bad_evals] {
+        let prepared = s.prepare_verify(&commits, &points, evals).unwrap();
+        assert_eq!(
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                evals,
+                &open
+            ),
+            s.finish_verify(&mut Transcript::new(b"testing"), prepared, &open)
+        );
+    }
+
+    let mut short_evals = evals.clone();
+    short_evals[2].pop();
+    assert_eq!(
+        Err(Error::EvalsAndPointsDifferentSizes {
+            n_evals: 4,
+            n_points: 5
+        }),
+        s.prepare_verify(&commits, &points, &short_evals)
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::EvalsAndCommitsDifferentSizes {
+            n_evals: 3,
+            n_commits: 2
+        }),
+        s.prepare_verify(&commits[..2], &points, &evals).map(|_| ())
+    );
+}
+
+/// Checks `open_point_major` gives the same proof as `open` with the evals transposed, and errors
+/// if a point has the wrong number of evals
+pub fn test_open_point_major<E, P>(s: &P)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize,
+{
+    let points = (0..5)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let evals_by_point: Vec<Vec<_>> = points
+        .iter()
+        .map(|x| polys.iter().map(|p| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let point_major = s
+        .open_point_major(
+            &mut Transcript::new(b"testing"),
+            &evals_by_point,
+            &coeffs,
+            &points,
+        )
+        .expect("Open failed");
+    let poly_major = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    point_major.serialize_compressed(&mut a).unwrap();
+    poly_major.serialize_compressed(&mut b).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &point_major
+        )
+    );
+
+    let mut short_row = evals_by_point.clone();
+    short_row[2].pop();
+    assert_eq!(
+        Err(Error::PointEvalsIncorrectSize {
+            point: 2,
+            n_evals: 2,
+            expected: 3
+        }),
+        s.open_point_major(
+            &mut Transcript::new(b"testing"),
+            &short_row,
+            &coeffs,
+            &points
+        )
+        .map(|_| ())
+    );
+}
+
+/// Opening by domain indices is the same as opening at the domain elements
+pub fn test_open_by_indices<E, P>(s: &P)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize,
+{
+    let domain = GeneralEvaluationDomain::<E::ScalarField>::new(16).unwrap();
+    let indices = [3, 0, 15, 8];
+    let points = indices
+        .iter()
+        .map(|&i| domain.element(i))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let by_indices = s
+        .open_by_indices(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &coeffs,
+            &domain,
+            &indices,
+        )
+        .expect("Open failed");
+    let by_points = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    by_indices.serialize_compressed(&mut a).unwrap();
+    by_points.serialize_compressed(&mut b).unwrap();
+    assert_eq!(a, b);
+
+    assert_eq!(
+        Ok(true),
+        s.verify_by_indices(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &domain,
+            &indices,
+            &evals,
+            &by_indices
+        )
+    );
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &by_indices
+        )
+    );
+    assert_eq!(
+        Err(Error::IndexOutOfDomain {
+            idx: 16,
+            domain_size: 16
+        }),
+        s.verify_by_indices(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &domain,
+            &[3, 0, 16, 8],
+            &evals,
+            &by_indices
+        )
+    );
+}
+
+pub fn test_linked_rounds<E, P>(s: &P)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize,
+{
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    // Use more than one polynomial so the proofs depend on the transcript's challenge
+    let polys = (0..2)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let commits = polys
+        .iter()
+        .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let rounds = points
+        .chunks(2)
+        .map(|pts| {
+            let evals = polys
+                .iter()
+                .map(|p| pts.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            (pts, evals)
+        })
+        .collect::<Vec<_>>();
+
+    let mut transcript = Transcript::new(b"testing");
+    let proofs = rounds
+        .iter()
+        .map(|(pts, evals)| {
+            s.open_linked(&mut transcript, evals, &coeffs, pts)
+                .expect("Open failed")
+        })
+        .collect::<Vec<_>>();
+
+    let mut transcript = Transcript::new(b"testing");
+    for ((pts, evals), proof) in rounds.iter().zip(proofs.iter()) {
+        assert_eq!(
+            Ok(true),
+            s.verify_linked(&mut transcript, &commits, pts, evals, proof)
+        );
+    }
+
+    // Verifying the rounds in a different order breaks the link
+    let mut transcript = Transcript::new(b"testing");
+    for ((pts, evals), proof) in rounds.iter().zip(proofs.iter()).rev() {
+        assert_eq!(
+            Ok(false),
+            s.verify_linked(&mut transcript, &commits, pts, evals, proof)
+        );
+    }
+}
+
+/// Checks that an identity transform matches plain `commit` and `open`, and that a doubling
+/// transform opens to the doubled evaluations
+pub fn test_transform<E, P>(s: &P)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize,
+{
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let proof_bytes = |p: &P::Proof| {
+        let mut bytes = Vec::new();
+        p.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    };
+
+    let identity = |c: &[E::ScalarField]| c.to_vec();
+    for c in coeffs.iter() {
+        assert_eq!(
+            s.commit(c).unwrap().0,
+            s.commit_with_transform(c, identity).unwrap().0
+        );
+    }
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    let open_identity = s
+        .open_with_transform(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &coeffs,
+            &points,
+            identity,
+        )
+        .expect("Open failed");
+    assert_eq!(proof_bytes(&open), proof_bytes(&open_identity));
+
+    let double = |c: &[E::ScalarField]| c.iter().map(|x| x.double()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|c| s.commit_with_transform(c, double).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let doubled_evals = evals
+        .iter()
+        .map(|e| e.iter().map(|x| x.double()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let open = s
+        .open_with_transform(
+            &mut Transcript::new(b"testing"),
+            &doubled_evals,
+            &coeffs,
+            &points,
+            double,
+        )
+        .expect("Open failed");
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &doubled_evals,
+            &open
+        )
+    );
+    assert_eq!(
+        Ok(false),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+/// Checks that `open_binding` proofs verify with the same commitments, and not with the
+/// commitments reordered, swapped for other commitments with the same evals, or left out of the
+/// transcript
+pub fn test_binding<E, P>(s: &P)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+{
+    let points = (0..4)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let commits = polys
+        .iter()
+        .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let proof = s
+        .open_binding(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &evals,
+            &coeffs,
+            &points,
+        )
+        .expect("Open failed");
+    assert_eq!(
+        Ok(true),
+        s.verify_binding(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+    assert_eq!(
+        Ok(false),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+
+    let mut reordered = commits.clone();
+    reordered.swap(0, 2);
+    assert_eq!(
+        Ok(false),
+        s.verify_binding(
+            &mut Transcript::new(b"testing"),
+            &reordered,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+
+    // Polynomials that agree on the points have the same evals but other commitments
+    let vp = vanishing_polynomial(&points);
+    let other_commits = polys
+        .iter()
+        .map(|p| {
+            let shift = DensePolynomial::<E::ScalarField>::rand(3, &mut test_rng());
+            s.commit((p + &(&vp * &shift)).coeffs)
+                .expect("Commit failed")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        Ok(false),
+        s.verify_binding(
+            &mut Transcript::new(b"testing"),
+            &other_commits,
+            &points,
+            &evals,
+            &proof
+        )
+    );
+
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 3,
+            got: 2
+        }),
+        s.open_binding(
+            &mut Transcript::new(b"testing"),
+            &commits[1..],
+            &evals,
+            &coeffs,
+            &points,
+        )
+        .map(|_| ())
+    );
+}
+
+/// Checks `verify_lazy` against materializing the lazy commitments and calling `verify`
+pub fn test_verify_lazy<E: Pairing, P: ConfigurableScheme<E> + Committer<E>>(s: &P) {
+    let n_bases = 4;
+    let bases = (0..n_bases)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let base_commits = bases
+        .iter()
+        .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    let lazy = (0..3)
+        .map(|_| {
+            base_commits
+                .iter()
+                .map(|c| (*c, E::ScalarField::rand(&mut test_rng())))
+                .collect::<LazyCommitment<E>>()
+        })
+        .collect::<Vec<_>>();
+    let polys = lazy
+        .iter()
+        .map(|l| {
+            l.iter()
+                .zip(bases.iter())
+                .fold(DensePolynomial::zero(), |acc, ((_, c), p)| acc + p * *c)
+        })
+        .collect::<Vec<_>>();
+    let materialized = polys
+        .iter()
+        .map(|p| s.commit(&p.coeffs).expect("Commit failed"))
+        .collect::<Vec<_>>();
+
+    let points = (0..5)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &materialized,
+            &points,
+            &evals,
+            &open
+        )
+    );
+    assert_eq!(
+        Ok(true),
+        s.verify_lazy(
+            &mut Transcript::new(b"testing"),
+            &lazy,
+            &points,
+            &evals,
+            &open
+        )
+    );
+    let mut bad = lazy.clone();
+    bad[1][2].1 += E::ScalarField::one();
+    assert_eq!(
+        Ok(false),
+        s.verify_lazy(
+            &mut Transcript::new(b"testing"),
+            &bad,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+/// Checks opening with a given vanishing polynomial gives the same proof as `open`
+pub fn test_open_with_vanishing<E, P, O>(s: &P, open_with_vanishing: O)
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    P::Proof: CanonicalSerialize,
+    O: Fn(
+        &mut Transcript,
+        &[Vec<E::ScalarField>],
+        &[Vec<E::ScalarField>],
+        &[E::ScalarField],
+        &[E::ScalarField],
+    ) -> Result<P::Proof, Error>,
+{
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..5)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let vp = vanishing_polynomial(&points);
+
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    let open_vp = open_with_vanishing(
+        &mut Transcript::new(b"testing"),
+        &evals,
+        &coeffs,
+        &points,
+        &vp.coeffs,
+    )
+    .expect("Open failed");
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    open.serialize_compressed(&mut a).unwrap();
+    open_vp.serialize_compressed(&mut b).unwrap();
+    assert_eq!(a, b);
+}
+
+/// Checks schemes built by `new` from the same seed are the same setup, and from different seeds
+/// aren't. Returns one of the schemes for checking its sizes.
+pub fn test_new_from_seeded_rng<E, P, N>(new: N) -> P
+where
+    E: Pairing,
+    P: PolyMultiProofNoPrecomp<E> + Committer<E>,
+    N: Fn(&mut StdRng) -> P,
+{
+    const SEED: u64 = 0x5eed;
+    let s1 = new(&mut StdRng::seed_from_u64(SEED));
+    let s2 = new(&mut StdRng::seed_from_u64(SEED));
+    let other = new(&mut StdRng::seed_from_u64(SEED + 1));
+
+    let poly = DensePolynomial::<E::ScalarField>::rand(10, &mut test_rng());
+    let commit = s1.commit(&poly.coeffs).expect("Commit failed");
+    assert_eq!(commit, s2.commit(&poly.coeffs).expect("Commit failed"));
+    assert_ne!(commit, other.commit(&poly.coeffs).expect("Commit failed"));
+    let (commits, points, evals, open) = random_valid_opening(&s1, 4, 3, 10, &mut test_rng());
+    assert_eq!(
+        Ok(true),
+        s2.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+    s1
+}
+
+/// Checks `commit_batch` matches `commit` on each polynomial, including empty and zero ones, and
+/// only errors for the polynomial with more than `setup_size` coefficients
+pub fn test_commit_batch<E: Pairing, P: Committer<E> + Sync>(s: &P, setup_size: usize) {
+    let polys = (0..30)
+        .map(|i| DensePolynomial::<E::ScalarField>::rand(i % setup_size, &mut test_rng()).coeffs)
+        .chain([
+            vec![],
+            vec![E::ScalarField::zero(); 5],
+            DensePolynomial::<E::ScalarField>::rand(setup_size, &mut test_rng()).coeffs,
+        ])
+        .collect::<Vec<_>>();
+    let commits = s.commit_batch(&polys);
+    assert_eq!(commits.len(), polys.len());
+    let (too_large, rest) = commits.split_last().unwrap();
+    for (c, p) in rest.iter().zip(&polys) {
+        assert_eq!(c.as_ref().unwrap(), &s.commit(p).unwrap());
+    }
+    assert_eq!(
+        Err(&Error::TooManyScalars {
+            n_coeffs: setup_size + 1,
+            expected_max: setup_size
+        }),
+        too_large.as_ref().map(|_| ())
+    );
+}
+
+/// Checks `commit_sparse` matches `commit` on the dense polynomial, and errors on an index past
+/// the `setup_size` powers of tau, including one too large to count
+pub fn test_commit_sparse<E: Pairing, P: Committer<E>>(s: &P, setup_size: usize) {
+    let nonzero = [
+        (0, E::ScalarField::rand(&mut test_rng())),
+        (17, E::ScalarField::rand(&mut test_rng())),
+        (setup_size - 1, E::ScalarField::rand(&mut test_rng())),
+    ];
+    let mut dense = vec![E::ScalarField::zero(); setup_size];
+    for (i, c) in nonzero {
+        dense[i] = c;
+    }
+    assert_eq!(
+        s.commit(&dense).unwrap(),
+        s.commit_sparse(&nonzero).unwrap()
+    );
+    assert_eq!(
+        Err(Error::TooManyScalars {
+            n_coeffs: setup_size + 1,
+            expected_max: setup_size
+        }),
+        s.commit_sparse(&[(setup_size, E::ScalarField::one())])
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err(Error::TooManyScalars {
+            n_coeffs: usize::MAX,
+            expected_max: setup_size
+        }),
+        s.commit_sparse(&[(usize::MAX, E::ScalarField::one())])
+            .map(|_| ())
+    );
+}
+
+pub fn test_matrix_column<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(
+    s: &P,
+    degree: usize,
+) {
+    let rows = (0..8)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(degree, &mut test_rng()).coeffs)
+        .collect::<Vec<_>>();
+    let commits = s.commit_matrix(&rows).unwrap();
+    let column_point = E::ScalarField::rand(&mut test_rng());
+    let (evals, proof) = s
+        .open_matrix_column(&mut Transcript::new(b"testing"), &rows, column_point)
+        .unwrap();
+    assert_eq!(
+        Ok(true),
+        s.verify_matrix_column(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            column_point,
+            &evals,
+            &proof
+        )
+    );
+    let mut bad_evals = evals.clone();
+    bad_evals[3] += E::ScalarField::one();
+    assert_eq!(
+        Ok(false),
+        s.verify_matrix_column(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            column_point,
+            &bad_evals,
+            &proof
+        )
+    );
+}
+
+/// Checks `commit_be` matches `commit` on the reversed coefficients, including for a polynomial
+/// with a zero constant term filling the `setup_size` powers of tau
+pub fn test_commit_be<E: Pairing, P: Committer<E>>(s: &P, setup_size: usize) {
+    let coeffs = DensePolynomial::<E::ScalarField>::rand(40, &mut test_rng()).coeffs;
+    let reversed = coeffs.iter().rev().copied().collect::<Vec<_>>();
+    assert_eq!(s.commit(&coeffs), s.commit_be(&reversed));
+    assert_ne!(s.commit(&coeffs), s.commit_be(&coeffs));
+
+    let mut shifted = vec![E::ScalarField::zero(); setup_size - coeffs.len()];
+    shifted.extend(&coeffs);
+    let reversed = shifted.iter().rev().copied().collect::<Vec<_>>();
+    assert_eq!(s.commit(&shifted), s.commit_be(&reversed));
+}
+
+/// Checks `verify_with_challenge_out` returns the gamma challenge the prover drew, whether or
+/// not the proof verifies
+pub fn test_verify_with_challenge_out<E: Pairing, P: ConfigurableScheme<E> + Committer<E>>(s: &P) {
+    let (commits, points, evals, open) = random_valid_opening(s, 5, 3, 30, &mut test_rng());
+    let gamma = replay_challenge(&points, &evals);
+    assert_eq!(
+        Ok((true, gamma)),
+        s.verify_with_challenge_out(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+    let mut bad_evals = evals.clone();
+    bad_evals[0][0] += E::ScalarField::one();
+    assert_eq!(
+        Ok((false, replay_challenge(&points, &bad_evals))),
+        s.verify_with_challenge_out(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &bad_evals,
+            &open
+        )
+    );
+}
+
+/// Checks capping the gamma challenge at 128 bits draws a challenge that small, which the prover
+/// and verifier have to agree on, and that caps below the minimum are rejected
+pub fn test_challenge_bits<E, P>(s: &P)
+where
+    E: Pairing,
+    P: ConfigurableScheme<E> + Committer<E> + Clone,
+{
+    for bits in [0, SchemeConfig::MIN_CHALLENGE_BITS - 1] {
+        assert_eq!(
+            Err(Error::ChallengeBitsTooSmall {
+                bits,
+                min: SchemeConfig::MIN_CHALLENGE_BITS
+            }),
+            s.clone().with_challenge_bits(bits).map(|_| ())
+        );
+    }
+    let capped = s.clone().with_challenge_bits(128).unwrap();
+    let (commits, points, evals, open) = random_valid_opening(&capped, 5, 3, 30, &mut test_rng());
+    let verify = |s: &P| {
+        s.verify_with_challenge_out(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open,
+        )
+    };
+    let (valid, gamma) = verify(&capped).unwrap();
+    assert!(valid);
+    assert!(gamma.into_bigint().num_bits() <= 128);
+    assert_eq!(Ok(false), verify(s).map(|(valid, _)| valid));
+}
+
+/// Checks the Miller loop output `verify_miller_output` gives is one after the final
+/// exponentiation exactly when the proof verifies
+pub fn test_verify_miller_output<E: Pairing, P: ConfigurableScheme<E> + Committer<E>>(s: &P) {
+    let (commits, points, evals, open) = random_valid_opening(s, 5, 3, 30, &mut test_rng());
+    let mut bad_evals = evals.clone();
+    bad_evals[1][2] += E::ScalarField::one();
+    for (evals, valid) in [(&evals, true), (&bad_evals, false)] {
+        let out = s
+            .verify_miller_output(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                evals,
+                &open,
+            )
+            .unwrap();
+        assert_eq!(valid, miller_output_is_one::<E>(out));
+        assert_eq!(
+            Ok(valid),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                evals,
+                &open
+            )
+        );
+    }
+}
+
+/// Checks committing to coefficients already out of Montgomery form matches `commit`
+pub fn test_commit_bigint<E: Pairing, P: Committer<E>>(s: &P, setup_size: usize) {
+    let coeffs = (0..setup_size)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let bigints = coeffs.iter().map(|c| c.into_bigint()).collect::<Vec<_>>();
+    assert_eq!(s.commit(&coeffs), s.commit_bigint(&bigints));
+    assert_eq!(
+        Err(Error::TooManyScalars {
+            n_coeffs: setup_size + 1,
+            expected_max: setup_size
+        }),
+        s.commit_bigint(&vec![E::ScalarField::one().into_bigint(); setup_size + 1])
+            .map(|_| ())
+    );
+}
+
+/// Checks `verify_with_powers` agrees with `verify` given the powers of the transcript's
+/// challenge, and rejects too few or wrong powers
+pub fn test_verify_with_powers<E: Pairing, P: ConfigurableScheme<E> + Committer<E>>(s: &P) {
+    let (commits, points, evals, open) = random_valid_opening(s, 5, 3, 30, &mut test_rng());
+    let verify_with = |powers: &[E::ScalarField]| {
+        s.verify_with_powers(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open,
+            powers,
+        )
+    };
+    // Extra powers beyond one per commitment are ignored
+    let powers = gen_powers(replay_challenge(&points, &evals), 8);
+    assert_eq!(Ok(true), verify_with(&powers));
+    assert_eq!(
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        ),
+        verify_with(&powers)
+    );
+    assert_eq!(
+        Err(Error::InvalidInputLength {
+            expected: 3,
+            got: 2
+        }),
+        verify_with(&powers[..2])
+    );
+
+    let wrong = gen_powers(E::ScalarField::rand(&mut test_rng()), 3);
+    if cfg!(feature = "debug-checks") {
+        assert_eq!(Err(Error::WrongChallengePowers), verify_with(&wrong));
+    } else {
+        assert_eq!(Ok(false), verify_with(&wrong));
+    }
+}
+
+pub fn test_size_errors<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..20)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..20)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(50, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).expect("Commit failed"))
+        .collect::<Vec<_>>();
+    // Eval row commit mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPolysDifferentSizes {
+            n_eval_rows: 19,
+            n_polys: 20,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals[..19],
+            &coeffs,
+            &points
+        )
+        .map(|_| ())
+    );
+    // Eval poly mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPolysDifferentSizes {
+            n_eval_rows: 20,
+            n_polys: 19,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals,
+            &coeffs[..19],
+            &points
+        )
+        .map(|_| ())
+    );
+    // Evals point mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPointsDifferentSizes {
+            n_evals: 19,
+            n_points: 20,
+        }),
+        s.open(
+            &mut Transcript::new(b"testing"),
+            &evals.iter().map(|s| &s[..19]).collect::<Vec<_>>(),
+            &coeffs,
+            &points,
+        )
+        .map(|_| ())
+    );
+
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .expect("Open failed");
+    // Eval row commit mismatch
+    assert_eq!(
+        Err(Error::EvalsAndCommitsDifferentSizes {
+            n_evals: 20,
+            n_commits: 19,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits[..19],
+            &points,
+            &evals,
+            &open
+        )
+    );
+    // Eval point mismatch
+    assert_eq!(
+        Err(Error::EvalsAndPointsDifferentSizes {
+            n_evals: 19,
+            n_points: 20,
+        }),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals.iter().map(|a| &a[..19]).collect::<Vec<_>>(),
+            &open
+        )
+    );
+}
+
+/// Checks that `unlimited` limited to at most 3 polynomials and 4 points rejects bigger openings
+/// before looking at them
+pub fn test_size_limits<E, P>(unlimited: &P)
+where
+    E: Pairing,
+    P: ConfigurableScheme<E> + Committer<E> + Clone,
+{
+    let limited = &unlimited.clone().with_limits(SizeLimits {
+        max_polys: Some(3),
+        max_points: Some(4),
+    });
+    let too_many_polys = Err(Error::TooManyPolynomials { n_polys: 4, max: 3 });
+    let too_many_points = Err(Error::TooManyPoints {
+        n_points: 5,
+        max: 4,
+    });
+
+    let (commits, points, evals, open) = random_valid_opening(unlimited, 4, 4, 30, &mut test_rng());
+    let coeffs = vec![vec![E::ScalarField::one()]; 4];
+    assert_eq!(
+        too_many_polys,
+        limited
+            .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .map(|_| ())
+    );
+    assert_eq!(
+        too_many_polys,
+        limited
+            .verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+            .map(|_| ())
+    );
+    assert_eq!(
+        too_many_polys,
+        limited
+            .prepare_verify(&commits, &points, &evals)
+            .map(|_| ())
+    );
+    let prepared = unlimited.prepare_verify(&commits, &points, &evals).unwrap();
+    assert_eq!(
+        Err(Error::PreparedByOtherScheme),
+        limited.finish_verify(&mut Transcript::new(b"testing"), prepared, &open)
+    );
+    // The limits are checked before the evals are, so mismatched sizes still hit them
+    assert_eq!(
+        too_many_polys,
+        limited
+            .open(
+                &mut Transcript::new(b"testing"),
+                &[] as &[Vec<E::ScalarField>],
+                &coeffs,
+                &points
+            )
+            .map(|_| ())
+    );
+
+    let (commits, points, evals, open) = random_valid_opening(unlimited, 5, 3, 30, &mut test_rng());
+    assert_eq!(
+        too_many_points,
+        limited
+            .open(
+                &mut Transcript::new(b"testing"),
+                &evals,
+                &coeffs[..3],
+                &points
+            )
+            .map(|_| ())
+    );
+    assert_eq!(
+        too_many_points,
+        limited
+            .verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+            .map(|_| ())
+    );
+
+    // Openings at the limits still work
+    let (commits, points, evals, open) = random_valid_opening(limited, 4, 3, 30, &mut test_rng());
+    assert_eq!(
+        Ok(true),
+        limited.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+}
+
+/// With `debug-checks`, opening with a wrong eval errors instead of making a proof
+#[cfg(feature = "debug-checks")]
+pub fn test_inconsistent_evals<E: Pairing, P: PolyMultiProofNoPrecomp<E> + Committer<E>>(s: &P) {
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = (0..5)
+        .map(|_| DensePolynomial::<E::ScalarField>::rand(30, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let mut evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    assert!(s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .is_ok());
+
+    evals[3][7] += E::ScalarField::one();
+    assert_eq!(
+        Err(Error::InconsistentEvals {
+            poly_index: 3,
+            point_index: 7
+        }),
+        s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .map(|_| ())
+    );
+}
+
+/// Opening a polynomial with more coefficients than the `setup_size` powers of tau errors up front
+pub fn test_open_too_large<E: Pairing, P: PolyMultiProofNoPrecomp<E>>(s: &P, setup_size: usize) {
+    let points = (0..10)
+        .map(|_| E::ScalarField::rand(&mut test_rng()))
+        .collect::<Vec<_>>();
+    let polys = [setup_size - 1, setup_size, setup_size - 10]
+        .into_iter()
+        .map(|d| DensePolynomial::<E::ScalarField>::rand(d, &mut test_rng()))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        Err(Error::PolynomialTooLarge {
+            poly: 1,
+            n_coeffs: setup_size + 1,
+            expected_max: setup_size,
+        }),
+        s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+            .map(|_| ())
+    );
+}
+
+pub fn test_kzg(srs: &(impl KZGProof<Bls12_381> + Committer<Bls12_381>)) {
+    use ark_bls12_381::Fr;
+
+    fn run(srs: &(impl KZGProof<Bls12_381> + Committer<Bls12_381>), coeffs: Vec<Fr>) {
+        let pt = Fr::rand(&mut test_rng());
+        let value = DensePolynomial::from_coefficients_vec(coeffs.clone()).evaluate(&pt);
+
+        let commit = srs.commit(&coeffs).unwrap();
+        let witness = srs.compute_witness_polynomial(coeffs, pt).unwrap();
+        let proof = srs.open(witness).unwrap();
+
+        let veri = srs
+            .verify::<BlstMSMEngine>(&commit, pt, value, &proof)
+            .unwrap();
+        assert!(veri);
+    }
+
+    // Test random poly works
+    run(srs, DensePolynomial::<Fr>::rand(50, &mut test_rng()).coeffs);
+    // Test zero poly works
+    run(srs, vec![Fr::zero(); 50]);
+    // Test constant poly works
+    run(srs, vec![Fr::one(); 50]);
+    // Test unit poly works
+    let mut unit = vec![Fr::zero(); 50];
+    unit[0] = Fr::one();
+    run(srs, unit);
+}
@@ -1,15 +1,285 @@
 //! Traits used in the BDFG21 and KZG Schemes
-use ark_ec::pairing::{Pairing, PairingOutput};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::vec::Vec;
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ark_std::{
+    rand::{CryptoRng, RngCore},
+    vec,
+    vec::Vec,
+};
 use merlin::Transcript;
 
-use crate::{Commitment, Error};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    batch::PairingEquation,
+    cfg_iter, check_opening_sizes, check_verify_sizes, domain_points, get_challenge,
+    get_field_size,
+    kzg::{PoK, SinglePointOpen, ZeroProof},
+    lagrange::LagrangeInterpContext,
+    merkle::MerkleHasher,
+    poly_ops::{derivative, divide_poly, poly_from_roots, KzgDomain},
+    shard_ranges, shard_transcript, transcribe_generic, CeremonyId, Commitment, Error,
+    LazyCommitment, PointMajor, PreparedVerify, SchemeConfig, SizeLimits,
+};
 
 /// A curve-agnostic trait for a KZG commitment scheme
 pub trait Committer<E: Pairing> {
     /// Commit to the given polynomial
     fn commit(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<Commitment<E>, Error>;
+
+    /// The setup's powers of tau in G1, $[\tau^i]_1$, lowest power first
+    fn powers_of_g1(&self) -> &[E::G1Affine];
+
+    /// Commit to the given polynomial, leaving the commitment in projective form so that many can
+    /// be normalized together
+    fn commit_projective(&self, poly: impl AsRef<[E::ScalarField]>) -> Result<E::G1, Error> {
+        self.commit(poly).map(|c| c.0.into_group())
+    }
+
+    /// Commit to each of the given polynomials, in parallel if the `parallel` feature is enabled.
+    /// A failure to commit to one polynomial does not discard the others, the result for each
+    /// polynomial is returned at its index. The commitments are normalized to affine with a
+    /// single batch inversion.
+    fn commit_batch<P: AsRef<[E::ScalarField]> + Sync>(
+        &self,
+        polys: &[P],
+    ) -> Vec<Result<Commitment<E>, Error>>
+    where
+        Self: Sync,
+    {
+        let results = cfg_iter!(polys)
+            .map(|(_, p)| self.commit_projective(p))
+            .collect::<Vec<_>>();
+        let projective = results
+            .iter()
+            .filter_map(|r| r.as_ref().ok().copied())
+            .collect::<Vec<_>>();
+        let mut affine = E::G1::normalize_batch(&projective).into_iter();
+        results
+            .into_iter()
+            .map(|r| r.map(|_| Commitment(affine.next().expect("one point per commitment"))))
+            .collect()
+    }
+
+    /// Like `commit_batch`, but runs on `pool` rather than rayon's global pool
+    #[cfg(feature = "parallel")]
+    fn commit_batch_in_pool<P: AsRef<[E::ScalarField]> + Sync>(
+        &self,
+        pool: &rayon::ThreadPool,
+        polys: &[P],
+    ) -> Vec<Result<Commitment<E>, Error>>
+    where
+        Self: Sync,
+    {
+        pool.install(|| self.commit_batch(polys))
+    }
+
+    /// Commits to each row of a matrix, where row `i` holds the coefficients of the polynomial
+    /// $f(x, y_i)$ in $x$
+    fn commit_matrix(&self, rows: &[Vec<E::ScalarField>]) -> Result<Vec<Commitment<E>>, Error> {
+        rows.iter().map(|r| self.commit(r)).collect()
+    }
+
+    /// Commits to a sparse polynomial given as `(degree, coefficient)` pairs, only doing an msm
+    /// over the nonzero terms. Repeated degrees are summed.
+    fn commit_sparse(&self, nonzero: &[(usize, E::ScalarField)]) -> Result<Commitment<E>, Error> {
+        let res = crate::sparse_curve_msm::<E::G1>(self.powers_of_g1(), nonzero)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Commits to a polynomial whose coefficients are already converted out of Montgomery form,
+    /// handing them straight to the msm. Gives the same commitment as `commit` on the
+    /// corresponding field elements.
+    fn commit_bigint(
+        &self,
+        coeffs: &[<E::ScalarField as PrimeField>::BigInt],
+    ) -> Result<Commitment<E>, Error> {
+        let res = crate::curve_msm_bigint::<E::G1>(self.powers_of_g1(), coeffs)?;
+        Ok(Commitment(res.into_affine()))
+    }
+
+    /// Commit to a polynomial given highest degree first, i.e. `coeffs_high_to_low[0]` is the
+    /// leading coefficient and the last element is the constant term. `commit` takes them lowest
+    /// degree first.
+    fn commit_be(&self, coeffs_high_to_low: &[E::ScalarField]) -> Result<Commitment<E>, Error> {
+        let coeffs = coeffs_high_to_low.iter().rev().copied().collect::<Vec<_>>();
+        self.commit(coeffs)
+    }
+
+    /// Commits to `coeff_transform(coeffs)` rather than `coeffs`, e.g. to unpack coefficients
+    /// stored several to a field element. Open with the same transform using
+    /// [`PolyMultiProofNoPrecomp::open_with_transform`].
+    fn commit_with_transform(
+        &self,
+        coeffs: &[E::ScalarField],
+        coeff_transform: impl Fn(&[E::ScalarField]) -> Vec<E::ScalarField>,
+    ) -> Result<Commitment<E>, Error> {
+        self.commit(coeff_transform(coeffs))
+    }
+
+    /// Opens the polynomial with coefficients `coeffs` at the single point `z`, returning its
+    /// value there and a proof checked with [`crate::kzg::verify_single`]. This is smaller than a
+    /// multipoint proof for the same opening.
+    fn open_single(
+        &self,
+        coeffs: &[E::ScalarField],
+        z: E::ScalarField,
+    ) -> Result<(E::ScalarField, SinglePointOpen<E>), Error> {
+        let poly = DensePolynomial::from_coefficients_slice(coeffs);
+        let divisor = DensePolynomial::from_coefficients_vec(vec![-z, E::ScalarField::one()]);
+        let (witness, rem) = divide_poly(&poly, &divisor)?;
+        let y = rem.coeffs.first().copied().unwrap_or_default();
+        Ok((y, SinglePointOpen(self.commit(&witness.coeffs)?.0)))
+    }
+
+    /// Commits to the monic polynomial $\prod_i (x - r_i)$ with the given roots, built with
+    /// [`poly_from_roots`]. Errors with [`Error::TooManyScalars`] if its degree is too large for
+    /// the setup.
+    fn commit_from_roots(&self, roots: &[E::ScalarField]) -> Result<Commitment<E>, Error> {
+        self.commit(poly_from_roots(roots).coeffs)
+    }
+
+    /// Commits to the formal derivative $p'$ of the polynomial $p$ with coefficients `coeffs`
+    fn commit_derivative(&self, coeffs: &[E::ScalarField]) -> Result<Commitment<E>, Error> {
+        self.commit(derivative(coeffs))
+    }
+
+    /// Opens the formal derivative $p'$ of the polynomial $p$ with coefficients `coeffs` at `z`,
+    /// returning $p'(z)$ and a proof checked with [`crate::kzg::verify_single`] against
+    /// [`Self::commit_derivative`].
+    ///
+    /// The proof is only against the commitment to $p'$: nothing here shows that commitment is
+    /// the derivative of the one to $p$, which would take a further pairing relation between the
+    /// two. A verifier holding only the commitment to $p$ must get the one to $p'$ from a source
+    /// it trusts to have derived it.
+    fn open_derivative(
+        &self,
+        coeffs: &[E::ScalarField],
+        z: E::ScalarField,
+    ) -> Result<(E::ScalarField, SinglePointOpen<E>), Error> {
+        self.open_single(&derivative(coeffs), z)
+    }
+
+    /// Proves that the polynomial with coefficients `coeffs` is identically zero, which
+    /// [`crate::kzg::verify_zero`] checks against its commitment. Its commitment, the identity, is
+    /// absorbed into the transcript, so later challenges depend on it as they would on any other
+    /// proof. Errors if any coefficient is nonzero.
+    fn prove_zero(
+        &self,
+        transcript: &mut Transcript,
+        coeffs: &[E::ScalarField],
+    ) -> Result<ZeroProof, Error> {
+        if let Some(degree) = coeffs.iter().position(|c| !c.is_zero()) {
+            return Err(Error::PolynomialNotZero { degree });
+        }
+        transcribe_generic(transcript, b"zero commit", &E::G1Affine::zero())?;
+        Ok(ZeroProof)
+    }
+
+    /// Proves knowledge of the coefficients `coeffs` behind their commitment $C$. The prover
+    /// commits to a random mask $m$ as $A$, draws a challenge $e$ from the transcript after
+    /// absorbing $C$ and $A$, and responds with $m + e \cdot$ `coeffs`, which
+    /// [`Self::verify_commit_knowledge`] checks commits to $A + e C$. The response is as long as
+    /// `coeffs`, so this reveals the degree of the polynomial.
+    fn prove_commit_knowledge(
+        &self,
+        transcript: &mut Transcript,
+        coeffs: &[E::ScalarField],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<PoK<E>, Error> {
+        let commit = self.commit(coeffs)?;
+        let mask = (0..coeffs.len())
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let mask_commit = self.commit(&mask)?.0;
+        let e = commit_knowledge_challenge(transcript, &commit, &mask_commit)?;
+        let response = mask.iter().zip(coeffs).map(|(m, c)| *m + e * c).collect();
+        Ok(PoK {
+            mask_commit,
+            response,
+        })
+    }
+
+    /// Verifies a proof from [`Self::prove_commit_knowledge`] that the prover knows the
+    /// coefficients behind `commit`, using a transcript in the same state as the prover's
+    fn verify_commit_knowledge(
+        &self,
+        transcript: &mut Transcript,
+        commit: &Commitment<E>,
+        pok: &PoK<E>,
+    ) -> Result<bool, Error> {
+        let e = commit_knowledge_challenge(transcript, commit, &pok.mask_commit)?;
+        let lhs = self.commit_projective(&pok.response)?;
+        Ok(lhs == pok.mask_commit.into_group() + commit.0 * e)
+    }
+
+    /// Commits to the lagrange basis polynomials $L_i$ over `points`, so the commitment to the
+    /// interpolant of evals $y_i$ is $\sum_i y_i [L_i(\tau)]_1$
+    fn lagrange_basis_commitments(
+        &self,
+        points: &[E::ScalarField],
+    ) -> Result<Vec<Commitment<E>>, Error> {
+        let ctx = LagrangeInterpContext::new_from_points(points)?;
+        // A fresh context keeps its polynomials
+        ctx.lag_polys()
+            .unwrap_or_default()
+            .iter()
+            .map(|l| self.commit(&l.coeffs))
+            .collect()
+    }
+}
+
+/// Absorbs the commitment and the mask commitment of a proof of knowledge and draws its challenge
+fn commit_knowledge_challenge<E: Pairing>(
+    transcript: &mut Transcript,
+    commit: &Commitment<E>,
+    mask_commit: &E::G1Affine,
+) -> Result<E::ScalarField, Error> {
+    transcribe_generic(transcript, b"pok commit", commit)?;
+    transcribe_generic(transcript, b"pok mask", mask_commit)?;
+    Ok(get_challenge(
+        transcript,
+        b"pok challenge",
+        get_field_size::<E::ScalarField>(),
+    ))
+}
+
+/// A setup that comes from a particular powers of tau ceremony
+pub trait Ceremony<E: Pairing> {
+    /// The $[\tau]_2$ element of the setup. Errors with [`Error::SetupTooSmall`] if the setup
+    /// doesn't have it, like a method 1 setup made for opening at no points.
+    fn g2_tau(&self) -> Result<E::G2Affine, Error>;
+
+    /// Identifies the ceremony the setup came from by hashing $[\tau]_2$. Setups of different
+    /// sizes from the same ceremony have the same id. Errors like [`Self::g2_tau`].
+    fn ceremony_id(&self) -> Result<CeremonyId, Error> {
+        let mut bytes = Vec::new();
+        self.g2_tau()?
+            .serialize_compressed(&mut bytes)
+            .expect("serializing to a vec can't fail");
+        let mut transcript = Transcript::new(b"poly-multiproof ceremony id");
+        transcript.append_message(b"g2 tau", &bytes);
+        let mut id = [0u8; 32];
+        transcript.challenge_bytes(b"ceremony id", &mut id);
+        Ok(id)
+    }
+
+    /// Errors with [`Error::CeremonyMismatch`] if this setup isn't from the ceremony `expected`
+    fn check_ceremony(&self, expected: &CeremonyId) -> Result<(), Error> {
+        if &self.ceremony_id()? != expected {
+            return Err(Error::CeremonyMismatch);
+        }
+        Ok(())
+    }
 }
 
 /// A curve-agnostic trait for making KZG opening proofs
@@ -29,6 +299,18 @@ pub trait KZGProof<E: Pairing>: Sized {
     /// polynomial
     fn open(&self, witness_poly: Vec<E::ScalarField>) -> Result<Self::Proof, Error>;
 
+    /// Opens the polynomial at zero, returning its constant term and the proof. The witness
+    /// $(p(x) - p(0)) / x$ is just the coefficients shifted down, so no division is needed.
+    /// Verify with `verify` at the point zero.
+    fn open_at_zero(
+        &self,
+        coeffs: &[E::ScalarField],
+    ) -> Result<(E::ScalarField, Self::Proof), Error> {
+        let value = coeffs.first().copied().unwrap_or_else(E::ScalarField::zero);
+        let witness = coeffs.get(1..).unwrap_or_default().to_vec();
+        Ok((value, self.open(witness)?))
+    }
+
     /// Verifies a proof against a commitment
     fn verify<M: MSMEngine<E = E>>(
         &self,
@@ -62,6 +344,83 @@ pub trait PolyMultiProof<E: Pairing>: Sized {
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Like `verify`, but first checks that this setup is from the ceremony `expected_ceremony`
+    fn verify_in_ceremony(
+        &self,
+        expected_ceremony: &CeremonyId,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error>
+    where
+        Self: Ceremony<E>,
+    {
+        self.check_ceremony(expected_ceremony)?;
+        self.verify(transcript, commits, point_set_index, evals, proof)
+    }
+
+    /// Like `open`, but first absorbs `phase_label` into the transcript, so the proof only
+    /// verifies with [`Self::verify_in_phase`] under the same label
+    fn open_in_phase(
+        &self,
+        phase_label: &[u8],
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        point_set_index: usize,
+    ) -> Result<Self::Proof, Error> {
+        crate::transcript::append_message(transcript, b"open phase", phase_label);
+        self.open(transcript, evals, polys, point_set_index)
+    }
+
+    /// Like `verify`, but first absorbs `phase_label` into the transcript as
+    /// [`Self::open_in_phase`] does
+    fn verify_in_phase(
+        &self,
+        phase_label: &[u8],
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        crate::transcript::append_message(transcript, b"open phase", phase_label);
+        self.verify(transcript, commits, point_set_index, evals, proof)
+    }
+}
+
+/// A scheme with precomputation whose verification is a single pairing equation, so many proofs
+/// can be checked at once with a [`BatchVerifier`](crate::batch::BatchVerifier)
+pub trait PairingEquationProof<E: Pairing>: PolyMultiProof<E> {
+    /// Does everything `verify` does except the final pairing check, returning the equation
+    /// `verify` would check
+    fn verify_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<PairingEquation<E>, Error>;
+
+    /// Like `verify`, but returns the Miller loop output of the equation instead of checking it,
+    /// see [`PairingEquation::miller_output`]. The proof is valid exactly when the final
+    /// exponentiation of the output is one.
+    fn verify_miller_output(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        point_set_index: usize,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<E::TargetField, Error> {
+        Ok(self
+            .verify_equation(transcript, commits, point_set_index, evals, proof)?
+            .miller_output())
+    }
 }
 
 /// A curve-agnostic trait for a BDFG commitment scheme *without precomputation*
@@ -69,7 +428,9 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
     /// The output proof type
     type Proof: Clone;
 
-    /// Creates a proof of the given polynomials and evals at the given points
+    /// Creates a proof of the given polynomials and evals at the given points. With no points,
+    /// and so no evals for each polynomial, the proof is the identity and the transcript is
+    /// left as-is.
     fn open(
         &self,
         transcript: &mut Transcript,
@@ -78,7 +439,8 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
         points: &[E::ScalarField],
     ) -> Result<Self::Proof, Error>;
 
-    /// Verifies a proof against the given set of commitments and points
+    /// Verifies a proof against the given set of commitments and points. With no points this
+    /// holds exactly when the proof is the identity, as made by `open`.
     fn verify(
         &self,
         transcript: &mut Transcript,
@@ -87,6 +449,748 @@ pub trait PolyMultiProofNoPrecomp<E: Pairing>: Sized {
         evals: &[impl AsRef<[E::ScalarField]>],
         proof: &Self::Proof,
     ) -> Result<bool, Error>;
+
+    /// Opens like `open`, then absorbs the proof into the transcript. Calling this repeatedly on
+    /// the same transcript links each round's proof to every round before it. Verify the rounds
+    /// in the same order with [`Self::verify_linked`].
+    fn open_linked(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error>
+    where
+        Self::Proof: CanonicalSerialize,
+    {
+        let proof = self.open(transcript, evals, polys, points)?;
+        transcribe_generic(transcript, b"linked proof", &proof)?;
+        Ok(proof)
+    }
+
+    /// Verifies a proof made with [`Self::open_linked`], then absorbs it into the transcript the
+    /// same way the prover did
+    fn verify_linked(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error>
+    where
+        Self::Proof: CanonicalSerialize,
+    {
+        let res = self.verify(transcript, commits, points, evals, proof)?;
+        transcribe_generic(transcript, b"linked proof", proof)?;
+        Ok(res)
+    }
+
+    /// Like `open`, but first absorbs the commitments to `polys` into the transcript, binding
+    /// the proof to them. Verify with [`Self::verify_binding`], passing the commitments in the
+    /// same order.
+    fn open_binding(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        if commits.len() != polys.len() {
+            return Err(Error::InvalidInputLength {
+                expected: polys.len(),
+                got: commits.len(),
+            });
+        }
+        transcribe_generic(transcript, b"binding commits", &commits.to_vec())?;
+        self.open(transcript, evals, polys, points)
+    }
+
+    /// Verifies a proof made with [`Self::open_binding`], absorbing the commitments first the
+    /// same way the prover did
+    fn verify_binding(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        transcribe_generic(transcript, b"binding commits", &commits.to_vec())?;
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Like `open`, but opens `coeff_transform` applied to each polynomial, matching commitments
+    /// from [`Committer::commit_with_transform`]. `evals` are the transformed polynomials'
+    /// evaluations, and the proof is checked with `verify` as usual.
+    fn open_with_transform(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        coeff_transform: impl Fn(&[E::ScalarField]) -> Vec<E::ScalarField>,
+    ) -> Result<Self::Proof, Error> {
+        let polys = polys
+            .iter()
+            .map(|p| coeff_transform(p.as_ref()))
+            .collect::<Vec<_>>();
+        self.open(transcript, evals, &polys, points)
+    }
+
+    /// Like `open`, but with the evals point-major: `evals_by_point[j][i]` is polynomial `i` at
+    /// `points[j]`. The proof is the same as `open` with the evals transposed. The schemes in this
+    /// crate absorb the evals without transposing them, the default implementation transposes.
+    fn open_point_major(
+        &self,
+        transcript: &mut Transcript,
+        evals_by_point: &[Vec<E::ScalarField>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        PointMajor::new(evals_by_point, polys.len())?;
+        let evals = (0..polys.len())
+            .map(|i| evals_by_point.iter().map(|row| row[i]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        self.open(transcript, &evals, polys, points)
+    }
+
+    /// Like `open`, but first absorbs `phase_label` into the transcript, so the proof only
+    /// verifies with [`Self::verify_in_phase`] under the same label
+    fn open_in_phase(
+        &self,
+        phase_label: &[u8],
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        crate::transcript::append_message(transcript, b"open phase", phase_label);
+        self.open(transcript, evals, polys, points)
+    }
+
+    /// Like `verify`, but first absorbs `phase_label` into the transcript as
+    /// [`Self::open_in_phase`] does
+    fn verify_in_phase(
+        &self,
+        phase_label: &[u8],
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        crate::transcript::append_message(transcript, b"open phase", phase_label);
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Like `open`, but any parallel work runs on `pool` rather than rayon's global pool
+    #[cfg(feature = "parallel")]
+    fn open_in_pool(
+        &self,
+        pool: &rayon::ThreadPool,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]> + Sync],
+        polys: &[impl AsRef<[E::ScalarField]> + Sync],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error>
+    where
+        Self: Sync,
+        Self::Proof: Send,
+    {
+        pool.install(|| self.open(transcript, evals, polys, points))
+    }
+
+    /// Like `open`, but the points are given as indices into `domain`, opening at
+    /// `domain.element(i)` for each index `i`
+    fn open_by_indices(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        domain: &impl EvaluationDomain<E::ScalarField>,
+        indices: &[usize],
+    ) -> Result<Self::Proof, Error> {
+        let points = domain_points(domain, indices)?;
+        self.open(transcript, evals, polys, &points)
+    }
+
+    /// Verifies a proof made with [`Self::open_by_indices`], or equivalently an opening at the
+    /// domain elements the indices map to
+    fn verify_by_indices(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        domain: &impl EvaluationDomain<E::ScalarField>,
+        indices: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let points = domain_points(domain, indices)?;
+        self.verify(transcript, commits, &points, evals, proof)
+    }
+
+    /// Like [`Self::open_by_indices`], but looks the points up in a prebuilt domain
+    fn open_on_domain(
+        &self,
+        transcript: &mut Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        domain: &KzgDomain<E::ScalarField>,
+        indices: &[usize],
+    ) -> Result<Self::Proof, Error> {
+        let points = domain.points(indices)?;
+        self.open(transcript, evals, polys, &points)
+    }
+
+    /// Verifies a proof made with [`Self::open_on_domain`] or [`Self::open_by_indices`]
+    fn verify_on_domain(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        domain: &KzgDomain<E::ScalarField>,
+        indices: &[usize],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let points = domain.points(indices)?;
+        self.verify(transcript, commits, &points, evals, proof)
+    }
+
+    /// Splits an opening into `n_shards` proofs, each for a disjoint range of the polynomials
+    /// given by [`crate::shard_ranges`]. Each shard is opened with its own transcript forked from
+    /// `transcript`, so shards can be verified independently with [`Self::verify_shard`], and
+    /// all of them verifying is the same statement as the full opening verifying.
+    fn open_sharded(
+        &self,
+        transcript: &Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+        n_shards: usize,
+    ) -> Result<Vec<Self::Proof>, Error> {
+        check_opening_sizes(evals, polys, points.len())?;
+        shard_ranges(polys.len(), n_shards)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| {
+                self.open(
+                    &mut shard_transcript(transcript, i, n_shards),
+                    &evals[range.clone()],
+                    &polys[range],
+                    points,
+                )
+            })
+            .collect()
+    }
+
+    /// Verifies shard `shard` of `n_shards` made with [`Self::open_sharded`], given the
+    /// commitments and evals of the polynomials in that shard's range. `transcript` is the one
+    /// the shards were opened from, and is left as-is.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_shard(
+        &self,
+        transcript: &Transcript,
+        shard: usize,
+        n_shards: usize,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        if shard >= n_shards {
+            return Err(Error::InvalidShardIndex { shard, n_shards });
+        }
+        self.verify(
+            &mut shard_transcript(transcript, shard, n_shards),
+            commits,
+            points,
+            evals,
+            proof,
+        )
+    }
+
+    /// Opens each polynomial with its own proof, which is [`Self::open_sharded`] with one shard
+    /// per polynomial. Verify with [`Self::verify_unaggregated`], which reports which
+    /// polynomials' proofs fail.
+    fn open_unaggregated(
+        &self,
+        transcript: &Transcript,
+        evals: &[impl AsRef<[E::ScalarField]>],
+        polys: &[impl AsRef<[E::ScalarField]>],
+        points: &[E::ScalarField],
+    ) -> Result<Vec<Self::Proof>, Error> {
+        self.open_sharded(transcript, evals, polys, points, polys.len())
+    }
+
+    /// Verifies proofs made with [`Self::open_unaggregated`], returning the indices of the
+    /// polynomials whose proofs fail, so an empty result means they all verified. `transcript`
+    /// is the one the proofs were opened from, and is left as-is.
+    fn verify_unaggregated(
+        &self,
+        transcript: &Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proofs: &[Self::Proof],
+    ) -> Result<Vec<usize>, Error> {
+        check_verify_sizes(commits, evals, points.len())?;
+        if proofs.len() != commits.len() {
+            return Err(Error::InvalidInputLength {
+                expected: commits.len(),
+                got: proofs.len(),
+            });
+        }
+        let n_polys = commits.len();
+        let mut failing = Vec::new();
+        for (i, range) in shard_ranges(n_polys, n_polys)?.into_iter().enumerate() {
+            let valid = self.verify_shard(
+                transcript,
+                i,
+                n_polys,
+                &commits[range.clone()],
+                points,
+                &evals[range],
+                &proofs[i],
+            )?;
+            if !valid {
+                failing.push(i);
+            }
+        }
+        Ok(failing)
+    }
+
+    /// Opens every row polynomial of a matrix, as committed to by [`Committer::commit_matrix`],
+    /// at the single column point `column_point` in one proof. Returns each row's evaluation
+    /// and the proof.
+    fn open_matrix_column(
+        &self,
+        transcript: &mut Transcript,
+        rows: &[Vec<E::ScalarField>],
+        column_point: E::ScalarField,
+    ) -> Result<(Vec<E::ScalarField>, Self::Proof), Error> {
+        let evals = rows
+            .iter()
+            .map(|r| DensePolynomial::from_coefficients_slice(r).evaluate(&column_point))
+            .collect::<Vec<_>>();
+        let eval_rows = evals.iter().map(core::slice::from_ref).collect::<Vec<_>>();
+        let proof = self.open(transcript, &eval_rows, rows, &[column_point])?;
+        Ok((evals, proof))
+    }
+
+    /// Verifies a proof made with [`Self::open_matrix_column`] against the row commitments
+    fn verify_matrix_column(
+        &self,
+        transcript: &mut Transcript,
+        row_commits: &[Commitment<E>],
+        column_point: E::ScalarField,
+        evals: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let eval_rows = evals.iter().map(core::slice::from_ref).collect::<Vec<_>>();
+        self.verify(transcript, row_commits, &[column_point], &eval_rows, proof)
+    }
+
+    /// Runs the checks of `verify` that don't need the proof, such as the number of evals per
+    /// commitment and point, without any curve arithmetic. Finish with `finish_verify`.
+    fn prepare_verify<'a>(
+        &self,
+        commits: &'a [Commitment<E>],
+        points: &'a [E::ScalarField],
+        evals: &'a [impl AsRef<[E::ScalarField]>],
+    ) -> Result<PreparedVerify<'a, E>, Error> {
+        PreparedVerify::new(commits, points, evals)
+    }
+
+    /// Verifies `proof` against inputs checked by `prepare_verify`. The result is the same as
+    /// calling `verify` with them. The schemes skip the checks `prepare_verify` already did, and
+    /// error with [`Error::PreparedByOtherScheme`] on inputs another scheme prepared, while this
+    /// default just calls `verify`.
+    fn finish_verify(
+        &self,
+        transcript: &mut Transcript,
+        prepared: PreparedVerify<E>,
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        self.verify(
+            transcript,
+            prepared.commits,
+            prepared.points,
+            &prepared.evals,
+            proof,
+        )
+    }
+
+    /// Like `verify`, but first checks that the Merkle root over `evals`, as computed by
+    /// [`eval_root`](crate::merkle::eval_root) with `hasher`, is `expected_root`
+    #[allow(clippy::too_many_arguments)]
+    fn verify_with_eval_root(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+        expected_root: [u8; 32],
+        hasher: &impl MerkleHasher,
+    ) -> Result<bool, Error> {
+        if crate::merkle::eval_root(hasher, evals)? != expected_root {
+            return Err(Error::EvalRootMismatch);
+        }
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Verifies a proof against `point_count` commitments given as a concatenation of compressed
+    /// G1 points. Errors if the blob isn't a whole number of points, or holds a different number
+    /// of them. The points are deserialized into one buffer of `point_count` commitments, since
+    /// the verifier's msm needs all of its bases together.
+    fn verify_from_commit_bytes(
+        &self,
+        transcript: &mut Transcript,
+        commit_bytes: &[u8],
+        point_count: usize,
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let point_size = E::G1Affine::zero().compressed_size();
+        if commit_bytes.len() % point_size != 0 {
+            return Err(Error::InvalidCommitBytesLength {
+                n_bytes: commit_bytes.len(),
+                point_size,
+            });
+        }
+        if commit_bytes.len() / point_size != point_count {
+            return Err(Error::InvalidInputLength {
+                expected: point_count,
+                got: commit_bytes.len() / point_size,
+            });
+        }
+        let mut commits = Vec::with_capacity(point_count);
+        for b in commit_bytes.chunks_exact(point_size) {
+            commits.push(Commitment(E::G1Affine::deserialize_compressed(b)?));
+        }
+        self.verify(transcript, &commits, points, evals, proof)
+    }
+
+    /// Verifies a proof against commitments given as separate compressed G1 points. Each point
+    /// is checked to be in the subgroup, and bytes that aren't exactly one valid point give
+    /// [`Error::SerializationError`].
+    fn verify_raw(
+        &self,
+        transcript: &mut Transcript,
+        commit_g1_bytes: &[&[u8]],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let commits = commit_g1_bytes
+            .iter()
+            .map(|b| {
+                let mut reader = *b;
+                let point = E::G1Affine::deserialize_compressed(&mut reader)?;
+                if !reader.is_empty() {
+                    return Err(Error::SerializationError);
+                }
+                Ok(Commitment(point))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.verify(transcript, &commits, points, evals, proof)
+    }
+
+    /// Like `verify`, but first checks that this setup is from the ceremony `expected_ceremony`
+    fn verify_in_ceremony(
+        &self,
+        expected_ceremony: &CeremonyId,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error>
+    where
+        Self: Ceremony<E>,
+    {
+        self.check_ceremony(expected_ceremony)?;
+        self.verify(transcript, commits, points, evals, proof)
+    }
+
+    /// Opens polynomials given in evaluation form, each over its own domain, at the common
+    /// `points`. Each polynomial is interpolated over its domain, so the domains may differ in
+    /// size. Returns the evaluations at `points`, which `verify` takes along with commitments to
+    /// the interpolated polynomials, and the proof.
+    #[allow(clippy::type_complexity)]
+    fn open_mixed_domain(
+        &self,
+        transcript: &mut Transcript,
+        evals_and_domains: &[(Vec<E::ScalarField>, GeneralEvaluationDomain<E::ScalarField>)],
+        points: &[E::ScalarField],
+    ) -> Result<(Vec<Vec<E::ScalarField>>, Self::Proof), Error> {
+        let polys = evals_and_domains
+            .iter()
+            .map(|(domain_evals, domain)| {
+                if domain_evals.len() != domain.size() {
+                    return Err(Error::InvalidInputLength {
+                        expected: domain.size(),
+                        got: domain_evals.len(),
+                    });
+                }
+                Ok(DensePolynomial::from_coefficients_vec(
+                    domain.ifft(domain_evals),
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys
+            .iter()
+            .map(|p| p.coeffs.as_slice())
+            .collect::<Vec<_>>();
+        let proof = self.open(transcript, &evals, &coeffs, points)?;
+        Ok((evals, proof))
+    }
+
+    /// Interpolates `data` over `data_domain` to get the low-degree polynomial it is the
+    /// Reed-Solomon encoding of, commits to it, and opens it at `sample_points`.
+    /// Returns the commitment, the evaluations at `sample_points`, and the proof.
+    #[allow(clippy::type_complexity)]
+    fn commit_and_open_rs(
+        &self,
+        transcript: &mut Transcript,
+        data: &[E::ScalarField],
+        data_domain: &impl EvaluationDomain<E::ScalarField>,
+        sample_points: &[E::ScalarField],
+    ) -> Result<(Commitment<E>, Vec<E::ScalarField>, Self::Proof), Error>
+    where
+        Self: Committer<E>,
+    {
+        if data.len() != data_domain.size() {
+            return Err(Error::InvalidInputLength {
+                expected: data_domain.size(),
+                got: data.len(),
+            });
+        }
+        let poly = DensePolynomial::from_coefficients_vec(data_domain.ifft(data));
+        let commit = self.commit(&poly.coeffs)?;
+        let evals = sample_points
+            .iter()
+            .map(|p| poly.evaluate(p))
+            .collect::<Vec<_>>();
+        let proof = self.open(
+            transcript,
+            &[evals.as_slice()],
+            &[poly.coeffs.as_slice()],
+            sample_points,
+        )?;
+        Ok((commit, evals, proof))
+    }
+
+    /// Proves that the polynomials `p` and `q` agree on every point in `points` by opening
+    /// $p - q$ to zero at those points. If they don't agree the resulting proof won't verify, or
+    /// with the `debug-checks` feature this errors.
+    fn prove_agree_on_set(
+        &self,
+        transcript: &mut Transcript,
+        p: &[E::ScalarField],
+        q: &[E::ScalarField],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let diff = &DensePolynomial::from_coefficients_slice(p)
+            - &DensePolynomial::from_coefficients_slice(q);
+        let zeros = vec![E::ScalarField::zero(); points.len()];
+        self.open(transcript, &[zeros], &[diff.coeffs], points)
+    }
+
+    /// Verifies a proof made with [`Self::prove_agree_on_set`] that the polynomials committed to
+    /// by `commit_p` and `commit_q` agree on every point in `points`
+    fn verify_agree_on_set(
+        &self,
+        transcript: &mut Transcript,
+        commit_p: &Commitment<E>,
+        commit_q: &Commitment<E>,
+        points: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let diff = Commitment((commit_p.0.into_group() - commit_q.0).into_affine());
+        let zeros = vec![E::ScalarField::zero(); points.len()];
+        self.verify(transcript, &[diff], points, &[zeros], proof)
+    }
+
+    /// Proves that the polynomial with coefficients `coeffs` vanishes on every point in `points`
+    /// by opening it to zero at those points. Errors with [`Error::PolynomialDoesNotVanish`] if
+    /// it doesn't.
+    fn prove_vanishes_on(
+        &self,
+        transcript: &mut Transcript,
+        coeffs: &[E::ScalarField],
+        points: &[E::ScalarField],
+    ) -> Result<Self::Proof, Error> {
+        let poly = DensePolynomial::from_coefficients_slice(coeffs);
+        if let Some(point_index) = points.iter().position(|x| !poly.evaluate(x).is_zero()) {
+            return Err(Error::PolynomialDoesNotVanish { point_index });
+        }
+        let zeros = vec![E::ScalarField::zero(); points.len()];
+        self.open(transcript, &[zeros], &[coeffs], points)
+    }
+
+    /// Verifies a proof made with [`Self::prove_vanishes_on`] that the polynomial committed to by
+    /// `commit` vanishes on every point in `points`
+    fn verify_vanishes_on(
+        &self,
+        transcript: &mut Transcript,
+        commit: &Commitment<E>,
+        points: &[E::ScalarField],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let zeros = vec![E::ScalarField::zero(); points.len()];
+        self.verify(transcript, &[*commit], points, &[zeros], proof)
+    }
+}
+
+/// The settings and variants of `verify` shared by the schemes without precomputation. Each
+/// scheme gives the pairing equation its `verify` checks, and the variants are built on that.
+pub trait ConfigurableScheme<E: Pairing>: PolyMultiProofNoPrecomp<E> {
+    /// The scheme's settings
+    fn config(&self) -> &SchemeConfig;
+
+    /// The scheme's settings, for the builders below to change
+    fn config_mut(&mut self) -> &mut SchemeConfig;
+
+    /// Checks the inputs as `verify` does, then computes the pairing equation it checks and the
+    /// gamma challenge drawn from the transcript. Folds with `gamma_powers` instead of generating
+    /// them when given, see [`Self::verify_with_powers`].
+    fn equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+        gamma_powers: Option<&[E::ScalarField]>,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error>;
+
+    /// Like [`Self::equation`], for commitments given as linear combinations of base commitments
+    fn lazy_equation(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[LazyCommitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(PairingEquation<E>, E::ScalarField), Error>;
+
+    /// Checks an equation from [`Self::equation`] the way `verify` does
+    fn check_equation(&self, eq: &PairingEquation<E>) -> bool;
+
+    /// Sets the caps on the number of polynomials and points this scheme will open or verify
+    fn with_limits(mut self, limits: SizeLimits) -> Self {
+        self.config_mut().limits = limits;
+        self
+    }
+
+    /// Sets whether verifying rejects commitments to the point at infinity. These commit to the
+    /// zero polynomial, which a verifier may not expect to see.
+    fn with_reject_identity_commitments(mut self, reject: bool) -> Self {
+        self.config_mut().reject_identity_commitments = reject;
+        self
+    }
+
+    /// Sets how group elements are serialized when absorbed into the transcript, for matching a
+    /// verifier that absorbs them uncompressed
+    fn with_transcript_compress(mut self, compress: Compress) -> Self {
+        self.config_mut().transcript_compress = compress;
+        self
+    }
+
+    /// Caps the gamma challenge that folds the polynomials at `bits` bits, so folding multiplies
+    /// by smaller scalars. This is for experimenting with small fields, where a full size
+    /// challenge buys little. It costs soundness: a wrong opening of $n$ polynomials passes with
+    /// probability about $n / 2^{bits}$ instead of $n / |F|$, so e.g. 128 bits keeps it
+    /// negligible. The prover and verifier must agree on this. Caps below
+    /// [`SchemeConfig::MIN_CHALLENGE_BITS`] error with [`Error::ChallengeBitsTooSmall`], and caps
+    /// at or above the field size do nothing.
+    fn with_challenge_bits(mut self, bits: usize) -> Result<Self, Error> {
+        if bits < SchemeConfig::MIN_CHALLENGE_BITS {
+            return Err(Error::ChallengeBitsTooSmall {
+                bits,
+                min: SchemeConfig::MIN_CHALLENGE_BITS,
+            });
+        }
+        self.config_mut().challenge_bits = Some(bits);
+        Ok(self)
+    }
+
+    /// Verifies a proof against commitments given as linear combinations of base commitments.
+    /// This is the same as materializing each commitment and calling `verify`, but the
+    /// combinations are folded into the verifier's single msm.
+    fn verify_lazy(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[LazyCommitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<bool, Error> {
+        let (eq, _) = self.lazy_equation(transcript, commits, points, evals, proof)?;
+        Ok(self.check_equation(&eq))
+    }
+
+    /// Like `verify`, but also returns the gamma challenge drawn from the transcript, which is
+    /// the same one the prover drew if the transcripts agree. With no points nothing is drawn and
+    /// the challenge is zero.
+    fn verify_with_challenge_out(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<(bool, E::ScalarField), Error> {
+        let (eq, gamma) = self.equation(transcript, commits, points, evals, proof, None)?;
+        Ok((self.check_equation(&eq), gamma))
+    }
+
+    /// Like `verify`, but folds with the given powers $1, \gamma, \gamma^2, \ldots$ of the gamma
+    /// challenge instead of generating them, which saves regenerating the same powers when
+    /// verifying many proofs that draw the same challenge. At least one power is needed per
+    /// commitment. The powers are trusted to be those of the challenge drawn from the transcript,
+    /// which is only checked with the `debug-checks` feature, and wrong powers fail to verify.
+    fn verify_with_powers(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+        gamma_powers: &[E::ScalarField],
+    ) -> Result<bool, Error> {
+        let (eq, _) = self.equation(
+            transcript,
+            commits,
+            points,
+            evals,
+            proof,
+            Some(gamma_powers),
+        )?;
+        Ok(self.check_equation(&eq))
+    }
+
+    /// Like `verify`, but returns the Miller loop output of the pairing equation instead of
+    /// checking it, see [`PairingEquation::miller_output`]
+    fn verify_miller_output(
+        &self,
+        transcript: &mut Transcript,
+        commits: &[Commitment<E>],
+        points: &[E::ScalarField],
+        evals: &[impl AsRef<[E::ScalarField]>],
+        proof: &Self::Proof,
+    ) -> Result<E::TargetField, Error> {
+        let (eq, _) = self.equation(transcript, commits, points, evals, proof, None)?;
+        Ok(eq.miller_output())
+    }
 }
 
 /// A curve-agnostic trait for fast multi-scalar multiplication
@@ -131,6 +1235,49 @@ pub trait MSMEngine: Clone + Copy {
     ) -> PairingOutput<Self::E>;
 }
 
+/// The current format version of serialized proofs, see [`VersionedProof`]
+pub const PROOF_FORMAT_VERSION: u8 = 1;
+
+/// Serialization of proofs prefixed with a format version byte, so stored proofs from another
+/// version of the crate are rejected rather than misread
+pub trait VersionedProof: CanonicalSerialize + CanonicalDeserialize {
+    /// The format version this proof type is written and read with
+    const VERSION: u8 = PROOF_FORMAT_VERSION;
+
+    /// Writes the version byte followed by the compressed proof
+    fn serialize_versioned(&self) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::with_capacity(1 + self.compressed_size());
+        out.push(Self::VERSION);
+        self.serialize_compressed(&mut out)?;
+        Ok(out)
+    }
+
+    /// Reads a proof written with [`Self::serialize_versioned`]. Errors with
+    /// [`Error::UnsupportedProofVersion`] if it was written with a different version, and with
+    /// [`Error::SerializationError`] if any bytes are left after the proof.
+    fn deserialize_versioned(bytes: &[u8]) -> Result<Self, Error> {
+        let (&found, mut reader) = bytes.split_first().ok_or(Error::SerializationError)?;
+        if found != Self::VERSION {
+            return Err(Error::UnsupportedProofVersion {
+                found,
+                supported: Self::VERSION,
+            });
+        }
+        let proof = Self::deserialize_compressed(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(Error::SerializationError);
+        }
+        Ok(proof)
+    }
+}
+
+impl<E: Pairing> VersionedProof for crate::method1::Proof<E> {}
+impl<E: Pairing> VersionedProof for crate::method1::DegreeCapProof<E> {}
+impl<E: Pairing> VersionedProof for crate::method2::Proof<E> {}
+impl<E: Pairing> VersionedProof for SinglePointOpen<E> {}
+impl VersionedProof for ZeroProof {}
+impl<E: Pairing> VersionedProof for PoK<E> {}
+
 /// Utility trait for serialization and deserialization
 pub trait AsBytes<const N: usize>: Sized {
     /// Convert to bytes
@@ -0,0 +1,267 @@
+//! Wrappers for the merlin transcript calls made while proving and verifying. With the
+//! `transcript-debug` feature, every message absorbed and challenge squeezed can be recorded with
+//! [`record`], which is useful for finding where a prover and verifier diverge. With the
+//! `insecure-deterministic-challenges` feature, `with_counter_challenges` swaps the challenges
+//! for a counter.
+use crate::{get_challenge, get_field_size};
+use ark_ff::PrimeField;
+use ark_std::{collections::BTreeSet, vec::Vec};
+use merlin::Transcript;
+
+#[cfg(feature = "transcript-debug")]
+pub use debug::{record, TranscriptLog};
+
+#[cfg(all(
+    any(test, feature = "insecure-deterministic-challenges"),
+    feature = "std"
+))]
+pub use counter::with_counter_challenges;
+
+pub(crate) fn append_message(transcript: &mut Transcript, label: &'static [u8], message: &[u8]) {
+    #[cfg(feature = "transcript-debug")]
+    debug::push(label, message);
+    transcript.append_message(label, message);
+}
+
+/// Binds the proof to some external context, such as a block header hash, by absorbing it
+/// into the transcript. Call this before `open`, and make the same call on the verifier's
+/// transcript before `verify`, otherwise the challenges won't match and the proof won't verify.
+pub fn bind_context(transcript: &mut Transcript, label: &'static [u8], context: &[u8]) {
+    append_message(transcript, label, context);
+}
+
+/// Derives `k` distinct nonzero sample points from `seed`, e.g. a block hash, by squeezing them
+/// from a transcript that has absorbed the seed. A point that was already drawn is squeezed
+/// again. Samplers and provers using the same seed get the same points in the same order.
+pub fn derive_sample_points<F: PrimeField>(seed: &[u8], k: usize) -> Vec<F> {
+    let mut transcript = Transcript::new(b"sample points");
+    append_message(&mut transcript, b"sample seed", seed);
+    let field_size_bytes = get_field_size::<F>();
+    let mut seen = BTreeSet::new();
+    let mut points = Vec::with_capacity(k);
+    while points.len() < k {
+        let point = get_challenge::<F>(&mut transcript, b"sample point", field_size_bytes);
+        if seen.insert(point) {
+            points.push(point);
+        }
+    }
+    points
+}
+
+pub(crate) fn append_u64(transcript: &mut Transcript, label: &'static [u8], x: u64) {
+    #[cfg(feature = "transcript-debug")]
+    debug::push(label, &x.to_le_bytes());
+    transcript.append_u64(label, x);
+}
+
+pub(crate) fn challenge_bytes(transcript: &mut Transcript, label: &'static [u8], dest: &mut [u8]) {
+    transcript.challenge_bytes(label, dest);
+    #[cfg(feature = "transcript-debug")]
+    debug::push(label, dest);
+}
+
+#[cfg(feature = "transcript-debug")]
+mod debug {
+    use std::{cell::RefCell, vec::Vec};
+
+    /// The `(label, bytes)` pairs absorbed or squeezed, in order
+    pub type TranscriptLog = Vec<(Vec<u8>, Vec<u8>)>;
+
+    std::thread_local! {
+        static LOG: RefCell<Option<TranscriptLog>> = const { RefCell::new(None) };
+    }
+
+    pub(super) fn push(label: &[u8], bytes: &[u8]) {
+        LOG.with(|log| {
+            if let Some(log) = log.borrow_mut().as_mut() {
+                log.push((label.to_vec(), bytes.to_vec()));
+            }
+        });
+    }
+
+    /// Runs `f`, recording every transcript call this crate makes on the current thread while it
+    /// runs. Challenges are recorded with the bytes that were squeezed.
+    pub fn record<R>(f: impl FnOnce() -> R) -> (R, TranscriptLog) {
+        let outer = LOG.with(|log| log.replace(Some(Vec::new())));
+        let res = f();
+        let recorded = LOG.with(|log| log.replace(outer)).unwrap_or_default();
+        (res, recorded)
+    }
+}
+
+#[cfg(all(
+    any(test, feature = "insecure-deterministic-challenges"),
+    feature = "std"
+))]
+pub(crate) mod counter {
+    use core::cell::Cell;
+
+    std::thread_local! {
+        static NEXT: Cell<Option<u64>> = const { Cell::new(None) };
+    }
+
+    /// The next counter challenge, if counter challenges are on for the current thread
+    pub(crate) fn next() -> Option<u64> {
+        NEXT.with(|next| {
+            let c = next.get()?;
+            next.set(Some(c + 1));
+            Some(c)
+        })
+    }
+
+    /// Runs `f` with every challenge this crate draws on the current thread replaced by the
+    /// counter `1, 2, 3, ...`. This makes failing tests easy to reproduce, but anyone can forge
+    /// proofs against predictable challenges, so it's behind the
+    /// `insecure-deterministic-challenges` feature, which must never reach a real build. Run the
+    /// prover and the verifier in separate calls so that both count from one.
+    pub fn with_counter_challenges<R>(f: impl FnOnce() -> R) -> R {
+        let outer = NEXT.with(|next| next.replace(Some(1)));
+        let res = f();
+        NEXT.with(|next| next.set(outer));
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "transcript-debug")]
+    use super::record;
+    use super::{bind_context, with_counter_challenges};
+    #[cfg(feature = "transcript-debug")]
+    use crate::testing::random_valid_opening;
+    use crate::traits::{Committer, ConfigurableScheme, PolyMultiProofNoPrecomp};
+    use crate::{method2::M2NoPrecomp, test_rng};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::UniformRand;
+    use merlin::Transcript;
+
+    #[cfg(feature = "transcript-debug")]
+    #[test]
+    fn test_open_and_verify_record_the_same() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let ((commits, points, evals, open), open_log) =
+            record(|| random_valid_opening(&s, 4, 3, 30, &mut test_rng()));
+        let (res, verify_log) = record(|| {
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+        });
+        assert_eq!(Ok(true), res);
+        assert!(!open_log.is_empty());
+        assert_eq!(open_log, verify_log);
+    }
+
+    #[test]
+    fn test_counter_challenges() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+
+        let open = with_counter_challenges(|| {
+            s.open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+                .unwrap()
+        });
+        // The verifier counts from one too, so it draws the same challenges, gamma first
+        assert_eq!(
+            Ok((true, Fr::from(1u64))),
+            with_counter_challenges(|| s.verify_with_challenge_out(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            ))
+        );
+        assert_eq!(
+            Ok(false),
+            s.verify(
+                &mut Transcript::new(b"testing"),
+                &commits,
+                &points,
+                &evals,
+                &open
+            )
+        );
+    }
+
+    #[test]
+    fn test_bind_context() {
+        let s = M2NoPrecomp::<Bls12_381>::new(64, &mut test_rng());
+        let points = (0..4)
+            .map(|_| Fr::rand(&mut test_rng()))
+            .collect::<Vec<_>>();
+        let polys = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(30, &mut test_rng()))
+            .collect::<Vec<_>>();
+        let evals = polys
+            .iter()
+            .map(|p| points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+        let commits = coeffs
+            .iter()
+            .map(|p| s.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let transcript_with = |context: Option<&[u8]>| {
+            let mut transcript = Transcript::new(b"testing");
+            if let Some(context) = context {
+                bind_context(&mut transcript, b"block header", context);
+            }
+            transcript
+        };
+
+        let open = s
+            .open(
+                &mut transcript_with(Some(b"header 1")),
+                &evals,
+                &coeffs,
+                &points,
+            )
+            .unwrap();
+        let verify = |context| {
+            s.verify(
+                &mut transcript_with(context),
+                &commits,
+                &points,
+                &evals,
+                &open,
+            )
+        };
+        assert_eq!(Ok(true), verify(Some(b"header 1")));
+        assert_eq!(Ok(false), verify(Some(b"header 2")));
+        assert_eq!(Ok(false), verify(None));
+    }
+
+    #[test]
+    fn test_derive_sample_points() {
+        use super::derive_sample_points;
+        use ark_std::collections::BTreeSet;
+
+        let points = derive_sample_points::<Fr>(b"block hash", 50);
+        assert_eq!(50, points.len());
+        assert_eq!(50, points.iter().collect::<BTreeSet<_>>().len());
+        assert_eq!(points, derive_sample_points::<Fr>(b"block hash", 50));
+        // Asking for fewer gives a prefix
+        assert_eq!(&points[..10], derive_sample_points::<Fr>(b"block hash", 10));
+        assert_ne!(points, derive_sample_points::<Fr>(b"other hash", 50));
+        assert!(derive_sample_points::<Fr>(b"block hash", 0).is_empty());
+    }
+}
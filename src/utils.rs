@@ -1,4 +1,5 @@
 //! Crate-wide utility functions.
+use ark_ff::FftField;
 
 /// Finds the smallest power of 2 greater than or equal to `a`.
 pub fn smallest_power_of_2_greater_than(a: usize) -> usize {
@@ -13,3 +14,71 @@ pub fn smallest_power_of_2_greater_than(a: usize) -> usize {
 
     power
 }
+
+/// The degree of the witness (quotient) polynomial when opening a polynomial of degree
+/// `poly_degree` at `n_points` points, i.e. when dividing it by the degree `n_points` vanishing
+/// polynomial of the points. The quotient is zero when there are more points than the degree, and
+/// its degree is reported as 0.
+pub fn witness_degree(poly_degree: usize, n_points: usize) -> usize {
+    poly_degree.saturating_sub(n_points)
+}
+
+/// The largest radix-2 FFT domain over `F`, `2^two_adicity`. Saturates at the largest power of
+/// two that fits in a `usize`.
+pub fn max_domain_size<F: FftField>() -> usize {
+    1usize
+        .checked_shl(F::TWO_ADICITY)
+        .unwrap_or(1 << (usize::BITS - 1))
+}
+
+/// Whether `n` is the size of a radix-2 FFT domain over `F`
+pub fn is_valid_domain_size<F: FftField>(n: usize) -> bool {
+    n.is_power_of_two() && n <= max_domain_size::<F>()
+}
+
+/// Rounds `n` up to the size of the smallest radix-2 FFT domain over `F` that fits it, or `None`
+/// if `n` is bigger than [`max_domain_size`]
+pub fn round_up_domain_size<F: FftField>(n: usize) -> Option<usize> {
+    n.checked_next_power_of_two()
+        .filter(|&size| size <= max_domain_size::<F>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_witness_degree() {
+        for (d, n, expected) in [
+            (255, 16, 239),
+            (16, 16, 0),
+            (16, 1, 15),
+            (3, 10, 0),
+            (0, 1, 0),
+            (10, 0, 10),
+        ] {
+            assert_eq!(expected, witness_degree(d, n), "d = {d}, n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_domain_sizes() {
+        let max = max_domain_size::<Fr>();
+        // Fr has two-adicity 32, which saturates on targets with a smaller usize
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(1 << 32, max);
+        #[cfg(not(target_pointer_width = "64"))]
+        assert_eq!(1 << (usize::BITS - 1), max);
+        assert!(is_valid_domain_size::<Fr>(max));
+        if let Some(double) = max.checked_mul(2) {
+            assert!(!is_valid_domain_size::<Fr>(double));
+        }
+        assert!(!is_valid_domain_size::<Fr>(max - 1));
+        assert_eq!(Some(max), round_up_domain_size::<Fr>(max));
+        assert_eq!(Some(max), round_up_domain_size::<Fr>(max / 2 + 1));
+        assert_eq!(None, round_up_domain_size::<Fr>(max + 1));
+        assert_eq!(Some(1), round_up_domain_size::<Fr>(0));
+        assert_eq!(Some(64), round_up_domain_size::<Fr>(33));
+    }
+}
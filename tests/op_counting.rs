@@ -0,0 +1,168 @@
+//! The op counters are global, so these run in their own test binary, and each test holds
+//! [`COUNTERS`] while it counts so the tests in here don't reset each other's counts
+#![cfg(feature = "op-counting")]
+
+use ark_bls12_381::{Bls12_381, Fr};
+use poly_multiproof::{
+    ark_ff::UniformRand,
+    ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial},
+    merlin::Transcript,
+    method1::{precompute::M1Precomp, M1NoPrecomp},
+    method2::{precompute::M2Precomp, M2NoPrecomp},
+    msm::{blst::BlstMSMEngine, ArkMSMEngine},
+    op_count::{op_counts, reset_op_counts, OpCounts},
+    traits::{Committer, PolyMultiProof, PolyMultiProofNoPrecomp},
+    Commitment, Error, VerifyCost,
+};
+use rand::thread_rng;
+use std::sync::{Mutex, MutexGuard};
+
+/// Held by each test while it reads the op counters
+static COUNTERS: Mutex<()> = Mutex::new(());
+
+fn lock_counters() -> MutexGuard<'static, ()> {
+    // A failed test poisons the lock, which shouldn't fail the others too
+    COUNTERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn check_verify_counts<P>(s: &P)
+where
+    P: PolyMultiProofNoPrecomp<Bls12_381> + Committer<Bls12_381>,
+{
+    let mut rng = thread_rng();
+    let points = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+    let polys = (0..3)
+        .map(|_| DensePolynomial::<Fr>::rand(30, &mut rng))
+        .collect::<Vec<_>>();
+    let evals: Vec<Vec<_>> = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.iter().map(|p| p.coeffs.clone()).collect::<Vec<_>>();
+    let commits = coeffs
+        .iter()
+        .map(|p| s.commit(p).unwrap())
+        .collect::<Vec<_>>();
+    reset_op_counts();
+    let open = s
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .unwrap();
+    assert_eq!(0, op_counts().pairings);
+
+    reset_op_counts();
+    assert_eq!(OpCounts::default(), op_counts());
+    assert_eq!(
+        Ok(true),
+        s.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open
+        )
+    );
+    let counts = op_counts();
+    assert_eq!(2, counts.pairings);
+    assert!(counts.msms > 0);
+}
+
+#[test]
+fn test_verify_pairing_count() {
+    let _counters = lock_counters();
+    check_verify_counts(&M1NoPrecomp::<Bls12_381, ArkMSMEngine<Bls12_381>>::new(
+        64,
+        8,
+        &mut thread_rng(),
+    ));
+    check_verify_counts(&M2NoPrecomp::<Bls12_381>::new(64, &mut thread_rng()));
+}
+
+/// Random polynomials with their commitments and evals at `points`
+#[allow(clippy::type_complexity)]
+fn random_polys<C: Committer<Bls12_381>>(
+    s: &C,
+    n_polys: usize,
+    points: &[Fr],
+) -> (Vec<Vec<Fr>>, Vec<Commitment<Bls12_381>>, Vec<Vec<Fr>>) {
+    let polys = (0..n_polys)
+        .map(|_| DensePolynomial::<Fr>::rand(30, &mut thread_rng()))
+        .collect::<Vec<_>>();
+    let evals = polys
+        .iter()
+        .map(|p| points.iter().map(|x| p.evaluate(x)).collect())
+        .collect();
+    let coeffs = polys.into_iter().map(|p| p.coeffs).collect::<Vec<_>>();
+    let commits = coeffs.iter().map(|p| s.commit(p).unwrap()).collect();
+    (coeffs, commits, evals)
+}
+
+/// Runs a verification that should succeed and returns the operations it did
+fn count_verify(verify: impl FnOnce() -> Result<bool, Error>) -> OpCounts {
+    reset_op_counts();
+    assert_eq!(Ok(true), verify());
+    op_counts()
+}
+
+/// Checks the counts against `cost`. `extra_bases` are MSM bases the estimate leaves out, and
+/// `scalar_muls` are single scalar multiplications the estimate counts as one base each, which
+/// aren't MSMs so aren't counted.
+fn assert_cost(cost: VerifyCost, counts: OpCounts, extra_bases: usize, scalar_muls: usize) {
+    assert_eq!(cost.pairings as u64, counts.pairings);
+    assert_eq!(
+        (cost.g1_msm_size + cost.g2_msm_size + extra_bases) as u64,
+        counts.msm_bases + scalar_muls as u64
+    );
+}
+
+#[test]
+fn test_verify_cost_matches_counts() {
+    let _counters = lock_counters();
+    let (n_points, n_polys) = (6, 5);
+    let points = (0..n_points)
+        .map(|_| Fr::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+
+    let m1 = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::new(64, 8, &mut thread_rng());
+    let m1_cost = M1NoPrecomp::<Bls12_381, BlstMSMEngine>::verify_cost(n_points, n_polys);
+    let (coeffs, commits, evals) = random_polys(&m1, n_polys, &points);
+    let open = m1
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .unwrap();
+    // Without precomputation there's also the G2 msm committing to the vanishing polynomial
+    let counts = count_verify(|| {
+        m1.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open,
+        )
+    });
+    assert_cost(m1_cost, counts, n_points + 1, 0);
+    let m1_pc = M1Precomp::from_inner(m1, vec![points.clone()]).unwrap();
+    let counts =
+        count_verify(|| m1_pc.verify(&mut Transcript::new(b"testing"), &commits, 0, &evals, &open));
+    assert_cost(m1_cost, counts, 0, 0);
+
+    let m2 = M2NoPrecomp::<Bls12_381>::new(64, &mut thread_rng());
+    let m2_cost = M2NoPrecomp::<Bls12_381>::verify_cost(n_points, n_polys);
+    let (coeffs, commits, evals) = random_polys(&m2, n_polys, &points);
+    let open = m2
+        .open(&mut Transcript::new(b"testing"), &evals, &coeffs, &points)
+        .unwrap();
+    let counts = count_verify(|| {
+        m2.verify(
+            &mut Transcript::new(b"testing"),
+            &commits,
+            &points,
+            &evals,
+            &open,
+        )
+    });
+    // [r(z)]_1, z(z) W_1 and [x - z]_2 are scalar multiplications
+    assert_cost(m2_cost, counts, 0, 3);
+    let m2_pc = M2Precomp::from_inner(m2, vec![points]).unwrap();
+    let counts =
+        count_verify(|| m2_pc.verify(&mut Transcript::new(b"testing"), &commits, 0, &evals, &open));
+    assert_cost(m2_cost, counts, 0, 3);
+}